@@ -0,0 +1,35 @@
+use advent_of_code_2019::int_code::Program;
+use std::fs::read_to_string;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 20;
+
+// The quickest of a few runs, to keep scheduling noise out of timings.
+fn fastest<T>(mut f: impl FnMut() -> T) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+// Compares loading a program from the binary format with parsing its text,
+// on the day five program repeated to a size worth timing.
+fn main() {
+    let text = read_to_string("src/five.txt").unwrap();
+    let text = vec![text.trim(); 200].join(",");
+    let bytes = text.parse::<Program>().unwrap().to_binary();
+
+    let parsing = fastest(|| text.parse::<Program>().unwrap());
+    let loading = fastest(|| Program::from_binary(&bytes).unwrap());
+
+    println!("parsing {} bytes of text: {:?}", text.len(), parsing);
+    println!("loading {} bytes of binary: {:?}", bytes.len(), loading);
+    println!(
+        "loading is {:.1}x faster",
+        parsing.as_secs_f64() / loading.as_secs_f64()
+    );
+}
@@ -0,0 +1,225 @@
+use super::int_code::*;
+use super::read_input;
+use std::collections::{HashMap, HashSet};
+use std::iter;
+
+const BLACK: i64 = 0;
+const WHITE: i64 = 1;
+
+pub fn main() {
+    let input = match read_input("src/eleven.txt") {
+        Some(input) => input,
+        None => return,
+    };
+
+    let brain = input.parse::<Program>().unwrap();
+
+    println!("Part one:");
+    println!(
+        "Panels painted at least once: {}",
+        paint_hull(&brain, BLACK).painted()
+    );
+
+    println!();
+    println!("Part two:");
+    println!("{}", paint_hull(&brain, WHITE).render());
+}
+
+type Point = (i64, i64);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    fn turn(self, output: i64) -> Direction {
+        match (self, output) {
+            (Direction::Up, 0) => Direction::Left,
+            (Direction::Left, 0) => Direction::Down,
+            (Direction::Down, 0) => Direction::Right,
+            (Direction::Right, 0) => Direction::Up,
+            (Direction::Up, _) => Direction::Right,
+            (Direction::Right, _) => Direction::Down,
+            (Direction::Down, _) => Direction::Left,
+            (Direction::Left, _) => Direction::Up,
+        }
+    }
+
+    // Y grows downwards, so the hull renders top to bottom.
+    fn step(self, (x, y): Point) -> Point {
+        match self {
+            Direction::Up => (x, y - 1),
+            Direction::Right => (x + 1, y),
+            Direction::Down => (x, y + 1),
+            Direction::Left => (x - 1, y),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Hull {
+    panels: HashMap<Point, i64>,
+    painted: HashSet<Point>,
+}
+
+impl Hull {
+    fn colour(&self, point: Point) -> i64 {
+        self.panels.get(&point).copied().unwrap_or(BLACK)
+    }
+
+    fn paint(&mut self, point: Point, colour: i64) {
+        self.panels.insert(point, colour);
+        self.painted.insert(point);
+    }
+
+    fn painted(&self) -> usize {
+        self.painted.len()
+    }
+
+    fn render(&self) -> String {
+        let white = self
+            .panels
+            .iter()
+            .filter(|(_, &colour)| colour == WHITE)
+            .map(|(&point, _)| point)
+            .collect::<Vec<_>>();
+
+        if white.is_empty() {
+            return String::new();
+        }
+
+        let min_x = white.iter().map(|p| p.0).min().unwrap();
+        let max_x = white.iter().map(|p| p.0).max().unwrap();
+        let min_y = white.iter().map(|p| p.1).min().unwrap();
+        let max_y = white.iter().map(|p| p.1).max().unwrap();
+
+        (min_y..=max_y)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| match self.colour((x, y)) {
+                        WHITE => '#',
+                        _ => ' ',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// The brain is told the colour under the robot and answers with a colour to
+// paint it and which way to turn, 0 for left and 1 for right.
+struct Robot {
+    brain: Program,
+    hull: Hull,
+    position: Point,
+    direction: Direction,
+}
+
+impl Robot {
+    fn new(brain: &Program, starting_panel: i64) -> Robot {
+        let mut hull = Hull::default();
+        hull.panels.insert((0, 0), starting_panel);
+
+        Robot {
+            brain: brain.clone(),
+            hull,
+            position: (0, 0),
+            direction: Direction::Up,
+        }
+    }
+
+    // Paints one panel and moves on, or returns false once the brain halts.
+    fn tick(&mut self) -> bool {
+        let mut camera = iter::once(self.hull.colour(self.position));
+
+        let colour = match self.brain.run_until_next_output(&mut camera) {
+            Some(colour) => colour,
+            None => return false,
+        };
+
+        let turn = self
+            .brain
+            .run_until_next_output(&mut camera)
+            .expect("Robot halted between painting and turning");
+
+        self.hull.paint(self.position, colour);
+        self.direction = self.direction.turn(turn);
+        self.position = self.direction.step(self.position);
+
+        true
+    }
+}
+
+fn paint_hull(brain: &Program, starting_panel: i64) -> Hull {
+    let mut robot = Robot::new(brain, starting_panel);
+
+    while robot.tick() {}
+
+    robot.hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads the camera into a cell past the end of the program and then
+    // answers with each pair of outputs in turn.
+    fn scripted_brain(outputs: &[(i64, i64)]) -> Program {
+        let mut code = Vec::new();
+
+        for &(colour, turn) in outputs {
+            code.extend_from_slice(&[3, 1000, 104, colour, 104, turn]);
+        }
+
+        code.push(99);
+
+        Program::new(code)
+    }
+
+    fn example_brain() -> Program {
+        scripted_brain(&[(1, 0), (0, 0), (1, 0), (1, 0), (0, 1), (1, 0), (1, 0)])
+    }
+
+    #[test]
+    fn counts_panels_painted_at_least_once() {
+        assert_eq!(paint_hull(&example_brain(), BLACK).painted(), 6);
+    }
+
+    #[test]
+    fn renders_the_white_panels() {
+        assert_eq!(
+            paint_hull(&example_brain(), BLACK).render(),
+            "  #\n  #\n## "
+        );
+    }
+
+    #[test]
+    fn the_starting_panel_is_only_painted_once_the_robot_paints_it() {
+        let hull = paint_hull(&scripted_brain(&[]), WHITE);
+
+        assert_eq!(hull.painted(), 0);
+        assert_eq!(hull.render(), "#");
+    }
+
+    #[test]
+    fn feeds_the_colour_under_the_robot_to_the_brain() {
+        // Paints each panel the colour it already is, then turns right. It
+        // never halts, so stop once the robot is back where it started.
+        let echo = "3,100,4,100,104,1,1105,1,0".parse::<Program>().unwrap();
+        let mut robot = Robot::new(&echo, WHITE);
+
+        for _ in 0..4 {
+            assert!(robot.tick());
+        }
+
+        assert_eq!(robot.position, (0, 0));
+        assert_eq!(robot.hull.colour((0, 0)), WHITE);
+        assert_eq!(robot.hull.colour((1, 0)), BLACK);
+        assert_eq!(robot.hull.painted(), 4);
+    }
+}
@@ -0,0 +1,241 @@
+use super::int_code::*;
+use super::read_input;
+use std::collections::{HashMap, VecDeque};
+use std::iter;
+
+pub fn main() {
+    let input = match read_input("src/fifteen.txt") {
+        Some(input) => input,
+        None => return,
+    };
+
+    let droid = input.parse::<Program>().unwrap();
+    let area = explore(&droid);
+    let oxygen = area
+        .oxygen
+        .expect("The droid never found the oxygen system");
+
+    println!("{}", area.render());
+
+    println!("Part one:");
+    println!(
+        "Fewest moves to the oxygen system: {}",
+        area.distances[&oxygen]
+    );
+
+    println!();
+    println!("Part two:");
+    println!("Minutes to fill with oxygen: {}", area.fill_time(oxygen));
+}
+
+type Point = (i64, i64);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Cell {
+    Wall,
+    Open,
+    OxygenSystem,
+}
+
+// Move commands in the order the droid numbers them.
+const MOVES: [(i64, Point); 4] = [(1, (0, -1)), (2, (0, 1)), (3, (-1, 0)), (4, (1, 0))];
+
+fn step((x, y): Point, (dx, dy): Point) -> Point {
+    (x + dx, y + dy)
+}
+
+#[derive(Debug)]
+struct Area {
+    cells: HashMap<Point, Cell>,
+    // Fewest moves from the droid's starting point to each open cell.
+    distances: HashMap<Point, usize>,
+    oxygen: Option<Point>,
+}
+
+// Breadth-first search where every frontier cell keeps its own copy of the
+// droid, paused right after it arrived there. Trying a move clones that
+// copy, so the droid never has to walk back.
+fn explore(droid: &Program) -> Area {
+    let mut area = Area {
+        cells: HashMap::new(),
+        distances: HashMap::new(),
+        oxygen: None,
+    };
+
+    let mut frontier = VecDeque::new();
+
+    area.cells.insert((0, 0), Cell::Open);
+    area.distances.insert((0, 0), 0);
+    frontier.push_back(((0, 0), droid.clone()));
+
+    while let Some((position, droid)) = frontier.pop_front() {
+        let distance = area.distances[&position];
+
+        for &(command, delta) in &MOVES {
+            let next = step(position, delta);
+
+            if area.cells.contains_key(&next) {
+                continue;
+            }
+
+            let mut moved = droid.clone();
+            let status = moved
+                .run_until_next_output(&mut iter::once(command))
+                .expect("The droid halted");
+
+            let cell = match status {
+                0 => Cell::Wall,
+                1 => Cell::Open,
+                2 => Cell::OxygenSystem,
+                _ => panic!("Unexpected status: {}", status),
+            };
+
+            area.cells.insert(next, cell);
+
+            if cell == Cell::Wall {
+                continue;
+            }
+
+            if cell == Cell::OxygenSystem {
+                area.oxygen = Some(next);
+            }
+
+            area.distances.insert(next, distance + 1);
+            frontier.push_back((next, moved));
+        }
+    }
+
+    area
+}
+
+impl Area {
+    // Oxygen spreads to every neighbouring open cell each minute.
+    fn fill_time(&self, from: Point) -> usize {
+        let mut minutes = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        minutes.insert(from, 0);
+        queue.push_back(from);
+
+        while let Some(position) = queue.pop_front() {
+            for &(_, delta) in &MOVES {
+                let next = step(position, delta);
+
+                let open = match self.cells.get(&next) {
+                    Some(Cell::Wall) | None => false,
+                    Some(_) => true,
+                };
+
+                if open && !minutes.contains_key(&next) {
+                    minutes.insert(next, minutes[&position] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        minutes.values().copied().max().unwrap_or(0)
+    }
+
+    fn render(&self) -> String {
+        let min_x = self.cells.keys().map(|p| p.0).min().unwrap_or(0);
+        let max_x = self.cells.keys().map(|p| p.0).max().unwrap_or(0);
+        let min_y = self.cells.keys().map(|p| p.1).min().unwrap_or(0);
+        let max_y = self.cells.keys().map(|p| p.1).max().unwrap_or(0);
+
+        let mut out = String::new();
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                out.push(match self.cells.get(&(x, y)) {
+                    _ if (x, y) == (0, 0) => 'D',
+                    Some(Cell::Wall) => '#',
+                    Some(Cell::Open) => '.',
+                    Some(Cell::OxygenSystem) => 'O',
+                    None => ' ',
+                });
+            }
+
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds a droid for a maze drawn with `#` walls, `.` open cells, `D`
+    // for the start and `O` for the oxygen system. It keeps its position as
+    // an index into a copy of the maze stored after the code, and patches
+    // its own parameters to look up cells and move deltas.
+    fn maze_droid(maze: &str) -> Program {
+        let rows = maze.lines().collect::<Vec<_>>();
+        let width = rows[0].len() as i64;
+
+        let grid = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|c| match c {
+                '#' => 0,
+                'O' => 2,
+                _ => 1,
+            })
+            .collect::<Vec<_>>();
+
+        let start = rows.concat().find('D').unwrap() as i64;
+
+        let (cmd, pos, target, tile, deltas, cells) = (31, 32, 33, 34, 35, 39);
+
+        #[rustfmt::skip]
+        let mut code = vec![
+            3, cmd,                    // in [cmd]
+            1001, cmd, deltas - 1, 8,  // point the next add at the delta
+            1, pos, 0, target,         // add [pos], [delta], [target]
+            1001, target, cells, 15,   // point the next add at the cell
+            1001, 0, 0, tile,          // add [cell], 0, [tile]
+            4, tile,                   // out [tile]
+            1006, tile, 0,             // jz [tile], 0
+            1001, target, 0, pos,      // add [target], 0, [pos]
+            1105, 1, 0,                // jnz 1, 0
+            99,
+            0, start, 0, 0,            // cmd, pos, target, tile
+            -width, width, -1, 1,      // north, south, west, east
+        ];
+
+        code.extend(grid);
+
+        Program::new(code)
+    }
+
+    const MAZE: &str = "\
+######
+#D..##
+#.#.O#
+#...##
+######";
+
+    #[test]
+    fn finds_the_shortest_path_to_the_oxygen_system() {
+        let area = explore(&maze_droid(MAZE));
+
+        assert_eq!(area.oxygen, Some((3, 1)));
+        assert_eq!(area.distances[&(3, 1)], 4);
+    }
+
+    #[test]
+    fn times_how_long_oxygen_takes_to_fill_the_area() {
+        let area = explore(&maze_droid(MAZE));
+
+        assert_eq!(area.fill_time(area.oxygen.unwrap()), 5);
+    }
+
+    #[test]
+    fn renders_everything_the_droid_found() {
+        assert_eq!(
+            explore(&maze_droid(MAZE)).render(),
+            [" ###  ", "#D..# ", "#.#.O#", "#...# ", " ###  ", ""].join("\n")
+        );
+    }
+}
@@ -1,12 +1,13 @@
 use super::int_code::*;
+use std::fmt;
 use std::fs::read_to_string;
 
 pub fn main() {
     let input = read_to_string("src/five.txt").unwrap();
-    let mut program = input.trim().parse::<Program>().unwrap();
+    let mut program = input.parse::<Program>().unwrap();
 
     println!("Part one:");
-    println!("{}", part_one(&mut (program.clone())));
+    println!("{}", part_one(&program));
     println!();
 
     println!("Part two:");
@@ -14,10 +15,17 @@ pub fn main() {
     println!();
 }
 
-fn part_one(program: &mut Program) -> i64 {
-    let output = program.run(vec![1]);
+// Shows which checks failed, if any did, before the diagnostic code.
+fn part_one(program: &Program) -> i64 {
+    let report = Report::run(program, 1);
 
-    output[output.len() - 1]
+    if !report.passed() {
+        print!("{}", report);
+    }
+
+    report
+        .diagnostic_code()
+        .expect("The program halted without a diagnostic code")
 }
 
 fn part_two(program: &mut Program) -> i64 {
@@ -25,3 +33,297 @@ fn part_two(program: &mut Program) -> i64 {
 
     output[0]
 }
+
+// An instruction as it ran, with the cells it read and wrote resolved
+// against the relative base at the time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Executed {
+    decoded: Decoded,
+    op_code: i64,
+    reads: Vec<usize>,
+    write: Option<usize>,
+}
+
+impl Executed {
+    fn next(program: &Program) -> Executed {
+        let decoded = program.current_instruction().unwrap();
+        let write_param = decoded.instruction.write_param();
+
+        let resolve = |operand: &Operand| match operand.mode {
+            Mode::Position => Some(operand.value as usize),
+            Mode::Relative => Some((program.relative_base() + operand.value) as usize),
+            Mode::Immediate => None,
+        };
+
+        let reads = decoded
+            .operands
+            .iter()
+            .enumerate()
+            .filter(|&(param, _)| Some(param) != write_param)
+            .filter_map(|(_, operand)| resolve(operand))
+            .collect();
+
+        let write = write_param.and_then(|param| resolve(&decoded.operands[param]));
+
+        Executed {
+            op_code: program.code[decoded.address],
+            decoded,
+            reads,
+            write,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let modes = self
+            .decoded
+            .operands
+            .iter()
+            .map(|operand| format!("{:?}", operand.mode).to_lowercase())
+            .collect::<Vec<_>>();
+
+        format!(
+            "opcode {}: {} with {} parameters",
+            self.op_code,
+            self.decoded.instruction.mnemonic(),
+            modes.join(", ")
+        )
+    }
+}
+
+// One output, along with everything executed since the one before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Check {
+    output: i64,
+    executed: Vec<Executed>,
+}
+
+impl Check {
+    fn address(&self) -> usize {
+        self.executed.last().unwrap().decoded.address
+    }
+
+    fn passed(&self) -> bool {
+        self.output == 0
+    }
+
+    // Each check works out a result with the instruction under test,
+    // compares it with the expected value into a cell, and outputs that
+    // cell. So the culprit is whatever last wrote a cell read by the
+    // comparison, or the comparison itself if nothing did.
+    fn culprit(&self) -> &Executed {
+        let (output, before) = self.executed.split_last().unwrap();
+
+        let comparison = match output.reads.first() {
+            Some(&cell) => before.iter().rposition(|e| e.write == Some(cell)),
+            None => None,
+        };
+
+        match comparison {
+            Some(index) => before[..index]
+                .iter()
+                .rev()
+                .find(|e| {
+                    e.write
+                        .is_some_and(|cell| before[index].reads.contains(&cell))
+                })
+                .unwrap_or(&before[index]),
+            None => output,
+        }
+    }
+}
+
+// The TEST program outputs a zero for every check that passes, then the
+// diagnostic code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Report {
+    outputs: Vec<Check>,
+}
+
+impl Report {
+    fn run(program: &Program, system_id: i64) -> Report {
+        let mut program = program.clone();
+        let mut inputs = vec![system_id].into_iter();
+        let mut outputs = Vec::new();
+        let mut executed = Vec::new();
+
+        loop {
+            let next = Executed::next(&program);
+
+            match program.step(&mut inputs) {
+                Step::Executed => executed.push(next),
+                Step::Output(output) => {
+                    executed.push(next);
+                    outputs.push(Check {
+                        output,
+                        executed: std::mem::take(&mut executed),
+                    });
+                }
+                Step::AwaitingInput => panic!("No input given"),
+                Step::Halted => return Report { outputs },
+            }
+        }
+    }
+
+    fn checks(&self) -> &[Check] {
+        self.outputs.split_last().map_or(&[], |(_, checks)| checks)
+    }
+
+    fn passed(&self) -> bool {
+        self.failed().next().is_none()
+    }
+
+    fn failed(&self) -> impl Iterator<Item = &Check> {
+        self.checks().iter().filter(|check| !check.passed())
+    }
+
+    fn diagnostic_code(&self) -> Option<i64> {
+        self.outputs.last().map(|last| last.output)
+    }
+}
+
+// Failed checks list what ran since the previous output, marking the
+// instruction under test.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, check) in self.checks().iter().enumerate() {
+            if check.passed() {
+                writeln!(f, "Check {} at {:04}: passed", n + 1, check.address())?;
+                continue;
+            }
+
+            // By identity, since a loop can run the same instruction with the
+            // same operands more than once.
+            let culprit = check.culprit();
+
+            writeln!(
+                f,
+                "Check {} at {:04}: FAILED with {}",
+                n + 1,
+                check.address(),
+                check.output
+            )?;
+
+            for executed in &check.executed {
+                let line = format!("{:04}  {}", executed.decoded.address, executed.decoded);
+
+                if std::ptr::eq(executed, culprit) {
+                    writeln!(f, "  > {:<28} <- {}", line, executed.describe())?;
+                } else {
+                    writeln!(f, "    {}", line)?;
+                }
+            }
+        }
+
+        match self.outputs.last() {
+            Some(last) => writeln!(
+                f,
+                "Diagnostic code at {:04}: {}",
+                last.address(),
+                last.output
+            ),
+            None => writeln!(f, "Halted without a diagnostic code"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic_program() -> Program {
+        read_to_string("src/five.txt")
+            .unwrap()
+            .parse::<Program>()
+            .unwrap()
+    }
+
+    #[test]
+    fn every_check_passes_for_the_air_conditioner() {
+        let report = Report::run(&diagnostic_program(), 1);
+
+        assert!(report.passed());
+        assert_eq!(report.checks().len(), 9);
+        assert_eq!(report.checks()[1].address(), 28);
+        assert_eq!(
+            report.diagnostic_code(),
+            Some(part_one(&diagnostic_program()))
+        );
+    }
+
+    #[test]
+    fn points_at_the_instruction_under_test_when_a_check_fails() {
+        // Expect the multiplication in the third check to give one more.
+        let mut program = diagnostic_program();
+        program.code[47] = -1145;
+
+        let report = Report::run(&program, 1);
+        let failed = report.failed().collect::<Vec<_>>();
+
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].address(), 50);
+        assert_eq!(failed[0].output, -1);
+        assert_eq!(failed[0].culprit().decoded.address, 42);
+        assert_eq!(
+            failed[0].culprit().describe(),
+            "opcode 102: mul with immediate, position, position parameters"
+        );
+        assert!(report.to_string().contains(
+            "Check 3 at 0050: FAILED with -1\n    \
+             0030  mul [223], 8, [223]\n"
+        ));
+        assert!(report.to_string().contains(
+            "  > 0042  mul 52, [148], [224]   <- opcode 102: mul with immediate, \
+             position, position parameters\n    0046  add -1145, [224], [224]\n"
+        ));
+    }
+
+    #[test]
+    fn blames_the_output_itself_when_it_outputs_a_constant() {
+        // in [0]; out 7; out 0; halt
+        let program = Program::new(vec![3, 0, 104, 7, 104, 0, 99]);
+
+        let report = Report::run(&program, 1);
+        let failed = report.failed().next().unwrap();
+
+        assert_eq!(failed.executed.len(), 2);
+        assert_eq!(failed.culprit().decoded.address, 2);
+        assert_eq!(report.diagnostic_code(), Some(0));
+    }
+
+    #[test]
+    fn marks_only_the_last_run_of_a_repeated_instruction() {
+        #[rustfmt::skip]
+        let program = Program::new(vec![
+            1101, 0, 0, 30,     // add 0, 0, [30]
+            1001, 31, 1, 31,    // add [31], 1, [31]
+            1008, 31, 2, 32,    // eq [31], 2, [32]
+            1006, 32, 0,        // jz [32], 0
+            1008, 30, 0, 33,    // eq [30], 0, [33]
+            4, 33,              // out [33]
+            104, 0,             // out 0
+            99, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]);
+
+        let report = Report::run(&program, 1);
+        let text = report.to_string();
+        let marked = text
+            .lines()
+            .filter(|line| line.starts_with("  > "))
+            .collect::<Vec<_>>();
+
+        assert_eq!(report.failed().count(), 1);
+        assert_eq!(text.matches("0000  add 0, 0, [30]").count(), 2);
+        assert_eq!(marked.len(), 1);
+        assert!(text.contains("    0012  jz [32], 0\n  > 0000  add 0, 0, [30]"));
+    }
+
+    #[test]
+    fn reports_a_program_that_outputs_nothing() {
+        let report = Report::run(&Program::new(vec![99]), 1);
+
+        assert!(report.passed());
+        assert!(report.checks().is_empty());
+        assert_eq!(report.diagnostic_code(), None);
+        assert_eq!(report.to_string(), "Halted without a diagnostic code\n");
+    }
+}
@@ -1,24 +1,68 @@
-use std::num::ParseIntError;
+mod binary;
+mod check;
+mod compile;
+mod coverage;
+mod decompile;
+mod devices;
+mod diff;
+mod disassemble;
+mod link;
+mod memory;
+mod network;
+mod optimize;
+mod parser;
+mod routines;
+mod session;
+mod spec;
+mod specialize;
+mod symbols;
+mod transpile;
+
+pub use self::binary::DecodeError;
+pub use self::check::{check, has_errors, Diagnostic, DiagnosticKind, Severity};
+pub use self::compile::{compile, CompileError, CompileErrorKind};
+pub use self::coverage::{BranchCounts, Coverage};
+pub use self::decompile::{decompile, Decompiler};
+use self::devices::Mapping;
+pub use self::devices::{Console, Device, Framebuffer, MapError, MapErrorKind, Random, Timer};
+pub use self::diff::{assert_memory_eq, Change, MemoryDiff};
+pub use self::disassemble::{disassemble, listing, listing_with_symbols, Decoded, Line, Operand};
+pub use self::link::{link, Fragment, LinkError, LinkErrorKind, Linked, Relocation, Target};
+pub use self::memory::Memory;
+pub use self::network::{MachineId, Network, Outcome, Status};
+pub use self::optimize::{optimize, verify, Mismatch, Optimized, Rewrite};
+pub use self::parser::{ParseError, ParseErrorKind};
+pub use self::routines::library;
+pub use self::session::{
+    replay, Divergence, Event, EventKind, LogError, LogErrorKind, Recorder, Recording,
+};
+pub use self::spec::{Case, CaseReport, Failure, Spec, SpecError, SpecErrorKind, SpecProgram};
+pub use self::specialize::{specialize, Specialized, Stop};
+pub use self::symbols::{Symbol, SymbolError, SymbolErrorKind, Symbols, UnknownSymbol};
+pub use self::transpile::{run_compiled, transpile, Compiled};
+use std::rc::Rc;
 use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Mode {
+pub enum Mode {
     Position,
     Immediate,
+    Relative,
 }
 
 impl Mode {
-    fn from_i64(int: i64) -> Mode {
-        if int == 0 {
-            Mode::Position
-        } else {
-            Mode::Immediate
+    fn from_i64(int: i64) -> Option<Mode> {
+        match int {
+            0 => Some(Mode::Position),
+            1 => Some(Mode::Immediate),
+            2 => Some(Mode::Relative),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Instruction {
+pub enum Instruction {
     Add([Mode; 3]),
     Multiply([Mode; 3]),
     ReadInput(Mode),
@@ -27,51 +71,229 @@ enum Instruction {
     JumpIfFalse([Mode; 2]),
     LessThan([Mode; 3]),
     Equals([Mode; 3]),
+    AdjustRelativeBase(Mode),
+    Halt,
 }
 
 impl Instruction {
-    fn from_i64(op_code: i64) -> Instruction {
-        let code = op_code % 100;
-        let mode_1 = Mode::from_i64((op_code / 100) % 10);
-        let mode_2 = Mode::from_i64((op_code / 1000) % 10);
-        let mode_3 = Mode::from_i64((op_code / 10000) % 10);
-
-        match code {
-            1 => Instruction::Add([mode_1, mode_2, mode_3]),
-            2 => Instruction::Multiply([mode_1, mode_2, mode_3]),
-            3 => Instruction::ReadInput(mode_1),
-            4 => Instruction::WriteOutput(mode_1),
-            5 => Instruction::JumpIfTrue([mode_1, mode_2]),
-            6 => Instruction::JumpIfFalse([mode_1, mode_2]),
-            7 => Instruction::LessThan([mode_1, mode_2, mode_3]),
-            8 => Instruction::Equals([mode_1, mode_2, mode_3]),
-            _ => panic!("Unexpected opcode: {}", op_code),
+    pub fn from_i64(op_code: i64) -> Instruction {
+        Instruction::decode(op_code).unwrap_or_else(|| panic!("Unexpected opcode: {}", op_code))
+    }
+
+    // Fails on an unknown opcode, or on a mode digit other than 0, 1 or 2
+    // for any parameter the instruction has.
+    pub fn decode(op_code: i64) -> Option<Instruction> {
+        let mode = |param: u32| Mode::from_i64((op_code / 10_i64.pow(param + 2)) % 10);
+
+        Some(match op_code % 100 {
+            1 => Instruction::Add([mode(0)?, mode(1)?, mode(2)?]),
+            2 => Instruction::Multiply([mode(0)?, mode(1)?, mode(2)?]),
+            3 => Instruction::ReadInput(mode(0)?),
+            4 => Instruction::WriteOutput(mode(0)?),
+            5 => Instruction::JumpIfTrue([mode(0)?, mode(1)?]),
+            6 => Instruction::JumpIfFalse([mode(0)?, mode(1)?]),
+            7 => Instruction::LessThan([mode(0)?, mode(1)?, mode(2)?]),
+            8 => Instruction::Equals([mode(0)?, mode(1)?, mode(2)?]),
+            9 => Instruction::AdjustRelativeBase(mode(0)?),
+            99 => Instruction::Halt,
+            _ => return None,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.modes().len() + 1
+    }
+
+    pub fn modes(&self) -> &[Mode] {
+        match self {
+            Instruction::Add(modes) => modes,
+            Instruction::Multiply(modes) => modes,
+            Instruction::ReadInput(mode) => std::slice::from_ref(mode),
+            Instruction::WriteOutput(mode) => std::slice::from_ref(mode),
+            Instruction::JumpIfTrue(modes) => modes,
+            Instruction::JumpIfFalse(modes) => modes,
+            Instruction::LessThan(modes) => modes,
+            Instruction::Equals(modes) => modes,
+            Instruction::AdjustRelativeBase(mode) => std::slice::from_ref(mode),
+            Instruction::Halt => &[],
+        }
+    }
+
+    // The index of the parameter this instruction stores its result through.
+    pub fn write_param(&self) -> Option<usize> {
+        match self {
+            Instruction::Add(_) => Some(2),
+            Instruction::Multiply(_) => Some(2),
+            Instruction::ReadInput(_) => Some(0),
+            Instruction::LessThan(_) => Some(2),
+            Instruction::Equals(_) => Some(2),
+            _ => None,
         }
     }
 
-    fn width(&self) -> usize {
+    pub fn mnemonic(&self) -> &'static str {
         match self {
-            Instruction::Add(_) => 4,
-            Instruction::Multiply(_) => 4,
-            Instruction::ReadInput(_) => 2,
-            Instruction::WriteOutput(_) => 2,
-            Instruction::JumpIfTrue(_) => 3,
-            Instruction::JumpIfFalse(_) => 3,
-            Instruction::LessThan(_) => 4,
-            Instruction::Equals(_) => 4,
+            Instruction::Add(_) => "add",
+            Instruction::Multiply(_) => "mul",
+            Instruction::ReadInput(_) => "in",
+            Instruction::WriteOutput(_) => "out",
+            Instruction::JumpIfTrue(_) => "jnz",
+            Instruction::JumpIfFalse(_) => "jz",
+            Instruction::LessThan(_) => "lt",
+            Instruction::Equals(_) => "eq",
+            Instruction::AdjustRelativeBase(_) => "arb",
+            Instruction::Halt => "halt",
         }
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Step {
+    Executed,
+    Output(i64),
+    // The program wants input that isn't available yet. Nothing has been
+    // changed, so stepping again once there is input carries on as normal.
+    AwaitingInput,
+    Halted,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Program {
-    pub code: Vec<i64>,
+    pub code: Memory,
     i: usize,
+    relative_base: i64,
+    // Where `reset` goes back to, which is only past the start for
+    // specialized programs.
+    entry: usize,
+    entry_relative_base: i64,
+    devices: Vec<Mapping>,
+    symbols: Rc<Symbols>,
 }
 
 impl Program {
     pub fn new(code: Vec<i64>) -> Program {
-        Program { code, i: 0 }
+        Program {
+            code: Memory::new(code),
+            i: 0,
+            relative_base: 0,
+            entry: 0,
+            entry_relative_base: 0,
+            devices: Vec::new(),
+            symbols: Rc::new(Symbols::new()),
+        }
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Program, DecodeError> {
+        binary::decode(bytes).map(Program::new)
+    }
+
+    pub fn to_binary(&self) -> Vec<u8> {
+        binary::encode(&self.code.to_vec())
+    }
+
+    pub fn disassemble(&self) -> String {
+        listing_with_symbols(&self.code.to_vec(), &self.symbols)
+    }
+
+    pub fn decompile(&self) -> String {
+        decompile(&self.code.to_vec())
+    }
+
+    pub fn check(&self) -> Vec<Diagnostic> {
+        check(&self.code.to_vec())
+    }
+
+    pub fn specialize(&self, known_inputs: &[i64]) -> Specialized {
+        specialize(self, known_inputs)
+    }
+
+    pub fn memory_diff(&self) -> MemoryDiff<'_> {
+        MemoryDiff::from_owned(self.code.base(), self.code.to_vec()).with_symbols(&self.symbols)
+    }
+
+    pub fn symbols(&self) -> &Symbols {
+        &self.symbols
+    }
+
+    pub fn set_symbols(&mut self, symbols: Symbols) {
+        self.symbols = Rc::new(symbols);
+    }
+
+    pub fn read_symbol(&self, name: &str) -> Result<i64, UnknownSymbol> {
+        Ok(self.code[self.symbol_address(name)?])
+    }
+
+    pub fn write_symbol(&mut self, name: &str, value: i64) -> Result<(), UnknownSymbol> {
+        let address = self.symbol_address(name)?;
+
+        self.code[address] = value;
+        Ok(())
+    }
+
+    fn symbol_address(&self, name: &str) -> Result<usize, UnknownSymbol> {
+        self.symbols.address(name).ok_or_else(|| UnknownSymbol {
+            name: name.to_string(),
+        })
+    }
+
+    // Puts the program back how it was loaded, sharing nothing it wrote.
+    // Mapped devices stay mapped and keep their state.
+    pub fn reset(&mut self) {
+        self.code.reset();
+        self.i = self.entry;
+        self.relative_base = self.entry_relative_base;
+    }
+
+    // Sends parameter reads and writes for `start..start + len` to the
+    // device from now on. Fails if the range overlaps another device or is
+    // longer than the device.
+    pub fn map_device<D: Device>(
+        &mut self,
+        start: usize,
+        len: usize,
+        device: D,
+    ) -> Result<(), MapError> {
+        let error = |kind| MapError { kind, start, len };
+
+        if let Some(other) = self
+            .devices
+            .iter()
+            .find(|other| start < other.start + other.len && other.start < start + len)
+        {
+            return Err(error(MapErrorKind::Overlaps {
+                start: other.start,
+                len: other.len,
+            }));
+        }
+
+        if let Some(size) = device.size().filter(|&size| len > size) {
+            return Err(error(MapErrorKind::TooLong { size }));
+        }
+
+        self.devices.push(Mapping {
+            start,
+            len,
+            device: Box::new(device),
+        });
+
+        Ok(())
+    }
+
+    // The device mapped at `start`, if it's a `D`.
+    pub fn device<D: Device>(&self, start: usize) -> Option<&D> {
+        let mapping = self.devices.iter().find(|m| m.start == start)?;
+
+        devices::downcast(mapping.device.as_ref())
+    }
+
+    pub fn device_mut<D: Device>(&mut self, start: usize) -> Option<&mut D> {
+        let mapping = self.devices.iter_mut().find(|m| m.start == start)?;
+
+        devices::downcast_mut(mapping.device.as_mut())
+    }
+
+    pub fn has_devices(&self) -> bool {
+        !self.devices.is_empty()
     }
 
     pub fn run<I>(&mut self, inputs: I) -> Vec<i64>
@@ -92,104 +314,230 @@ impl Program {
     where
         I: Iterator<Item = i64>,
     {
-        while self.code[self.i] != 99 {
-            let instruction = Instruction::from_i64(self.code[self.i]);
-
-            match instruction {
-                Instruction::Add([mode_1, mode_2, mode_3]) => {
-                    let x = self.read(1, mode_1);
-                    let y = self.read(2, mode_2);
+        loop {
+            match self.step(inputs) {
+                Step::Executed => {}
+                Step::Output(output) => return Some(output),
+                Step::AwaitingInput => panic!("No input given"),
+                Step::Halted => return None,
+            }
+        }
+    }
 
-                    self.write(3, mode_3, x + y);
+    // Runs to the end, describing each instruction as it executes along
+    // with what it wrote or output, using the program's symbols.
+    pub fn trace<I>(&mut self, inputs: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        let mut inputs = inputs.into_iter();
+        let mut lines = Vec::new();
+
+        loop {
+            let decoded = self.current_instruction().unwrap_or_else(|| {
+                panic!(
+                    "Unexpected opcode: {} at {}",
+                    self.code[self.i],
+                    self.symbols.describe(self.i)
+                )
+            });
+
+            let target = decoded
+                .instruction
+                .write_param()
+                .map(|param| self.address_of(param + 1, decoded.operands[param].mode));
+
+            let mut line = format!("{:04}  {}", self.i, decoded.display_with(&self.symbols));
+
+            match self.step(&mut inputs) {
+                Step::Executed => {
+                    if let Some(target) = target {
+                        let name = self.symbols.name_of(target);
+                        let name = name.unwrap_or_else(|| format!("[{}]", target));
+
+                        line.push_str(&format!("  -> {} = {}", name, self.code[target]));
+                    }
+                }
+                Step::Output(output) => line.push_str(&format!("  -> out {}", output)),
+                Step::AwaitingInput => panic!("No input given"),
+                Step::Halted => {
+                    lines.push(line);
+                    return lines;
                 }
+            }
 
-                Instruction::Multiply([mode_1, mode_2, mode_3]) => {
-                    let x = self.read(1, mode_1);
-                    let y = self.read(2, mode_2);
+            lines.push(line);
+        }
+    }
 
-                    self.write(3, mode_3, x * y);
-                }
+    pub fn address(&self) -> usize {
+        self.i
+    }
 
-                Instruction::ReadInput(mode) => {
-                    let input = inputs.next().expect("No input given");
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
 
-                    self.write(1, mode, input);
-                }
+    pub fn current_instruction(&self) -> Option<Decoded> {
+        let cells = (self.i..self.i + 4)
+            .map_while(|address| self.code.get(address))
+            .collect::<Vec<_>>();
 
-                Instruction::WriteOutput(mode) => {
-                    let output = self.read(1, mode);
+        Decoded::at(&cells, 0).map(|decoded| Decoded {
+            address: self.i,
+            ..decoded
+        })
+    }
 
-                    self.i += instruction.width();
-                    return Some(output);
-                }
+    pub fn step<I>(&mut self, inputs: &mut I) -> Step
+    where
+        I: Iterator<Item = i64>,
+    {
+        let step = self.execute(inputs);
 
-                Instruction::JumpIfTrue([mode_1, mode_2]) => {
-                    if self.read(1, mode_1) != 0 {
-                        self.i = self.read(2, mode_2) as usize;
-                        continue;
-                    }
+        if let Step::Executed | Step::Output(_) = step {
+            for mapping in &mut self.devices {
+                mapping.device.tick();
+            }
+        }
+
+        step
+    }
+
+    fn execute<I>(&mut self, inputs: &mut I) -> Step
+    where
+        I: Iterator<Item = i64>,
+    {
+        let op_code = self.code[self.i];
+        let instruction = Instruction::decode(op_code).unwrap_or_else(|| {
+            panic!(
+                "Unexpected opcode: {} at {}",
+                op_code,
+                self.symbols.describe(self.i)
+            )
+        });
+
+        match instruction {
+            Instruction::Add([mode_1, mode_2, mode_3]) => {
+                let x = self.read(1, mode_1);
+                let y = self.read(2, mode_2);
+
+                self.write(3, mode_3, x + y);
+            }
+
+            Instruction::Multiply([mode_1, mode_2, mode_3]) => {
+                let x = self.read(1, mode_1);
+                let y = self.read(2, mode_2);
+
+                self.write(3, mode_3, x * y);
+            }
+
+            Instruction::ReadInput(mode) => {
+                let input = match inputs.next() {
+                    Some(input) => input,
+                    None => return Step::AwaitingInput,
+                };
+
+                self.write(1, mode, input);
+            }
+
+            Instruction::WriteOutput(mode) => {
+                let output = self.read(1, mode);
+
+                self.i += instruction.width();
+                return Step::Output(output);
+            }
+
+            Instruction::JumpIfTrue([mode_1, mode_2]) => {
+                if self.read(1, mode_1) != 0 {
+                    self.i = self.read(2, mode_2) as usize;
+                    return Step::Executed;
                 }
+            }
 
-                Instruction::JumpIfFalse([mode_1, mode_2]) => {
-                    if self.read(1, mode_1) == 0 {
-                        self.i = self.read(2, mode_2) as usize;
-                        continue;
-                    }
+            Instruction::JumpIfFalse([mode_1, mode_2]) => {
+                if self.read(1, mode_1) == 0 {
+                    self.i = self.read(2, mode_2) as usize;
+                    return Step::Executed;
                 }
+            }
 
-                Instruction::LessThan([mode_1, mode_2, mode_3]) => {
-                    let x = self.read(1, mode_1);
-                    let y = self.read(2, mode_2);
+            Instruction::LessThan([mode_1, mode_2, mode_3]) => {
+                let x = self.read(1, mode_1);
+                let y = self.read(2, mode_2);
 
-                    let out = if x < y { 1 } else { 0 };
+                let out = if x < y { 1 } else { 0 };
 
-                    self.write(3, mode_3, out);
-                }
+                self.write(3, mode_3, out);
+            }
 
-                Instruction::Equals([mode_1, mode_2, mode_3]) => {
-                    let x = self.read(1, mode_1);
-                    let y = self.read(2, mode_2);
+            Instruction::Equals([mode_1, mode_2, mode_3]) => {
+                let x = self.read(1, mode_1);
+                let y = self.read(2, mode_2);
 
-                    let out = if x == y { 1 } else { 0 };
+                let out = if x == y { 1 } else { 0 };
 
-                    self.write(3, mode_3, out);
-                }
+                self.write(3, mode_3, out);
+            }
+
+            Instruction::AdjustRelativeBase(mode) => {
+                self.relative_base += self.read(1, mode);
             }
 
-            self.i += instruction.width();
+            Instruction::Halt => return Step::Halted,
         }
 
-        None
+        self.i += instruction.width();
+
+        Step::Executed
     }
 
-    fn read(&self, offset: usize, mode: Mode) -> i64 {
-        let val = self.code[self.i + offset];
+    fn read(&mut self, offset: usize, mode: Mode) -> i64 {
+        let address = self.address_of(offset, mode);
 
-        match mode {
-            Mode::Position => self.code[val as usize],
-            Mode::Immediate => val,
+        match self.devices.iter_mut().find(|m| m.contains(address)) {
+            Some(mapping) => mapping.device.read(address - mapping.start),
+            None => self.code[address],
         }
     }
 
     fn write(&mut self, offset: usize, mode: Mode, value: i64) {
-        let write_addr = match mode {
-            Mode::Position => self.code[self.i + offset] as usize,
-            Mode::Immediate => self.i + offset,
+        let write_addr = self.address_of(offset, mode);
+
+        match self.devices.iter_mut().find(|m| m.contains(write_addr)) {
+            Some(mapping) => mapping.device.write(write_addr - mapping.start, value),
+            None => self.code[write_addr] = value,
+        }
+    }
+
+    // The cell a parameter refers to. Immediate parameters refer to their
+    // own cell, which is what writing through one has always done.
+    fn address_of(&self, offset: usize, mode: Mode) -> usize {
+        let param = self.code[self.i + offset];
+
+        let address = match mode {
+            Mode::Position => param,
+            Mode::Immediate => return self.i + offset,
+            Mode::Relative => self.relative_base + param,
         };
 
-        self.code[write_addr] = value;
+        if address < 0 {
+            panic!(
+                "Negative address {} at {}",
+                address,
+                self.symbols.describe(self.i)
+            );
+        }
+
+        address as usize
     }
 }
 
 impl FromStr for Program {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Program, Self::Err> {
-        input
-            .split(',')
-            .map(|s| s.parse::<i64>())
-            .collect::<Result<Vec<i64>, _>>()
-            .map(Program::new)
+        parser::parse(input).map(Program::new)
     }
 }
 
@@ -202,7 +550,7 @@ mod day_two_tests {
 
         program.run(vec![]);
 
-        assert_eq!(program.code, expected_code)
+        assert_memory_eq(&program.code.to_vec(), &expected_code)
     }
 
     #[test]
@@ -232,6 +580,19 @@ mod day_two_tests {
     fn example_test_case_4() {
         assert_program_output_is("1,1,1,4,99,5,6,0,99", vec![30, 1, 1, 4, 2, 5, 6, 0, 99])
     }
+
+    #[test]
+    fn reset_returns_to_the_loaded_program() {
+        let mut program = "1,9,10,3,2,3,11,0,99,30,40,50".parse::<Program>().unwrap();
+        let pristine = program.clone();
+
+        program.run(vec![]);
+        program.reset();
+
+        assert_eq!(program, pristine);
+        assert_eq!(program.address(), 0);
+        assert_eq!(program.code.dirty_pages(), 0);
+    }
 }
 
 #[cfg(test)]
@@ -270,7 +631,6 @@ mod day_five_part_one_tests {
     fn runs_diagnostic_program_correctly() {
         let mut program = read_to_string("src/five.txt")
             .unwrap()
-            .trim()
             .parse::<Program>()
             .unwrap();
 
@@ -328,3 +688,38 @@ mod day_five_part_two_tests {
         assert_eq!(program.clone().run(vec!(9)), vec!(1001));
     }
 }
+
+#[cfg(test)]
+mod day_nine_tests {
+    use super::*;
+
+    #[test]
+    fn outputs_a_copy_of_itself() {
+        let code = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let mut program = code.parse::<Program>().unwrap();
+
+        assert_eq!(program.run(vec![]), parser::parse(code).unwrap());
+    }
+
+    #[test]
+    fn supports_large_numbers() {
+        let mut program = "1102,34915192,34915192,7,4,7,99,0"
+            .parse::<Program>()
+            .unwrap();
+
+        assert_eq!(program.run(vec![]), vec![1219070632396864]);
+
+        let mut program = "104,1125899906842624,99".parse::<Program>().unwrap();
+
+        assert_eq!(program.run(vec![]), vec![1125899906842624]);
+    }
+
+    #[test]
+    fn writes_relative_to_the_base_past_the_end_of_the_program() {
+        let mut program = "109,1000,203,5,204,5,99".parse::<Program>().unwrap();
+
+        assert_eq!(program.run(vec![42]), vec![42]);
+        assert_eq!(program.relative_base(), 1000);
+        assert_eq!(program.code.len(), 1006);
+    }
+}
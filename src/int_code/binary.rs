@@ -0,0 +1,237 @@
+use std::error::Error;
+use std::fmt;
+
+const MAGIC: &[u8; 4] = b"ICB\x00";
+const VERSION: u8 = 1;
+const MAX_VARINT_BYTES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    Truncated { offset: usize },
+    VarintTooLong { offset: usize },
+    VarintOverflow { offset: usize },
+    TrailingBytes { offset: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "not an Intcode binary (bad magic header)"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported binary version {}", v),
+            DecodeError::Truncated { offset } => write!(f, "truncated at byte {}", offset),
+            DecodeError::VarintTooLong { offset } => {
+                write!(f, "malformed varint at byte {}", offset)
+            }
+            DecodeError::VarintOverflow { offset } => {
+                write!(f, "varint at byte {} doesn't fit in 64 bits", offset)
+            }
+            DecodeError::TrailingBytes { offset } => {
+                write!(f, "unexpected trailing bytes from byte {}", offset)
+            }
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+// Layout: the 4 byte magic, a version byte, the number of words as a varint
+// and then each word zigzag encoded as a varint, so small negative numbers
+// stay as short as small positive ones.
+pub fn encode(code: &[i64]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + code.len() * 2);
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    write_varint(&mut bytes, code.len() as u64);
+
+    for &word in code {
+        write_varint(&mut bytes, zigzag(word));
+    }
+
+    bytes
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Vec<i64>, DecodeError> {
+    if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(DecodeError::BadMagic);
+    }
+
+    let mut reader = Reader {
+        bytes,
+        offset: MAGIC.len(),
+    };
+
+    let version = reader.byte()?;
+
+    if version != VERSION {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+
+    let len = reader.varint()? as usize;
+
+    // Every word takes at least one byte, so this bounds the allocation by
+    // the size of the input rather than trusting the header.
+    if len > bytes.len() - reader.offset {
+        return Err(DecodeError::Truncated {
+            offset: bytes.len(),
+        });
+    }
+
+    let mut code = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        code.push(unzigzag(reader.varint()?));
+    }
+
+    if reader.offset != bytes.len() {
+        return Err(DecodeError::TrailingBytes {
+            offset: reader.offset,
+        });
+    }
+
+    Ok(code)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let byte = *self.bytes.get(self.offset).ok_or(DecodeError::Truncated {
+            offset: self.offset,
+        })?;
+
+        self.offset += 1;
+
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let start = self.offset;
+        let mut value = 0u64;
+
+        for i in 0..MAX_VARINT_BYTES {
+            let byte = self.byte()?;
+
+            // The last byte only has room for the top bit of the value.
+            if i == MAX_VARINT_BYTES - 1 && byte & 0x7e != 0 {
+                return Err(DecodeError::VarintOverflow { offset: start });
+            }
+
+            value |= u64::from(byte & 0x7f) << (7 * i);
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(DecodeError::VarintTooLong { offset: start })
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+fn zigzag(x: i64) -> u64 {
+    ((x << 1) ^ (x >> 63)) as u64
+}
+
+fn unzigzag(x: u64) -> i64 {
+    ((x >> 1) as i64) ^ -((x & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Program;
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    fn zigzag_keeps_small_negative_numbers_small() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        assert_eq!(zigzag(-2), 3);
+        assert_eq!(unzigzag(zigzag(i64::MIN)), i64::MIN);
+        assert_eq!(unzigzag(zigzag(i64::MAX)), i64::MAX);
+    }
+
+    #[test]
+    fn round_trips_extreme_values() {
+        let code = vec![0, 1, -1, 99, -99_999, i64::MAX, i64::MIN];
+
+        assert_eq!(decode(&encode(&code)), Ok(code));
+    }
+
+    #[test]
+    fn round_trips_the_day_five_program() {
+        let text = read_to_string("src/five.txt").unwrap();
+        let program = text.parse::<Program>().unwrap();
+
+        let bytes = program.to_binary();
+
+        assert_eq!(Program::from_binary(&bytes).unwrap().code, program.code);
+        assert!(bytes.len() < text.len());
+    }
+
+    #[test]
+    fn rejects_files_without_the_header() {
+        assert_eq!(decode(b"1,2,3"), Err(DecodeError::BadMagic));
+        assert_eq!(
+            decode(b"ICB\x00\x07\x00"),
+            Err(DecodeError::UnsupportedVersion(7))
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let bytes = encode(&[1, 500, 3]);
+
+        for len in MAGIC.len()..bytes.len() {
+            assert!(
+                matches!(decode(&bytes[..len]), Err(DecodeError::Truncated { .. })),
+                "decoded a file truncated to {} bytes",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut bytes = encode(&[1, 2]);
+        bytes.push(0);
+
+        assert_eq!(
+            decode(&bytes),
+            Err(DecodeError::TrailingBytes { offset: 8 })
+        );
+    }
+
+    #[test]
+    fn rejects_varints_that_overflow() {
+        let mut bytes = b"ICB\x00\x01\x01".to_vec();
+        // Ten bytes, with more than the top bit set in the last one.
+        bytes.extend_from_slice(&[0xff; 9]);
+        bytes.push(0x03);
+
+        assert_eq!(
+            decode(&bytes),
+            Err(DecodeError::VarintOverflow { offset: 6 })
+        );
+
+        // The largest value still fits.
+        let largest = encode(&[i64::MIN]);
+        assert_eq!(largest[largest.len() - 1], 0x01);
+        assert_eq!(decode(&largest), Ok(vec![i64::MIN]));
+    }
+}
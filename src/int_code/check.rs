@@ -0,0 +1,415 @@
+use super::{Decoded, Instruction, Mode};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    // Something the checker couldn't follow, so the rest may be incomplete.
+    Note,
+    Warning,
+    // Running the program would panic or do something the spec forbids.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    InvalidOpcode(i64),
+    // An invalid opcode in a cell the program writes to, which may well be
+    // valid by the time it runs.
+    PatchedOpcode(i64),
+    // The instruction's parameters run past the end of the program.
+    Truncated,
+    RunsOffEnd,
+    ImmediateWrite { param: usize },
+    NegativeAddress(i64),
+    AddressPastEnd(i64),
+    JumpOutside(i64),
+    JumpIntoInstruction { target: usize, instruction: usize },
+    DynamicJump,
+    ModifiesCode { instruction: usize },
+}
+
+impl DiagnosticKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            DiagnosticKind::InvalidOpcode(_)
+            | DiagnosticKind::Truncated
+            | DiagnosticKind::RunsOffEnd
+            | DiagnosticKind::ImmediateWrite { .. }
+            | DiagnosticKind::NegativeAddress(_)
+            | DiagnosticKind::JumpOutside(_)
+            | DiagnosticKind::JumpIntoInstruction { .. } => Severity::Error,
+            DiagnosticKind::AddressPastEnd(_) | DiagnosticKind::ModifiesCode { .. } => {
+                Severity::Warning
+            }
+            DiagnosticKind::PatchedOpcode(_) | DiagnosticKind::DynamicJump => Severity::Note,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub address: usize,
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04} {}: ", self.address, self.severity)?;
+
+        match &self.kind {
+            DiagnosticKind::InvalidOpcode(op_code) => write!(f, "invalid opcode {}", op_code),
+            DiagnosticKind::PatchedOpcode(op_code) => write!(
+                f,
+                "invalid opcode {} is written to before it runs, so nothing after it is checked",
+                op_code
+            ),
+            DiagnosticKind::Truncated => write!(f, "instruction runs past the end of the program"),
+            DiagnosticKind::RunsOffEnd => write!(f, "execution runs off the end of the program"),
+            DiagnosticKind::ImmediateWrite { param } => {
+                write!(f, "parameter {} writes through immediate mode", param + 1)
+            }
+            DiagnosticKind::NegativeAddress(address) => {
+                write!(f, "negative address {}", address)
+            }
+            DiagnosticKind::AddressPastEnd(address) => {
+                write!(f, "address {} is past the end of the program", address)
+            }
+            DiagnosticKind::JumpOutside(target) => {
+                write!(f, "jump target {} is outside the program", target)
+            }
+            DiagnosticKind::JumpIntoInstruction {
+                target,
+                instruction,
+            } => write!(
+                f,
+                "jump target {} is inside the instruction at {}",
+                target, instruction
+            ),
+            DiagnosticKind::DynamicJump => write!(f, "jump target is only known at runtime"),
+            DiagnosticKind::ModifiesCode { instruction } => {
+                write!(f, "writes into the instruction at {}", instruction)
+            }
+        }
+    }
+}
+
+// Follows every path from address zero without running anything, and
+// reports problems with the instructions it reaches, in address order.
+// Jumps through memory or the relative base can't be followed, so code only
+// reached that way isn't checked.
+pub fn check(code: &[i64]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut report = |address, kind: DiagnosticKind| {
+        diagnostics.push(Diagnostic {
+            address,
+            severity: kind.severity(),
+            kind,
+        })
+    };
+
+    let mut reachable = BTreeMap::new();
+    let mut jumps = Vec::new();
+    let mut pending = vec![0];
+
+    while let Some(address) = pending.pop() {
+        if reachable.contains_key(&address) {
+            continue;
+        }
+
+        if address >= code.len() {
+            report(address, DiagnosticKind::RunsOffEnd);
+            continue;
+        }
+
+        let decoded = match Decoded::at(code, address) {
+            Some(decoded) => decoded,
+            None if Instruction::decode(code[address]).is_some() => {
+                report(address, DiagnosticKind::Truncated);
+                continue;
+            }
+            None => {
+                report(address, DiagnosticKind::InvalidOpcode(code[address]));
+                continue;
+            }
+        };
+
+        let mut addresses = BTreeSet::new();
+
+        for (param, operand) in decoded.operands.iter().enumerate() {
+            match operand.mode {
+                Mode::Immediate if decoded.instruction.write_param() == Some(param) => {
+                    report(address, DiagnosticKind::ImmediateWrite { param });
+                }
+                Mode::Position if addresses.insert(operand.value) => {
+                    if operand.value < 0 {
+                        report(address, DiagnosticKind::NegativeAddress(operand.value));
+                    } else if operand.value as usize >= code.len() {
+                        report(address, DiagnosticKind::AddressPastEnd(operand.value));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let (taken_when, operands) = match decoded.instruction {
+            Instruction::JumpIfTrue(_) => (true, &decoded.operands),
+            Instruction::JumpIfFalse(_) => (false, &decoded.operands),
+            Instruction::Halt => {
+                reachable.insert(address, decoded);
+                continue;
+            }
+            _ => {
+                pending.push(decoded.next());
+                reachable.insert(address, decoded);
+                continue;
+            }
+        };
+
+        let condition = operands[0];
+        let always = condition.mode == Mode::Immediate && (condition.value != 0) == taken_when;
+        let never = condition.mode == Mode::Immediate && (condition.value != 0) != taken_when;
+
+        if !always {
+            pending.push(decoded.next());
+        }
+
+        if !never {
+            let target = operands[1];
+
+            if target.mode != Mode::Immediate {
+                report(address, DiagnosticKind::DynamicJump);
+            } else if target.value < 0 || target.value as usize >= code.len() {
+                report(address, DiagnosticKind::JumpOutside(target.value));
+            } else {
+                jumps.push((address, target.value as usize));
+                pending.push(target.value as usize);
+            }
+        }
+
+        reachable.insert(address, decoded);
+    }
+
+    let instruction_at = |cell: usize| {
+        reachable
+            .range(..=cell)
+            .next_back()
+            .filter(|(_, decoded)| cell < decoded.next())
+            .map(|(&start, _)| start)
+    };
+
+    for (address, target) in jumps {
+        // A target that's the start of some other, overlapping, decoding is
+        // still inside this one.
+        let containing = reachable
+            .values()
+            .find(|decoded| decoded.address < target && target < decoded.next());
+
+        if let Some(decoded) = containing {
+            report(
+                address,
+                DiagnosticKind::JumpIntoInstruction {
+                    target,
+                    instruction: decoded.address,
+                },
+            );
+        }
+    }
+
+    let written = reachable
+        .values()
+        .filter_map(|decoded| decoded.write_address())
+        .collect::<BTreeSet<_>>();
+
+    let mut modified = BTreeSet::new();
+
+    for decoded in reachable.values() {
+        let target = match decoded.write_address() {
+            Some(target) => target,
+            None => continue,
+        };
+
+        if let Some(instruction) = instruction_at(target) {
+            if modified.insert((decoded.address, instruction)) {
+                report(
+                    decoded.address,
+                    DiagnosticKind::ModifiesCode { instruction },
+                );
+            }
+        }
+    }
+
+    for diagnostic in &mut diagnostics {
+        if let DiagnosticKind::InvalidOpcode(op_code) = diagnostic.kind {
+            if written.contains(&diagnostic.address) {
+                diagnostic.kind = DiagnosticKind::PatchedOpcode(op_code);
+                diagnostic.severity = diagnostic.kind.severity();
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.address);
+    diagnostics
+}
+
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(code: &[i64]) -> Vec<(usize, DiagnosticKind)> {
+        check(code)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.address, diagnostic.kind))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_the_day_nine_quine() {
+        let quine = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+
+        // It keeps a counter past its end on purpose, which is only worth a
+        // warning.
+        assert!(!has_errors(&check(&quine)));
+        assert_eq!(
+            kinds(&quine),
+            vec![
+                (4, DiagnosticKind::AddressPastEnd(100)),
+                (8, DiagnosticKind::AddressPastEnd(100)),
+                (8, DiagnosticKind::AddressPastEnd(101)),
+                (12, DiagnosticKind::AddressPastEnd(101)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_and_truncated_instructions() {
+        assert_eq!(
+            kinds(&[1101, 1, 1, 5, 42, 0]),
+            vec![(4, DiagnosticKind::InvalidOpcode(42))]
+        );
+        assert_eq!(kinds(&[1101, 1, 1]), vec![(0, DiagnosticKind::Truncated)]);
+        assert_eq!(kinds(&[4, 0]), vec![(2, DiagnosticKind::RunsOffEnd)]);
+    }
+
+    #[test]
+    fn reports_invalid_parameter_modes() {
+        // An add with a mode 3 third parameter, and one with a mode 9 first.
+        assert_eq!(
+            kinds(&[30001, 0, 0, 9, 99, 0, 0, 0, 0, 0]),
+            vec![(0, DiagnosticKind::InvalidOpcode(30001))]
+        );
+        assert_eq!(
+            kinds(&[1101, 0, 0, 9, 901, 0, 0, 9, 99, 0]),
+            vec![(4, DiagnosticKind::InvalidOpcode(901))]
+        );
+    }
+
+    #[test]
+    fn allows_opcodes_patched_before_they_run() {
+        // Day five's input adds the first input to 1100 to make an add.
+        assert_eq!(
+            kinds(&[3, 9, 1, 9, 6, 6, 1100, 1, 238, 0]),
+            vec![(6, DiagnosticKind::PatchedOpcode(1100))]
+        );
+    }
+
+    #[test]
+    fn only_checks_reachable_code() {
+        // The jump skips over garbage.
+        assert_eq!(kinds(&[1105, 1, 4, 42, 99]), vec![]);
+    }
+
+    #[test]
+    fn reports_writes_through_immediate_parameters() {
+        assert_eq!(
+            kinds(&[11101, 1, 2, 3, 99]),
+            vec![
+                (0, DiagnosticKind::ImmediateWrite { param: 2 }),
+                (0, DiagnosticKind::ModifiesCode { instruction: 0 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_about_self_modifying_code() {
+        // add [4], 1, [4] turns the add after it into a multiply.
+        assert_eq!(
+            kinds(&[1001, 4, 1, 4, 1, 9, 9, 9, 99, 0]),
+            vec![(0, DiagnosticKind::ModifiesCode { instruction: 4 })]
+        );
+    }
+
+    #[test]
+    fn reports_constant_addresses_out_of_bounds() {
+        assert_eq!(
+            kinds(&[4, -3, 4, 50, 99]),
+            vec![
+                (0, DiagnosticKind::NegativeAddress(-3)),
+                (2, DiagnosticKind::AddressPastEnd(50)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_bad_jump_targets() {
+        // jz [9], 20; jnz [9], 1; halt, where 1 is inside the first jump.
+        let code = [1006, 9, 20, 1005, 9, 1, 99, 0, 0, 0];
+
+        assert_eq!(
+            kinds(&code),
+            vec![
+                (0, DiagnosticKind::JumpOutside(20)),
+                // What the jump lands on decodes as `arb [20]`.
+                (1, DiagnosticKind::AddressPastEnd(20)),
+                (
+                    3,
+                    DiagnosticKind::JumpIntoInstruction {
+                        target: 1,
+                        instruction: 0,
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn notes_jumps_it_cannot_follow() {
+        // Conditions read from memory are fine, as both ways are followed.
+        assert_eq!(kinds(&[1005, 4, 3, 99, 0]), vec![]);
+        assert_eq!(
+            kinds(&[105, 1, 4, 99, 3]),
+            vec![(0, DiagnosticKind::DynamicJump)]
+        );
+    }
+
+    #[test]
+    fn formats_diagnostics_with_address_and_severity() {
+        let diagnostics = check(&[1106, 0, -1]);
+
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "0000 error: jump target -1 is outside the program"
+        );
+        assert!(has_errors(&diagnostics));
+        assert!(Severity::Error > Severity::Warning && Severity::Warning > Severity::Note);
+    }
+}
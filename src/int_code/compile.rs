@@ -0,0 +1,764 @@
+use super::Program;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+// Compiles a small language down to Intcode. A program is a list of
+// statements:
+//
+//     n = input();
+//     total = 0;
+//     while n > 0 {
+//         total = total + n;
+//         n = n - 1;
+//     }
+//     if total == 0 { output(-1); } else { output(total); }
+//
+// Variables hold integers and must be assigned before they're read.
+// Expressions have `+`, `-`, `*`, unary `-`, parentheses and the comparisons
+// `==`, `!=`, `<`, `<=`, `>` and `>=`, which give 1 or 0. Conditions are true
+// when they're not 0. `#` starts a comment running to the end of the line.
+pub fn compile(source: &str) -> Result<Program, CompileError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        next: 0,
+        variables: HashMap::new(),
+    };
+
+    let statements = parser.statements()?;
+
+    if let Some((token, line)) = parser.tokens.get(parser.next) {
+        return Err(CompileError {
+            kind: CompileErrorKind::UnexpectedToken(token.to_string()),
+            line: *line,
+        });
+    }
+
+    let mut generator = Generator {
+        cells: Vec::new(),
+        labels: Vec::new(),
+        temps: 0,
+        max_temps: 0,
+    };
+
+    generator.statements(&statements);
+    generator.cells.push(Cell::Value(99));
+
+    Ok(Program::new(generator.link(parser.variables.len())))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileErrorKind {
+    UnexpectedCharacter(char),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UndefinedVariable(String),
+    InvalidNumber(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    pub kind: CompileErrorKind,
+    pub line: usize,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            CompileErrorKind::UnexpectedCharacter(c) => {
+                write!(f, "line {}: unexpected character `{}`", self.line, c)
+            }
+            CompileErrorKind::UnexpectedToken(token) => {
+                write!(f, "line {}: unexpected `{}`", self.line, token)
+            }
+            CompileErrorKind::UnexpectedEnd => {
+                write!(f, "line {}: unexpected end of program", self.line)
+            }
+            CompileErrorKind::UndefinedVariable(name) => write!(
+                f,
+                "line {}: `{}` is read before it's assigned",
+                self.line, name
+            ),
+            CompileErrorKind::InvalidNumber(number) => {
+                write!(f, "line {}: invalid number `{}`", self.line, number)
+            }
+        }
+    }
+}
+
+impl Error for CompileError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Name(name) => write!(f, "{}", name),
+            Token::Symbol(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
+// Longest first, so `<=` isn't read as `<` then `=`.
+const SYMBOLS: [&str; 15] = [
+    "==", "!=", "<=", ">=", "<", ">", "=", "+", "-", "*", "(", ")", "{", "}", ";",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, CompileError> {
+    let mut tokens = Vec::new();
+
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        let mut rest = line.split('#').next().unwrap().trim_start();
+
+        while let Some(c) = rest.chars().next() {
+            let length = if c.is_ascii_digit() {
+                let length = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(rest.len());
+                let number = rest[..length].parse().map_err(|_| CompileError {
+                    kind: CompileErrorKind::InvalidNumber(rest[..length].to_string()),
+                    line: line_number,
+                })?;
+
+                tokens.push((Token::Number(number), line_number));
+                length
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let length = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(rest.len());
+
+                tokens.push((Token::Name(rest[..length].to_string()), line_number));
+                length
+            } else if let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(*s)) {
+                tokens.push((Token::Symbol(symbol), line_number));
+                symbol.len()
+            } else {
+                return Err(CompileError {
+                    kind: CompileErrorKind::UnexpectedCharacter(c),
+                    line: line_number,
+                });
+            };
+
+            rest = rest[length..].trim_start();
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Number(i64),
+    // The variable's slot, numbered in the order they're first assigned.
+    Variable(usize),
+    Input,
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Statement {
+    Assign(usize, Expr),
+    Output(Expr),
+    If(Expr, Vec<Statement>, Vec<Statement>),
+    While(Expr, Vec<Statement>),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+    variables: HashMap<String, usize>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error(&self) -> CompileError {
+        let kind = match self.peek() {
+            Some(token) => CompileErrorKind::UnexpectedToken(token.to_string()),
+            None => CompileErrorKind::UnexpectedEnd,
+        };
+
+        CompileError {
+            kind,
+            line: self.line(),
+        }
+    }
+
+    fn advance(&mut self) -> Result<Token, CompileError> {
+        let token = self.peek().cloned().ok_or_else(|| self.error())?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn accept(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.next += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), CompileError> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(name)) if name == keyword)
+    }
+
+    // Statements up to a closing brace or the end of the program.
+    fn statements(&mut self) -> Result<Vec<Statement>, CompileError> {
+        let mut statements = Vec::new();
+
+        while self.peek().is_some() && self.peek() != Some(&Token::Symbol("}")) {
+            statements.push(self.statement()?);
+        }
+
+        Ok(statements)
+    }
+
+    fn block(&mut self) -> Result<Vec<Statement>, CompileError> {
+        self.expect("{")?;
+        let statements = self.statements()?;
+        self.expect("}")?;
+
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Statement, CompileError> {
+        if self.is_keyword("if") {
+            self.next += 1;
+            let condition = self.expression()?;
+            let then = self.block()?;
+
+            let otherwise = if self.is_keyword("else") {
+                self.next += 1;
+
+                if self.is_keyword("if") {
+                    vec![self.statement()?]
+                } else {
+                    self.block()?
+                }
+            } else {
+                Vec::new()
+            };
+
+            return Ok(Statement::If(condition, then, otherwise));
+        }
+
+        if self.is_keyword("while") {
+            self.next += 1;
+            let condition = self.expression()?;
+
+            return Ok(Statement::While(condition, self.block()?));
+        }
+
+        if self.is_keyword("output") {
+            self.next += 1;
+            self.expect("(")?;
+            let value = self.expression()?;
+            self.expect(")")?;
+            self.expect(";")?;
+
+            return Ok(Statement::Output(value));
+        }
+
+        let name = match self.peek() {
+            Some(Token::Name(name)) if !is_reserved(name) => name.clone(),
+            _ => return Err(self.error()),
+        };
+
+        self.next += 1;
+        self.expect("=")?;
+        let value = self.expression()?;
+        self.expect(";")?;
+
+        // Added after the value is parsed, so `x = x + 1;` can't be the
+        // first assignment to `x`.
+        let count = self.variables.len();
+        let slot = *self.variables.entry(name).or_insert(count);
+
+        Ok(Statement::Assign(slot, value))
+    }
+
+    fn expression(&mut self) -> Result<Expr, CompileError> {
+        let left = self.sum()?;
+
+        let op = match self.peek() {
+            Some(Token::Symbol("==")) => BinaryOp::Equal,
+            Some(Token::Symbol("!=")) => BinaryOp::NotEqual,
+            Some(Token::Symbol("<")) => BinaryOp::Less,
+            Some(Token::Symbol("<=")) => BinaryOp::LessOrEqual,
+            Some(Token::Symbol(">")) => BinaryOp::Greater,
+            Some(Token::Symbol(">=")) => BinaryOp::GreaterOrEqual,
+            _ => return Ok(left),
+        };
+
+        self.next += 1;
+        let right = self.sum()?;
+
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn sum(&mut self) -> Result<Expr, CompileError> {
+        let mut left = self.product()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("+")) => BinaryOp::Add,
+                Some(Token::Symbol("-")) => BinaryOp::Subtract,
+                _ => return Ok(left),
+            };
+
+            self.next += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, CompileError> {
+        let mut left = self.unary()?;
+
+        while self.accept("*") {
+            left = Expr::Binary(BinaryOp::Multiply, Box::new(left), Box::new(self.unary()?));
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CompileError> {
+        if self.accept("-") {
+            return match self.unary()? {
+                Expr::Number(n) => Ok(Expr::Number(-n)),
+                expr => Ok(Expr::Negate(Box::new(expr))),
+            };
+        }
+
+        let line = self.line();
+
+        match self.advance()? {
+            Token::Number(n) => Ok(Expr::Number(n)),
+            Token::Symbol("(") => {
+                let expr = self.expression()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Name(name) if name == "input" => {
+                self.expect("(")?;
+                self.expect(")")?;
+                Ok(Expr::Input)
+            }
+            Token::Name(name) if !is_reserved(&name) => match self.variables.get(&name) {
+                Some(&slot) => Ok(Expr::Variable(slot)),
+                None => Err(CompileError {
+                    kind: CompileErrorKind::UndefinedVariable(name),
+                    line,
+                }),
+            },
+            _ => {
+                self.next -= 1;
+                Err(self.error())
+            }
+        }
+    }
+}
+
+fn is_reserved(name: &str) -> bool {
+    ["if", "else", "while", "input", "output"].contains(&name)
+}
+
+// A cell of generated code, before the data after the code has an address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Cell {
+    Value(i64),
+    Variable(usize),
+    Temp(usize),
+    Label(usize),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operand {
+    Immediate(i64),
+    Position(Cell),
+}
+
+impl Operand {
+    fn mode(self) -> i64 {
+        match self {
+            Operand::Immediate(_) => 1,
+            Operand::Position(_) => 0,
+        }
+    }
+
+    fn cell(self) -> Cell {
+        match self {
+            Operand::Immediate(value) => Cell::Value(value),
+            Operand::Position(cell) => cell,
+        }
+    }
+}
+
+struct Generator {
+    cells: Vec<Cell>,
+    // Where each label ended up, once it's been placed.
+    labels: Vec<Option<usize>>,
+    temps: usize,
+    max_temps: usize,
+}
+
+impl Generator {
+    fn label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.cells.len());
+    }
+
+    fn temp(&mut self) -> Cell {
+        self.temps += 1;
+        self.max_temps = self.max_temps.max(self.temps);
+        Cell::Temp(self.temps - 1)
+    }
+
+    fn emit(&mut self, op_code: i64, params: &[Operand]) {
+        let modes = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| param.mode() * 10_i64.pow(i as u32 + 2))
+            .sum::<i64>();
+
+        self.cells.push(Cell::Value(op_code + modes));
+        self.cells.extend(params.iter().map(|param| param.cell()));
+    }
+
+    fn jump(&mut self, op_code: i64, condition: Operand, label: usize) {
+        self.emit(op_code, &[condition, Operand::Immediate(0)]);
+        *self.cells.last_mut().unwrap() = Cell::Label(label);
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            // Temporaries only live for one statement.
+            self.temps = 0;
+            self.statement(statement);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign(slot, value) => {
+                let value = self.expression(value);
+                let target = Operand::Position(Cell::Variable(*slot));
+
+                self.emit(1, &[value, Operand::Immediate(0), target]);
+            }
+
+            Statement::Output(value) => {
+                let value = self.expression(value);
+                self.emit(4, &[value]);
+            }
+
+            Statement::If(condition, then, otherwise) => {
+                let else_label = self.label();
+                let end_label = self.label();
+
+                let condition = self.expression(condition);
+                self.jump(6, condition, else_label);
+                self.statements(then);
+
+                if otherwise.is_empty() {
+                    self.place(else_label);
+                } else {
+                    self.jump(5, Operand::Immediate(1), end_label);
+                    self.place(else_label);
+                    self.statements(otherwise);
+                }
+
+                self.place(end_label);
+            }
+
+            Statement::While(condition, body) => {
+                let start_label = self.label();
+                let end_label = self.label();
+
+                self.place(start_label);
+                let condition = self.expression(condition);
+                self.jump(6, condition, end_label);
+                self.statements(body);
+                self.jump(5, Operand::Immediate(1), start_label);
+                self.place(end_label);
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &Expr) -> Operand {
+        match expr {
+            Expr::Number(n) => Operand::Immediate(*n),
+            Expr::Variable(slot) => Operand::Position(Cell::Variable(*slot)),
+
+            Expr::Input => {
+                let result = Operand::Position(self.temp());
+                self.emit(3, &[result]);
+                result
+            }
+
+            Expr::Negate(value) => {
+                let value = self.expression(value);
+                let result = Operand::Position(self.temp());
+
+                self.emit(2, &[value, Operand::Immediate(-1), result]);
+                result
+            }
+
+            Expr::Binary(op, left, right) => {
+                let left = self.expression(left);
+                let right = self.expression(right);
+                let result = Operand::Position(self.temp());
+
+                // Intcode only has add, multiply, less than and equals, so
+                // the rest are built out of those.
+                match op {
+                    BinaryOp::Add => self.emit(1, &[left, right, result]),
+                    BinaryOp::Multiply => self.emit(2, &[left, right, result]),
+                    BinaryOp::Less => self.emit(7, &[left, right, result]),
+                    BinaryOp::Greater => self.emit(7, &[right, left, result]),
+                    BinaryOp::Equal => self.emit(8, &[left, right, result]),
+                    BinaryOp::Subtract => {
+                        self.emit(2, &[right, Operand::Immediate(-1), result]);
+                        self.emit(1, &[left, result, result]);
+                    }
+                    BinaryOp::NotEqual | BinaryOp::LessOrEqual | BinaryOp::GreaterOrEqual => {
+                        match op {
+                            BinaryOp::NotEqual => self.emit(8, &[left, right, result]),
+                            BinaryOp::LessOrEqual => self.emit(7, &[right, left, result]),
+                            _ => self.emit(7, &[left, right, result]),
+                        }
+
+                        self.emit(8, &[result, Operand::Immediate(0), result]);
+                    }
+                }
+
+                result
+            }
+        }
+    }
+
+    // Variables go straight after the code, then the temporaries.
+    fn link(&self, variables: usize) -> Vec<i64> {
+        let data = self.cells.len();
+
+        let mut code = self
+            .cells
+            .iter()
+            .map(|&cell| match cell {
+                Cell::Value(value) => value,
+                Cell::Variable(slot) => (data + slot) as i64,
+                Cell::Temp(slot) => (data + variables + slot) as i64,
+                Cell::Label(label) => self.labels[label].expect("Label never placed") as i64,
+            })
+            .collect::<Vec<_>>();
+
+        code.resize(data + variables + self.max_temps, 0);
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str, inputs: Vec<i64>) -> Vec<i64> {
+        compile(source).unwrap().run(inputs)
+    }
+
+    fn error(source: &str) -> CompileError {
+        compile(source).unwrap_err()
+    }
+
+    #[test]
+    fn echoes_input() {
+        assert_eq!(run("output(input());", vec![42]), vec![42]);
+        assert_eq!(run("", vec![]), vec![]);
+    }
+
+    #[test]
+    fn evaluates_arithmetic_with_precedence() {
+        assert_eq!(run("output(2 + 3 * 4);", vec![]), vec![14]);
+        assert_eq!(run("output((2 + 3) * 4);", vec![]), vec![20]);
+        assert_eq!(run("output(10 - 3 - 2);", vec![]), vec![5]);
+        assert_eq!(run("x = 7; output(-x * -2);", vec![]), vec![14]);
+    }
+
+    #[test]
+    fn compares_to_one_or_zero() {
+        let source = "
+            a = input();
+            b = input();
+            output(a == b);
+            output(a != b);
+            output(a < b);
+            output(a <= b);
+            output(a > b);
+            output(a >= b);
+        ";
+
+        assert_eq!(run(source, vec![1, 2]), vec![0, 1, 1, 1, 0, 0]);
+        assert_eq!(run(source, vec![2, 2]), vec![1, 0, 0, 1, 0, 1]);
+        assert_eq!(run(source, vec![3, 2]), vec![0, 1, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn branches_with_if_and_else() {
+        let source = "
+            x = input();
+            if x < 0 {
+                output(-1);
+            } else if x == 0 {
+                output(0);
+            } else {
+                output(1);
+            }
+            if x == 5 { output(5); }
+        ";
+
+        assert_eq!(run(source, vec![-7]), vec![-1]);
+        assert_eq!(run(source, vec![0]), vec![0]);
+        assert_eq!(run(source, vec![5]), vec![1, 5]);
+    }
+
+    #[test]
+    fn loops_with_while() {
+        let source = "
+            # Factorials up to the input.
+            n = input();
+            i = 1;
+            product = 1;
+            while i <= n {
+                product = product * i;
+                output(product);
+                i = i + 1;
+            }
+        ";
+
+        assert_eq!(run(source, vec![5]), vec![1, 2, 6, 24, 120]);
+        assert_eq!(run(source, vec![0]), vec![]);
+    }
+
+    #[test]
+    fn runs_nested_loops() {
+        // Counts the primes below the input by trial multiplication, since
+        // there's no division.
+        let source = "
+            limit = input();
+            count = 0;
+            n = 2;
+            while n < limit {
+                prime = 1;
+                a = 2;
+                while a * a <= n {
+                    b = a;
+                    while a * b <= n {
+                        if a * b == n { prime = 0; }
+                        b = b + 1;
+                    }
+                    a = a + 1;
+                }
+                count = count + prime;
+                n = n + 1;
+            }
+            output(count);
+        ";
+
+        assert_eq!(run(source, vec![30]), vec![10]);
+    }
+
+    #[test]
+    fn reads_input_in_order() {
+        assert_eq!(run("output(input() - input());", vec![10, 3]), vec![7]);
+    }
+
+    #[test]
+    fn keeps_variables_after_the_code() {
+        let program = compile("x = 3;").unwrap();
+
+        assert_eq!(program.code.to_vec(), vec![1101, 3, 0, 5, 99, 0]);
+    }
+
+    #[test]
+    fn reports_undefined_variables() {
+        assert_eq!(
+            error("x = 1;\ny = y + x;"),
+            CompileError {
+                kind: CompileErrorKind::UndefinedVariable("y".to_string()),
+                line: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_with_lines() {
+        assert_eq!(
+            error("x = 1;\n\nx = x / 2;"),
+            CompileError {
+                kind: CompileErrorKind::UnexpectedCharacter('/'),
+                line: 3,
+            }
+        );
+        assert_eq!(
+            error("while 1 { output(1) }"),
+            CompileError {
+                kind: CompileErrorKind::UnexpectedToken("}".to_string()),
+                line: 1,
+            }
+        );
+        assert_eq!(
+            error("x = (1 + 2"),
+            CompileError {
+                kind: CompileErrorKind::UnexpectedEnd,
+                line: 1,
+            }
+        );
+        assert_eq!(error("if = 3;").to_string(), "line 1: unexpected `=`");
+        assert_eq!(
+            error("x = 12ab;").kind,
+            CompileErrorKind::InvalidNumber("12ab".to_string())
+        );
+    }
+}
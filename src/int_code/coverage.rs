@@ -0,0 +1,247 @@
+use super::disassemble::Decoded;
+use super::{Decompiler, Instruction, Mode, Program, Step};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BranchCounts {
+    pub taken: usize,
+    pub not_taken: usize,
+}
+
+// Collects which instructions ran, and which way each conditional jump went,
+// over any number of runs of the same program.
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    program: Program,
+    instructions: BTreeMap<usize, Decoded>,
+    hits: BTreeMap<usize, usize>,
+    branches: BTreeMap<usize, BranchCounts>,
+}
+
+impl Coverage {
+    pub fn new(program: &Program) -> Coverage {
+        let instructions = Decompiler::new(&program.code.to_vec())
+            .reachable()
+            .into_iter()
+            .map(|decoded| (decoded.address, decoded))
+            .collect::<BTreeMap<_, _>>();
+
+        let branches = instructions
+            .values()
+            .filter(|decoded| is_branch(decoded))
+            .map(|decoded| (decoded.address, BranchCounts::default()))
+            .collect();
+
+        Coverage {
+            program: program.clone(),
+            instructions,
+            hits: BTreeMap::new(),
+            branches,
+        }
+    }
+
+    pub fn run<I>(&mut self, inputs: I) -> Vec<i64>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        let mut program = self.program.clone();
+        let mut inputs = inputs.into_iter();
+        let mut outputs = Vec::new();
+
+        loop {
+            let address = program.address();
+
+            // Decoded from live memory, since self-modifying code can run
+            // instructions that weren't there when the program was loaded.
+            let decoded = program.current_instruction();
+
+            let step = program.step(&mut inputs);
+
+            *self.hits.entry(address).or_insert(0) += 1;
+
+            if let Some(decoded) = decoded {
+                if is_branch(&decoded) {
+                    let counts = self.branches.entry(address).or_default();
+
+                    if program.address() == decoded.next() {
+                        counts.not_taken += 1;
+                    } else {
+                        counts.taken += 1;
+                    }
+                }
+
+                self.instructions.entry(address).or_insert(decoded);
+            }
+
+            match step {
+                Step::Executed => {}
+                Step::Output(output) => outputs.push(output),
+                Step::AwaitingInput => panic!("No input given"),
+                Step::Halted => return outputs,
+            }
+        }
+    }
+
+    pub fn executed(&self) -> usize {
+        self.hits.len()
+    }
+
+    pub fn instructions(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn percentage(&self) -> f64 {
+        percentage(self.executed(), self.instructions())
+    }
+
+    // Each conditional jump has two outcomes to cover.
+    pub fn branch_percentage(&self) -> f64 {
+        let covered = self
+            .branches
+            .values()
+            .map(|counts| (counts.taken > 0) as usize + (counts.not_taken > 0) as usize)
+            .sum();
+
+        percentage(covered, self.branches.len() * 2)
+    }
+
+    pub fn hits(&self, address: usize) -> usize {
+        self.hits.get(&address).copied().unwrap_or(0)
+    }
+
+    pub fn branch(&self, address: usize) -> Option<BranchCounts> {
+        self.branches.get(&address).copied()
+    }
+
+    pub fn uncovered(&self) -> Vec<usize> {
+        self.instructions
+            .keys()
+            .copied()
+            .filter(|address| self.hits(*address) == 0)
+            .collect()
+    }
+
+    pub fn listing(&self) -> String {
+        let mut out = String::new();
+
+        for (&address, decoded) in &self.instructions {
+            let hits = match self.hits(address) {
+                0 => "-".to_string(),
+                n => n.to_string(),
+            };
+
+            let mut line = format!("{:>6}  {:04}  {:<24}", hits, address, decoded.to_string());
+
+            if let Some(counts) = self.branch(address) {
+                line.push_str(&format!(
+                    " taken {}, not taken {}",
+                    counts.taken, counts.not_taken
+                ));
+
+                if counts.taken == 0 || counts.not_taken == 0 {
+                    line.push_str("  <- partial");
+                }
+            }
+
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "instructions: {}/{} ({:.1}%)",
+            self.executed(),
+            self.instructions(),
+            self.percentage()
+        )?;
+
+        write!(
+            f,
+            "branches: {} conditional jumps ({:.1}% of outcomes)",
+            self.branches.len(),
+            self.branch_percentage()
+        )
+    }
+}
+
+fn is_branch(decoded: &Decoded) -> bool {
+    match decoded.instruction {
+        Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_) => {
+            decoded.operands[0].mode != Mode::Immediate
+        }
+        _ => false,
+    }
+}
+
+fn percentage(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        100.0 * covered as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LARGER_EXAMPLE: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+    #[test]
+    fn a_single_input_leaves_paths_uncovered() {
+        let mut coverage = Coverage::new(&LARGER_EXAMPLE.parse::<Program>().unwrap());
+
+        assert_eq!(coverage.run(vec![8]), vec![1000]);
+
+        assert_eq!(coverage.uncovered(), vec![9, 13, 16, 31, 33, 36, 40, 42]);
+        assert_eq!(
+            coverage.branch(6),
+            Some(BranchCounts {
+                taken: 1,
+                not_taken: 0
+            })
+        );
+        assert!(coverage.percentage() < 50.0);
+    }
+
+    #[test]
+    fn the_day_five_examples_cover_every_path() {
+        let mut coverage = Coverage::new(&LARGER_EXAMPLE.parse::<Program>().unwrap());
+
+        for input in &[7, 8, 9] {
+            coverage.run(vec![*input]);
+        }
+
+        assert_eq!(coverage.uncovered(), vec![]);
+        assert_eq!(coverage.percentage(), 100.0);
+        assert_eq!(coverage.branch_percentage(), 100.0);
+        assert_eq!(
+            coverage.to_string(),
+            "instructions: 15/15 (100.0%)\nbranches: 2 conditional jumps (100.0% of outcomes)"
+        );
+    }
+
+    #[test]
+    fn annotates_the_listing_with_hits_and_branches() {
+        let mut coverage = Coverage::new(&LARGER_EXAMPLE.parse::<Program>().unwrap());
+
+        coverage.run(vec![7]);
+
+        let listing = coverage.listing();
+        let lines = listing.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "     1  0000  in [21]");
+        assert_eq!(
+            lines[2],
+            "     1  0006  jnz [20], 22             taken 0, not taken 1  <- partial"
+        );
+        assert_eq!(lines[5], "     -  0016  jz 0, 36");
+    }
+}
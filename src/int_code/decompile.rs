@@ -0,0 +1,914 @@
+use super::disassemble::{Decoded, Line, Operand};
+use super::{disassemble, Instruction, Mode};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// Gives up on refining which cells are written after this many passes. Each
+// pass can only make more jump targets dynamic, so this is rarely reached.
+const MAX_PASSES: usize = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Cond {
+    operand: Operand,
+    non_zero: bool,
+}
+
+impl Cond {
+    fn negate(self) -> Cond {
+        Cond {
+            non_zero: !self.non_zero,
+            ..self
+        }
+    }
+
+    fn render(self) -> String {
+        let op = if self.non_zero { "!=" } else { "==" };
+
+        format!("{} {} 0", value(self.operand), op)
+    }
+}
+
+// Where a jump goes when it can't be worked out ahead of time. `Indirect`
+// is a position mode target whose parameter is itself overwritten at runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Target {
+    Cell(Operand),
+    Indirect(usize),
+}
+
+impl Target {
+    fn render(self) -> String {
+        match self {
+            Target::Cell(operand) => value(operand),
+            Target::Indirect(cell) => format!("mem[v{}]", cell),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Flow {
+    Next,
+    Halt,
+    Goto(usize),
+    Branch(Cond, usize),
+    Dynamic(Option<Cond>, Target),
+}
+
+#[derive(Debug, Clone)]
+struct Op {
+    decoded: Decoded,
+    flow: Flow,
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Simple {
+        address: usize,
+        text: String,
+    },
+    If {
+        address: usize,
+        cond: String,
+        then: Vec<Stmt>,
+        otherwise: Vec<Stmt>,
+    },
+    While {
+        address: usize,
+        cond: String,
+        body: Vec<Stmt>,
+    },
+    DoWhile {
+        address: usize,
+        cond: String,
+        body: Vec<Stmt>,
+    },
+    Loop {
+        address: usize,
+        body: Vec<Stmt>,
+    },
+}
+
+impl Stmt {
+    fn address(&self) -> usize {
+        match self {
+            Stmt::Simple { address, .. } => *address,
+            Stmt::If { address, .. } => *address,
+            Stmt::While { address, .. } => *address,
+            Stmt::DoWhile { address, .. } => *address,
+            Stmt::Loop { address, .. } => *address,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct LoopContext {
+    head: usize,
+    exit: usize,
+}
+
+pub struct Decompiler<'a> {
+    code: &'a [i64],
+    entry_points: Vec<usize>,
+}
+
+impl<'a> Decompiler<'a> {
+    pub fn new(code: &'a [i64]) -> Decompiler<'a> {
+        Decompiler {
+            code,
+            entry_points: vec![0],
+        }
+    }
+
+    // Extra places to start decoding from, for code that is only reached
+    // through a jump whose target is computed at runtime.
+    pub fn entry_point(mut self, address: usize) -> Decompiler<'a> {
+        self.entry_points.push(address);
+        self
+    }
+
+    pub fn decompile(&self) -> String {
+        let (ops, invalid, written) = self.analyse();
+
+        let mut structurer = Structurer {
+            ops: &ops,
+            labels: self.entry_points.iter().skip(1).copied().collect(),
+        };
+
+        let stmts = structurer.block(0, usize::MAX, None);
+
+        let mut out = String::new();
+
+        for (&address, op) in &ops {
+            if (address..op.decoded.next()).any(|cell| written.contains(&cell)) {
+                out.push_str(&format!(
+                    "// warning: instruction at {} is modified at runtime\n",
+                    address
+                ));
+            }
+        }
+
+        for address in &invalid {
+            out.push_str(&format!(
+                "// warning: invalid instruction {} at {}\n",
+                self.code.get(*address).copied().unwrap_or(0),
+                address
+            ));
+        }
+
+        for cell in self.variables(&ops) {
+            out.push_str(&format!("var v{} = {};\n", cell, self.code[cell]));
+        }
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        render(&stmts, 0, &structurer.labels, None, &mut out);
+
+        out
+    }
+
+    pub fn reachable(&self) -> Vec<Decoded> {
+        let (ops, _, _) = self.analyse();
+
+        ops.into_values().map(|op| op.decoded).collect()
+    }
+
+    // Whether every path was followed to its end, without reaching an
+    // undecodable instruction or a jump whose target is computed at runtime,
+    // and every cell the code touches is known without running it.
+    pub fn is_complete(&self) -> bool {
+        let (ops, invalid, _) = self.analyse();
+
+        invalid.is_empty()
+            && ops.values().all(|op| {
+                !matches!(op.flow, Flow::Dynamic(_, _))
+                    && op
+                        .decoded
+                        .operands
+                        .iter()
+                        .all(|operand| operand.mode != Mode::Relative)
+            })
+    }
+
+    // Every cell that a reachable instruction can write to.
+    pub fn written(&self) -> BTreeSet<usize> {
+        let (_, _, written) = self.analyse();
+
+        written
+    }
+
+    fn analyse(&self) -> (BTreeMap<usize, Op>, BTreeSet<usize>, BTreeSet<usize>) {
+        let mut written = disassemble(self.code)
+            .iter()
+            .filter_map(|line| match line {
+                Line::Instruction(decoded) => decoded.write_address(),
+                Line::Data { .. } => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        let mut pass = 0;
+
+        loop {
+            let (ops, invalid) = self.discover(&written);
+
+            let reachable_writes = ops
+                .values()
+                .filter_map(|op| op.decoded.write_address())
+                .collect::<BTreeSet<_>>();
+
+            pass += 1;
+
+            if reachable_writes == written || pass == MAX_PASSES {
+                return (ops, invalid, written);
+            }
+
+            written = reachable_writes;
+        }
+    }
+
+    fn discover(&self, written: &BTreeSet<usize>) -> (BTreeMap<usize, Op>, BTreeSet<usize>) {
+        let facts = self.facts(written);
+        let mut ops = BTreeMap::new();
+        let mut invalid = BTreeSet::new();
+        let mut pending = self.entry_points.clone();
+
+        while let Some(start) = pending.pop() {
+            let mut address = start;
+
+            while address < self.code.len() && !ops.contains_key(&address) {
+                let decoded = match Decoded::at(self.code, address) {
+                    Some(decoded) => decoded,
+                    None => {
+                        invalid.insert(address);
+                        break;
+                    }
+                };
+
+                let known = facts.get(&address).cloned().unwrap_or_default();
+                let flow = self.flow(&decoded, &known, written);
+                let next = decoded.next();
+
+                ops.insert(address, Op { decoded, flow });
+
+                match flow {
+                    Flow::Next => address = next,
+                    Flow::Branch(_, target) => {
+                        pending.push(target);
+                        address = next;
+                    }
+                    Flow::Goto(target) => {
+                        pending.push(target);
+                        break;
+                    }
+                    Flow::Dynamic(Some(_), _) => address = next,
+                    Flow::Halt | Flow::Dynamic(None, _) => break,
+                }
+            }
+        }
+
+        (ops, invalid)
+    }
+
+    // The constants known on entry to each reachable instruction, whichever
+    // way it's reached. Arriving somewhere again keeps only the facts both
+    // ways agree on, and carries on from there if that lost any, so a jump
+    // is only resolved when its condition holds on every path to it.
+    fn facts(&self, written: &BTreeSet<usize>) -> HashMap<usize, HashMap<usize, i64>> {
+        let mut facts: HashMap<usize, HashMap<usize, i64>> = HashMap::new();
+        let mut pending = self
+            .entry_points
+            .iter()
+            .map(|&address| (address, HashMap::new()))
+            .collect::<Vec<_>>();
+
+        while let Some((start, mut known)) = pending.pop() {
+            let mut address = start;
+
+            while address < self.code.len() {
+                match facts.get_mut(&address) {
+                    Some(existing) => {
+                        let before = existing.len();
+                        existing.retain(|cell, value| known.get(cell) == Some(value));
+
+                        if existing.len() == before {
+                            break;
+                        }
+
+                        known = existing.clone();
+                    }
+                    None => {
+                        facts.insert(address, known.clone());
+                    }
+                }
+
+                let decoded = match Decoded::at(self.code, address) {
+                    Some(decoded) => decoded,
+                    None => break,
+                };
+
+                let flow = self.flow(&decoded, &known, written);
+
+                if decoded.instruction.write_param().is_some() {
+                    match decoded.write_address() {
+                        Some(target) => match self.evaluate(&decoded, &known, written) {
+                            Some(value) => known.insert(target, value),
+                            None => known.remove(&target),
+                        },
+                        // Could be any cell.
+                        None => {
+                            known.clear();
+                            None
+                        }
+                    };
+                }
+
+                match flow {
+                    Flow::Next | Flow::Dynamic(Some(_), _) => address = decoded.next(),
+                    Flow::Branch(_, target) => {
+                        pending.push((target, known.clone()));
+                        address = decoded.next();
+                    }
+                    Flow::Goto(target) => {
+                        pending.push((target, known));
+                        break;
+                    }
+                    Flow::Halt | Flow::Dynamic(None, _) => break,
+                }
+            }
+        }
+
+        facts
+    }
+
+    fn flow(
+        &self,
+        decoded: &Decoded,
+        known: &HashMap<usize, i64>,
+        written: &BTreeSet<usize>,
+    ) -> Flow {
+        let (non_zero, operands) = match decoded.instruction {
+            Instruction::JumpIfTrue(_) => (true, &decoded.operands),
+            Instruction::JumpIfFalse(_) => (false, &decoded.operands),
+            Instruction::Halt => return Flow::Halt,
+            _ => return Flow::Next,
+        };
+
+        let cond_cell = decoded.address + 1;
+        let target_cell = decoded.address + 2;
+
+        let cond = Cond {
+            operand: self.runtime_operand(operands[0], cond_cell, written),
+            non_zero,
+        };
+
+        let (target, dynamic_target) = if written.contains(&target_cell) {
+            let dynamic_target = match operands[1].mode {
+                Mode::Position => Target::Indirect(target_cell),
+                Mode::Immediate | Mode::Relative => {
+                    Target::Cell(self.runtime_operand(operands[1], target_cell, written))
+                }
+            };
+
+            (None, dynamic_target)
+        } else {
+            let target = self
+                .resolve(operands[1], known, written)
+                .filter(|&t| t >= 0 && (t as usize) < self.code.len())
+                .map(|t| t as usize);
+
+            (target, Target::Cell(operands[1]))
+        };
+
+        let cond_value = if written.contains(&cond_cell) {
+            None
+        } else {
+            self.resolve(operands[0], known, written)
+        };
+
+        match (cond_value, target) {
+            (Some(x), _) if (x != 0) != non_zero => Flow::Next,
+            (Some(_), Some(target)) => Flow::Goto(target),
+            (Some(_), None) => Flow::Dynamic(None, dynamic_target),
+            (None, Some(target)) => Flow::Branch(cond, target),
+            (None, None) => Flow::Dynamic(Some(cond), dynamic_target),
+        }
+    }
+
+    // An immediate parameter that gets overwritten behaves like a read of
+    // the parameter's own cell.
+    fn runtime_operand(&self, operand: Operand, cell: usize, written: &BTreeSet<usize>) -> Operand {
+        if operand.mode == Mode::Immediate && written.contains(&cell) {
+            Operand {
+                mode: Mode::Position,
+                value: cell as i64,
+            }
+        } else {
+            operand
+        }
+    }
+
+    fn resolve(
+        &self,
+        operand: Operand,
+        known: &HashMap<usize, i64>,
+        written: &BTreeSet<usize>,
+    ) -> Option<i64> {
+        match operand.mode {
+            Mode::Immediate => Some(operand.value),
+            Mode::Position => {
+                let address = operand.value as usize;
+
+                match known.get(&address) {
+                    Some(&value) => Some(value),
+                    None if !written.contains(&address) => self.code.get(address).copied(),
+                    None => None,
+                }
+            }
+            Mode::Relative => None,
+        }
+    }
+
+    fn evaluate(
+        &self,
+        decoded: &Decoded,
+        known: &HashMap<usize, i64>,
+        written: &BTreeSet<usize>,
+    ) -> Option<i64> {
+        let mut args = decoded.operands.iter();
+        let mut arg = || self.resolve(*args.next().unwrap(), known, written);
+
+        match decoded.instruction {
+            // Results that overflow aren't treated as constants.
+            Instruction::Add(_) => arg()?.checked_add(arg()?),
+            Instruction::Multiply(_) => arg()?.checked_mul(arg()?),
+            Instruction::LessThan(_) => Some((arg()? < arg()?) as i64),
+            Instruction::Equals(_) => Some((arg()? == arg()?) as i64),
+            _ => None,
+        }
+    }
+
+    // Cells that the decompiled code refers to by name and that are never
+    // executed, so they can be declared up front with their initial values.
+    fn variables(&self, ops: &BTreeMap<usize, Op>) -> BTreeSet<usize> {
+        let code_cells = ops
+            .values()
+            .flat_map(|op| op.decoded.address..op.decoded.next())
+            .collect::<BTreeSet<_>>();
+
+        ops.values()
+            .flat_map(|op| op.decoded.operands.iter())
+            .filter(|operand| operand.mode == Mode::Position)
+            .filter(|operand| operand.value >= 0 && (operand.value as usize) < self.code.len())
+            .map(|operand| operand.value as usize)
+            .filter(|cell| !code_cells.contains(cell))
+            .collect()
+    }
+}
+
+pub fn decompile(code: &[i64]) -> String {
+    Decompiler::new(code).decompile()
+}
+
+struct Structurer<'a> {
+    ops: &'a BTreeMap<usize, Op>,
+    labels: BTreeSet<usize>,
+}
+
+impl<'a> Structurer<'a> {
+    fn block(&mut self, start: usize, end: usize, context: Option<LoopContext>) -> Vec<Stmt> {
+        let mut stmts = Vec::new();
+        let mut address = start;
+
+        while let Some((&a, op)) = self.ops.range(address..end).next() {
+            let is_current_loop_head = context.is_some_and(|c| c.head == a);
+
+            if let Some(b) = self.back_edge_to(a, end).filter(|_| !is_current_loop_head) {
+                let (stmt, exit) = self.structure_loop(a, b);
+
+                stmts.push(stmt);
+                address = exit;
+                continue;
+            }
+
+            let next = op.decoded.next();
+
+            match op.flow {
+                Flow::Next | Flow::Halt => {
+                    stmts.push(Stmt::Simple {
+                        address: a,
+                        text: statement(&op.decoded),
+                    });
+                    address = next;
+                }
+
+                Flow::Goto(target) if self.falls_through(next, target, end) => {
+                    address = next;
+                }
+
+                Flow::Branch(cond, target) if target > a && target <= end => {
+                    let else_jump =
+                        self.ops
+                            .range(next..target)
+                            .next_back()
+                            .and_then(|(&jump, op)| match op.flow {
+                                Flow::Goto(join) if join > target && join <= end => {
+                                    Some((jump, join))
+                                }
+                                _ => None,
+                            });
+
+                    match else_jump {
+                        Some((jump, join)) => {
+                            let then = self.block(next, jump, context);
+                            let otherwise = self.block(target, join, context);
+
+                            stmts.push(Stmt::If {
+                                address: a,
+                                cond: cond.negate().render(),
+                                then,
+                                otherwise,
+                            });
+                            address = join;
+                        }
+                        None => {
+                            let then = self.block(next, target, context);
+
+                            stmts.push(Stmt::If {
+                                address: a,
+                                cond: cond.negate().render(),
+                                then,
+                                otherwise: vec![],
+                            });
+                            address = target;
+                        }
+                    }
+                }
+
+                Flow::Goto(target) => {
+                    stmts.push(self.jump(a, None, target, context));
+                    address = next;
+                }
+
+                Flow::Branch(cond, target) => {
+                    stmts.push(self.jump(a, Some(cond), target, context));
+                    address = next;
+                }
+
+                Flow::Dynamic(cond, target) => {
+                    stmts.push(conditional(a, cond, format!("goto *{};", target.render())));
+                    address = next;
+                }
+            }
+        }
+
+        stmts
+    }
+
+    fn structure_loop(&mut self, head: usize, back_edge: usize) -> (Stmt, usize) {
+        let jump = &self.ops[&back_edge];
+        let exit = jump.decoded.next();
+        let context = Some(LoopContext { head, exit });
+
+        let stmt = match jump.flow {
+            Flow::Branch(cond, _) => Stmt::DoWhile {
+                address: head,
+                cond: cond.render(),
+                body: self.block(head, back_edge, context),
+            },
+            _ => match self.ops[&head].flow {
+                Flow::Branch(cond, target) if target == exit => Stmt::While {
+                    address: head,
+                    cond: cond.negate().render(),
+                    body: self.block(self.ops[&head].decoded.next(), back_edge, context),
+                },
+                _ => Stmt::Loop {
+                    address: head,
+                    body: self.block(head, back_edge, context),
+                },
+            },
+        };
+
+        (stmt, exit)
+    }
+
+    // A jump over nothing but data, to code that would be next anyway.
+    fn falls_through(&self, next: usize, target: usize, end: usize) -> bool {
+        target >= next && target <= end && self.ops.range(next..target).next().is_none()
+    }
+
+    fn back_edge_to(&self, head: usize, end: usize) -> Option<usize> {
+        self.ops
+            .range(head..end)
+            .rev()
+            .find(|(_, op)| match op.flow {
+                Flow::Goto(target) | Flow::Branch(_, target) => target == head,
+                _ => false,
+            })
+            .map(|(&address, _)| address)
+    }
+
+    fn jump(
+        &mut self,
+        address: usize,
+        cond: Option<Cond>,
+        target: usize,
+        context: Option<LoopContext>,
+    ) -> Stmt {
+        let text = match context {
+            Some(c) if c.exit == target => "break;".to_string(),
+            Some(c) if c.head == target => "continue;".to_string(),
+            _ => {
+                self.labels.insert(target);
+                format!("goto L{};", target)
+            }
+        };
+
+        conditional(address, cond, text)
+    }
+}
+
+fn conditional(address: usize, cond: Option<Cond>, text: String) -> Stmt {
+    match cond {
+        Some(cond) => Stmt::If {
+            address,
+            cond: cond.render(),
+            then: vec![Stmt::Simple { address, text }],
+            otherwise: vec![],
+        },
+        None => Stmt::Simple { address, text },
+    }
+}
+
+// Nested statements can share an address with the statement around them,
+// in which case the label has already been written for the outer one.
+fn render(
+    stmts: &[Stmt],
+    depth: usize,
+    labels: &BTreeSet<usize>,
+    parent: Option<usize>,
+    out: &mut String,
+) {
+    let indent = "    ".repeat(depth);
+
+    for stmt in stmts {
+        let address = stmt.address();
+        let inner = Some(address);
+
+        if labels.contains(&address) && parent != inner {
+            out.push_str(&format!("{}L{}:\n", indent, address));
+        }
+
+        match stmt {
+            Stmt::Simple { text, .. } => {
+                out.push_str(&format!("{}{}\n", indent, text));
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                out.push_str(&format!("{}if ({}) {{\n", indent, cond));
+                render(then, depth + 1, labels, inner, out);
+
+                if !otherwise.is_empty() {
+                    out.push_str(&format!("{}}} else {{\n", indent));
+                    render(otherwise, depth + 1, labels, inner, out);
+                }
+
+                out.push_str(&format!("{}}}\n", indent));
+            }
+            Stmt::While { cond, body, .. } => {
+                out.push_str(&format!("{}while ({}) {{\n", indent, cond));
+                render(body, depth + 1, labels, inner, out);
+                out.push_str(&format!("{}}}\n", indent));
+            }
+            Stmt::DoWhile { cond, body, .. } => {
+                out.push_str(&format!("{}do {{\n", indent));
+                render(body, depth + 1, labels, inner, out);
+                out.push_str(&format!("{}}} while ({});\n", indent, cond));
+            }
+            Stmt::Loop { body, .. } => {
+                out.push_str(&format!("{}loop {{\n", indent));
+                render(body, depth + 1, labels, inner, out);
+                out.push_str(&format!("{}}}\n", indent));
+            }
+        }
+    }
+}
+
+fn statement(decoded: &Decoded) -> String {
+    let args = &decoded.operands;
+    let target = || match decoded.write_address() {
+        Some(address) => format!("v{}", address),
+        None => match args[decoded.instruction.write_param().unwrap()] {
+            operand if operand.mode == Mode::Relative => value(operand),
+            operand => format!("mem[{}]", operand.value),
+        },
+    };
+
+    if let [x, y, _] = args[..] {
+        if x.mode == Mode::Immediate && y.mode == Mode::Immediate {
+            let folded = match decoded.instruction {
+                Instruction::Add(_) => x.value.checked_add(y.value),
+                Instruction::Multiply(_) => x.value.checked_mul(y.value),
+                Instruction::LessThan(_) => Some((x.value < y.value) as i64),
+                _ => Some((x.value == y.value) as i64),
+            };
+
+            if let Some(folded) = folded {
+                return format!("{} = {};", target(), folded);
+            }
+        }
+    }
+
+    match decoded.instruction {
+        Instruction::Add(_) if is_negative_constant(args[1]) => {
+            format!("{} = {} - {};", target(), value(args[0]), -args[1].value)
+        }
+        Instruction::Add(_) if is_negative_constant(args[0]) => {
+            format!("{} = {} - {};", target(), value(args[1]), -args[0].value)
+        }
+        Instruction::Add(_) => format!("{} = {} + {};", target(), value(args[0]), value(args[1])),
+        Instruction::Multiply(_) => {
+            format!("{} = {} * {};", target(), value(args[0]), value(args[1]))
+        }
+        Instruction::LessThan(_) => {
+            format!("{} = {} < {};", target(), value(args[0]), value(args[1]))
+        }
+        Instruction::Equals(_) => {
+            format!("{} = {} == {};", target(), value(args[0]), value(args[1]))
+        }
+        Instruction::ReadInput(_) => format!("{} = input();", target()),
+        Instruction::WriteOutput(_) => format!("output({});", value(args[0])),
+        Instruction::AdjustRelativeBase(_) => format!("rb += {};", value(args[0])),
+        Instruction::Halt => "halt;".to_string(),
+        Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_) => unreachable!(),
+    }
+}
+
+fn is_negative_constant(operand: Operand) -> bool {
+    operand.mode == Mode::Immediate && operand.value < 0 && operand.value != i64::MIN
+}
+
+fn value(operand: Operand) -> String {
+    match operand.mode {
+        Mode::Position if operand.value >= 0 => format!("v{}", operand.value),
+        Mode::Position => format!("mem[{}]", operand.value),
+        Mode::Immediate => operand.value.to_string(),
+        Mode::Relative if operand.value < 0 => format!("mem[rb - {}]", -operand.value),
+        Mode::Relative => format!("mem[rb + {}]", operand.value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::int_code::Program;
+    use std::fs::read_to_string;
+
+    fn decompiled(input: &str) -> String {
+        input.parse::<Program>().unwrap().decompile()
+    }
+
+    #[test]
+    fn recovers_an_if_else() {
+        assert_eq!(
+            decompiled("3,20,1008,20,5,21,1005,21,14,104,0,1105,1,16,104,1,99,0,0,0,0,0"),
+            [
+                "var v20 = 0;",
+                "var v21 = 0;",
+                "",
+                "v20 = input();",
+                "v21 = v20 == 5;",
+                "if (v21 == 0) {",
+                "    output(0);",
+                "} else {",
+                "    output(1);",
+                "}",
+                "halt;",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn recovers_a_while_loop() {
+        assert_eq!(
+            decompiled("1006,13,12,4,13,1001,13,-1,13,1105,1,0,99,3"),
+            [
+                "var v13 = 3;",
+                "",
+                "while (v13 != 0) {",
+                "    output(v13);",
+                "    v13 = v13 - 1;",
+                "}",
+                "halt;",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn recovers_a_do_while_loop() {
+        assert_eq!(
+            decompiled(
+                "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5"
+            ),
+            [
+                "var v26 = 0;",
+                "var v27 = 0;",
+                "var v28 = 5;",
+                "",
+                "v26 = input();",
+                "v26 = v26 - 4;",
+                "do {",
+                "    v27 = input();",
+                "    v27 = v27 * 2;",
+                "    v27 = v27 + v26;",
+                "    output(v27);",
+                "    v28 = v28 - 1;",
+                "} while (v28 != 0);",
+                "halt;",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_gotos_for_unstructured_jumps() {
+        let out = decompiled("3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99");
+
+        assert!(out.contains("goto L31;"));
+        assert!(out.contains("L31:\n    output(999);"));
+    }
+
+    #[test]
+    fn resolves_jumps_through_constants_written_just_before() {
+        let mut program = read_to_string("src/five.txt")
+            .unwrap()
+            .parse::<Program>()
+            .unwrap();
+
+        // The diagnostic program adds its input to the opcode at 6, so it
+        // only decodes once that has happened. This is how it looks for 1.
+        program.code[6] += 1;
+
+        let out = program.decompile();
+
+        assert!(out.starts_with("// warning: instruction at 6 is modified at runtime\n"));
+        assert!(out.contains("v224 = v224 - 1344;"));
+        assert!(out.ends_with("output(v223);\nhalt;\n"));
+        assert!(!out.contains("goto"));
+    }
+
+    #[test]
+    fn decodes_code_only_reachable_through_extra_entry_points() {
+        let program = read_to_string("src/seven.txt")
+            .unwrap()
+            .parse::<Program>()
+            .unwrap();
+
+        let plain = decompile(&program.code.to_vec());
+        let with_entry = Decompiler::new(&program.code.to_vec())
+            .entry_point(21)
+            .decompile();
+
+        assert!(plain.contains("goto *mem[v8];"));
+        assert!(!plain.contains("L21:"));
+        assert!(with_entry.contains("L21:\nv9 = input();"));
+    }
+
+    #[test]
+    fn only_resolves_jumps_that_go_the_same_way_on_every_path() {
+        // The jz at 4 is taken the first time round, when [23] is 0, but
+        // not after the code at 12 sets it to 1 and jumps back.
+        let code = [
+            1101, 0, 0, 23, 1006, 23, 12, 4, 24, 99, 0, 0, 1002, 24, 1, 24, 1101, 1, 0, 23, 1105,
+            1, 4, 0, 42,
+        ];
+
+        let reachable = Decompiler::new(&code)
+            .reachable()
+            .iter()
+            .map(|decoded| decoded.address)
+            .collect::<Vec<_>>();
+
+        assert_eq!(reachable, vec![0, 4, 7, 9, 12, 16, 20]);
+        assert!(decompile(&code).contains("output(v24);"));
+    }
+
+    #[test]
+    fn leaves_arithmetic_that_overflows_unfolded() {
+        let sum = decompile(&[1101, i64::MAX, 1, 5, 99, 0]);
+        let product = decompile(&[1102, i64::MAX, 2, 5, 99, 0]);
+        let difference = decompile(&[1001, 5, i64::MIN, 5, 99, 0]);
+
+        assert!(sum.contains("v5 = 9223372036854775807 + 1;"));
+        assert!(product.contains("v5 = 9223372036854775807 * 2;"));
+        assert!(difference.contains("v5 = v5 + -9223372036854775808;"));
+    }
+}
@@ -0,0 +1,419 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+// Something mapped over a range of a program's memory. Parameters that read
+// or write an address in the range go to the device instead of memory, with
+// `offset` counted from the start of the range. Instructions are always
+// fetched from memory.
+pub trait Device: Any + fmt::Debug {
+    fn read(&mut self, offset: usize) -> i64;
+
+    fn write(&mut self, offset: usize, value: i64);
+
+    // Called after every instruction the program executes.
+    fn tick(&mut self) {}
+
+    // How many cells the device has, if it can't answer at every offset.
+    // Mapping more than this is refused.
+    fn size(&self) -> Option<usize> {
+        None
+    }
+
+    // Cloning a program clones its devices too, so a copy made as a save
+    // point doesn't share device state with the original.
+    fn box_clone(&self) -> Box<dyn Device>;
+}
+
+#[derive(Debug)]
+pub(crate) struct Mapping {
+    pub start: usize,
+    pub len: usize,
+    pub device: Box<dyn Device>,
+}
+
+impl Mapping {
+    pub fn contains(&self, address: usize) -> bool {
+        address >= self.start && address < self.start + self.len
+    }
+}
+
+impl Clone for Mapping {
+    fn clone(&self) -> Mapping {
+        Mapping {
+            start: self.start,
+            len: self.len,
+            device: self.device.box_clone(),
+        }
+    }
+}
+
+// Programs compare by where their devices are mapped, not by what state
+// the devices are in.
+impl PartialEq for Mapping {
+    fn eq(&self, other: &Mapping) -> bool {
+        self.start == other.start && self.len == other.len
+    }
+}
+
+impl Eq for Mapping {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapErrorKind {
+    Overlaps { start: usize, len: usize },
+    TooLong { size: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapError {
+    pub kind: MapErrorKind,
+    pub start: usize,
+    pub len: usize,
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let end = self.start + self.len;
+
+        match &self.kind {
+            MapErrorKind::Overlaps { start, len } => write!(
+                f,
+                "device at {}..{} overlaps the one at {}..{}",
+                self.start,
+                end,
+                start,
+                start + len
+            ),
+            MapErrorKind::TooLong { size } => write!(
+                f,
+                "device at {}..{} only has {} cells",
+                self.start, end, size
+            ),
+        }
+    }
+}
+
+impl Error for MapError {}
+
+pub(crate) fn downcast<D: Device>(device: &dyn Device) -> Option<&D> {
+    (device as &dyn Any).downcast_ref()
+}
+
+pub(crate) fn downcast_mut<D: Device>(device: &mut dyn Device) -> Option<&mut D> {
+    (device as &mut dyn Any).downcast_mut()
+}
+
+// A single cell: reading takes the next queued input, or -1 when there's
+// none, and writing appends to the output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Console {
+    pub input: VecDeque<i64>,
+    pub output: Vec<i64>,
+}
+
+impl Console {
+    pub fn new(input: &str) -> Console {
+        Console {
+            input: input.bytes().map(i64::from).collect(),
+            output: Vec::new(),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.output.iter().map(|&c| c as u8 as char).collect()
+    }
+}
+
+impl Device for Console {
+    fn read(&mut self, _offset: usize) -> i64 {
+        self.input.pop_front().unwrap_or(-1)
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) {
+        self.output.push(value);
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        Box::new(self.clone())
+    }
+}
+
+// A single cell counting the instructions executed since it was mapped.
+// Writing sets the count.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timer {
+    pub ticks: i64,
+}
+
+impl Device for Timer {
+    fn read(&mut self, _offset: usize) -> i64 {
+        self.ticks
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) {
+        self.ticks = value;
+    }
+
+    fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        Box::new(self.clone())
+    }
+}
+
+// A single cell giving a new non-negative pseudo-random number on every
+// read, from a xorshift generator. Writing reseeds it, so runs can be
+// repeated exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: i64) -> Random {
+        let mut random = Random { state: 0 };
+        random.write(0, seed);
+        random
+    }
+}
+
+impl Device for Random {
+    fn read(&mut self, _offset: usize) -> i64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        (self.state >> 1) as i64
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) {
+        // Xorshift gets stuck on zero.
+        self.state = (value as u64).max(1);
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        Box::new(self.clone())
+    }
+}
+
+// One cell per pixel, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<i64>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Framebuffer {
+        Framebuffer {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        }
+    }
+
+    // Zero pixels are blank and anything else is lit.
+    pub fn render(&self) -> String {
+        self.pixels
+            .chunks(self.width.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|&pixel| if pixel == 0 { ' ' } else { '#' })
+                    .chain(Some('\n'))
+                    .collect::<String>()
+            })
+            .collect()
+    }
+}
+
+impl Device for Framebuffer {
+    fn read(&mut self, offset: usize) -> i64 {
+        self.pixels[offset]
+    }
+
+    fn write(&mut self, offset: usize, value: i64) {
+        self.pixels[offset] = value;
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.pixels.len())
+    }
+
+    fn box_clone(&self) -> Box<dyn Device> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{compile, Program, Step};
+    use super::*;
+
+    // Records every access, and answers reads with the offset.
+    #[derive(Debug, Clone, Default)]
+    struct Probe {
+        accesses: Vec<(&'static str, usize, i64)>,
+    }
+
+    impl Device for Probe {
+        fn read(&mut self, offset: usize) -> i64 {
+            self.accesses.push(("read", offset, offset as i64));
+            offset as i64
+        }
+
+        fn write(&mut self, offset: usize, value: i64) {
+            self.accesses.push(("write", offset, value));
+        }
+
+        fn box_clone(&self) -> Box<dyn Device> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn sends_reads_and_writes_in_range_to_the_device() {
+        // add [10], [12], [11]; add [10], 0, [3]; halt
+        let mut program = Program::new(vec![1, 10, 12, 11, 1001, 10, 0, 3, 99]);
+        program.map_device(10, 3, Probe::default()).unwrap();
+
+        program.run(vec![]);
+
+        assert_eq!(
+            program.device::<Probe>(10).unwrap().accesses,
+            vec![
+                ("read", 0, 0),
+                ("read", 2, 2),
+                ("write", 1, 2),
+                ("read", 0, 0)
+            ]
+        );
+        assert_eq!(program.code[3], 0);
+        assert_eq!(program.code.len(), 9);
+    }
+
+    #[test]
+    fn echoes_through_a_console() {
+        // Copies the console to itself until it reads -1.
+        #[rustfmt::skip]
+        let mut program = Program::new(vec![
+            1001, 100, 0, 19,   // add [100], 0, [19]
+            1008, 19, -1, 20,   // eq [19], -1, [20]
+            1005, 20, 18,       // jnz [20], 18
+            1001, 19, 0, 100,   // add [19], 0, [100]
+            1105, 1, 0,         // jnz 1, 0
+            99,
+        ]);
+        program.map_device(100, 1, Console::new("hi!")).unwrap();
+
+        program.run(vec![]);
+
+        assert_eq!(program.device::<Console>(100).unwrap().text(), "hi!");
+    }
+
+    #[test]
+    fn counts_instructions_with_a_timer() {
+        let mut program = compile("i = 0; while i < 3 { i = i + 1; }").unwrap();
+        let steps = {
+            let mut copy = program.clone();
+            let mut count = 0;
+
+            while copy.step(&mut std::iter::empty()) != Step::Halted {
+                count += 1;
+            }
+
+            count
+        };
+
+        program.map_device(1000, 1, Timer::default()).unwrap();
+        program.run(vec![]);
+
+        assert_eq!(program.device::<Timer>(1000).unwrap().ticks, steps);
+    }
+
+    #[test]
+    fn repeats_random_numbers_for_the_same_seed() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+        let mut other = Random::new(7);
+
+        let a = (0..5).map(|_| first.read(0)).collect::<Vec<_>>();
+        let b = (0..5).map(|_| second.read(0)).collect::<Vec<_>>();
+        let c = (0..5).map(|_| other.read(0)).collect::<Vec<_>>();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.iter().all(|&x| x >= 0));
+
+        first.write(0, 42);
+        assert_eq!(first.read(0), a[0]);
+    }
+
+    #[test]
+    fn draws_into_a_framebuffer() {
+        // add 1, 0, [50]; add 1, 0, [54]; add 1, 0, [58]; halt
+        let mut program = Program::new(vec![1101, 1, 0, 50, 1101, 1, 0, 54, 1101, 1, 0, 58, 99]);
+        program.map_device(50, 9, Framebuffer::new(3, 3)).unwrap();
+
+        program.run(vec![]);
+
+        assert_eq!(
+            program.device::<Framebuffer>(50).unwrap().render(),
+            "#  \n # \n  #\n"
+        );
+    }
+
+    #[test]
+    fn clones_devices_along_with_the_program() {
+        let mut program = Program::new(vec![1101, 1, 0, 50, 99]);
+        program.map_device(50, 1, Console::default()).unwrap();
+
+        let mut copy = program.clone();
+        copy.run(vec![]);
+
+        assert_eq!(copy.device::<Console>(50).unwrap().output, vec![1]);
+        assert!(program.device::<Console>(50).unwrap().output.is_empty());
+    }
+
+    #[test]
+    fn refuses_overlapping_devices() {
+        let mut program = Program::new(vec![99]);
+        program.map_device(10, 5, Timer::default()).unwrap();
+
+        let err = program.map_device(14, 1, Timer::default()).unwrap_err();
+
+        assert_eq!(err.kind, MapErrorKind::Overlaps { start: 10, len: 5 });
+        assert_eq!(
+            err.to_string(),
+            "device at 14..15 overlaps the one at 10..15"
+        );
+        assert!(program.device::<Timer>(14).is_none());
+    }
+
+    #[test]
+    fn refuses_mappings_longer_than_the_device() {
+        let mut program = Program::new(vec![99]);
+
+        let err = program
+            .map_device(50, 10, Framebuffer::new(3, 3))
+            .unwrap_err();
+
+        assert_eq!(err.kind, MapErrorKind::TooLong { size: 9 });
+        assert_eq!(err.to_string(), "device at 50..60 only has 9 cells");
+        assert!(!program.has_devices());
+    }
+
+    #[test]
+    fn keeps_device_state_across_a_reset() {
+        let mut program = Program::new(vec![1101, 1, 0, 50, 99]);
+        program.map_device(50, 1, Console::default()).unwrap();
+
+        program.run(vec![]);
+        program.reset();
+
+        assert_eq!(program.device::<Console>(50).unwrap().output, vec![1]);
+    }
+}
@@ -0,0 +1,357 @@
+use super::Symbols;
+use std::borrow::Cow;
+use std::fmt;
+
+const CELLS_PER_ROW: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub start: usize,
+    pub before: Vec<i64>,
+    pub after: Vec<i64>,
+}
+
+impl Change {
+    pub fn end(&self) -> usize {
+        self.start + self.after.len()
+    }
+
+    fn contains(&self, address: usize) -> bool {
+        self.start <= address && address < self.end()
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.after.len() == 1 {
+            write!(f, "@{}: ", self.start)?;
+        } else {
+            write!(f, "@{}..{}: ", self.start, self.end())?;
+        }
+
+        write!(f, "{} -> {}", join(&self.before), join(&self.after))
+    }
+}
+
+// Cells past the end of either side are treated as zero, which is what an
+// Intcode machine sees when it reads memory it hasn't written to yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryDiff<'a> {
+    before: Cow<'a, [i64]>,
+    after: Cow<'a, [i64]>,
+    changes: Vec<Change>,
+    symbols: Symbols,
+}
+
+impl<'a> MemoryDiff<'a> {
+    pub fn between(before: &'a [i64], after: &'a [i64]) -> MemoryDiff<'a> {
+        MemoryDiff::new(before.into(), after.into())
+    }
+
+    pub(crate) fn from_owned(before: &'a [i64], after: Vec<i64>) -> MemoryDiff<'a> {
+        MemoryDiff::new(before.into(), after.into())
+    }
+
+    fn new(before: Cow<'a, [i64]>, after: Cow<'a, [i64]>) -> MemoryDiff<'a> {
+        let mut changes: Vec<Change> = Vec::new();
+
+        for address in 0..before.len().max(after.len()) {
+            let old = cell(&before, address);
+            let new = cell(&after, address);
+
+            if old == new {
+                continue;
+            }
+
+            match changes.last_mut() {
+                Some(change) if change.end() == address => {
+                    change.before.push(old);
+                    change.after.push(new);
+                }
+                _ => changes.push(Change {
+                    start: address,
+                    before: vec![old],
+                    after: vec![new],
+                }),
+            }
+        }
+
+        MemoryDiff {
+            before,
+            after,
+            changes,
+            symbols: Symbols::new(),
+        }
+    }
+
+    // Names changed cells in the hexdump and changes in the diff.
+    pub fn with_symbols(mut self, symbols: &Symbols) -> MemoryDiff<'a> {
+        self.symbols = symbols.clone();
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn changed_cells(&self) -> usize {
+        self.changes.iter().map(|change| change.after.len()).sum()
+    }
+
+    pub fn hexdump(&self) -> String {
+        let rows = self.rows_with_changes();
+
+        let width = rows
+            .iter()
+            .flat_map(|&row| row_addresses(row))
+            .map(|address| self.cell_width(address))
+            .max()
+            .unwrap_or(0);
+
+        let mut out = String::new();
+        let mut previous_row = None;
+
+        for row in rows {
+            if previous_row.map_or(row > 0, |prev| row > prev + 1) {
+                out.push_str("...\n");
+            }
+
+            let mut values = format!("{:04}:", row * CELLS_PER_ROW);
+            let mut annotations = " ".repeat(values.len());
+
+            for address in row_addresses(row) {
+                if address >= self.len() {
+                    break;
+                }
+
+                let new = cell(&self.after, address);
+
+                if self.is_changed(address) {
+                    let old = cell(&self.before, address);
+
+                    values.push_str(&format!(" {:>1$}", format!("*{}", new), width));
+                    annotations.push_str(&format!(" {:>1$}", format!("({})", old), width));
+                } else {
+                    values.push_str(&format!(" {:>1$}", new, width));
+                    annotations.push_str(&" ".repeat(width + 1));
+                }
+            }
+
+            out.push_str(values.trim_end());
+            out.push('\n');
+            out.push_str(annotations.trim_end());
+            out.push('\n');
+
+            previous_row = Some(row);
+        }
+
+        for change in &self.changes {
+            for address in change.start..change.end() {
+                if let Some(name) = self.symbols.name_of(address) {
+                    out.push_str(&format!(
+                        "{} @{}: {} -> {}\n",
+                        name,
+                        address,
+                        cell(&self.before, address),
+                        cell(&self.after, address)
+                    ));
+                }
+            }
+        }
+
+        out
+    }
+
+    fn len(&self) -> usize {
+        self.before.len().max(self.after.len())
+    }
+
+    fn is_changed(&self, address: usize) -> bool {
+        self.changes.iter().any(|change| change.contains(address))
+    }
+
+    fn cell_width(&self, address: usize) -> usize {
+        let new = cell(&self.after, address).to_string().len() + 1;
+        let old = cell(&self.before, address).to_string().len() + 2;
+
+        new.max(old)
+    }
+
+    fn rows_with_changes(&self) -> Vec<usize> {
+        let mut rows = self
+            .changes
+            .iter()
+            .flat_map(|change| {
+                (change.start / CELLS_PER_ROW)..=((change.end() - 1) / CELLS_PER_ROW)
+            })
+            .collect::<Vec<_>>();
+
+        rows.dedup();
+
+        rows
+    }
+}
+
+impl<'a> fmt::Display for MemoryDiff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            let names = self.symbols.starting_in(change.start, change.end());
+
+            if names.is_empty() {
+                writeln!(f, "{}", change)?;
+            } else {
+                writeln!(f, "{}  # {}", change, names.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[track_caller]
+pub fn assert_memory_eq(actual: &[i64], expected: &[i64]) {
+    let diff = MemoryDiff::between(expected, actual);
+
+    if actual.len() != expected.len() || !diff.is_empty() {
+        panic!(
+            "memory differs from expected ({} cells, expected {}; {} cells changed):\n{}\n{}",
+            actual.len(),
+            expected.len(),
+            diff.changed_cells(),
+            diff,
+            diff.hexdump()
+        );
+    }
+}
+
+fn cell(memory: &[i64], address: usize) -> i64 {
+    memory.get(address).copied().unwrap_or(0)
+}
+
+fn row_addresses(row: usize) -> impl Iterator<Item = usize> {
+    (row * CELLS_PER_ROW)..((row + 1) * CELLS_PER_ROW)
+}
+
+fn join(values: &[i64]) -> String {
+    values
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Program;
+    use super::*;
+
+    #[test]
+    fn groups_adjacent_changes_into_ranges() {
+        let diff = MemoryDiff::between(&[1, 2, 3, 4, 5, 6], &[1, 7, 8, 4, 9, 6]);
+
+        assert_eq!(
+            diff.changes(),
+            &[
+                Change {
+                    start: 1,
+                    before: vec![2, 3],
+                    after: vec![7, 8]
+                },
+                Change {
+                    start: 4,
+                    before: vec![5],
+                    after: vec![9]
+                }
+            ]
+        );
+        assert_eq!(diff.changed_cells(), 3);
+    }
+
+    #[test]
+    fn treats_missing_cells_as_zero() {
+        let diff = MemoryDiff::between(&[1, 2], &[1, 2, 0, 5]);
+
+        assert_eq!(diff.to_string(), "@3: 0 -> 5\n");
+    }
+
+    #[test]
+    fn lists_changes_with_old_and_new_values() {
+        let diff = MemoryDiff::between(&[1, 2, 3, 4, 5, 6], &[1, 7, 8, 4, 9, 6]);
+
+        assert_eq!(diff.to_string(), "@1..3: 2, 3 -> 7, 8\n@4: 5 -> 9\n");
+    }
+
+    #[test]
+    fn annotates_changed_cells_in_the_hexdump() {
+        let mut program = "1,9,10,3,2,3,11,0,99,30,40,50".parse::<Program>().unwrap();
+
+        program.run(vec![]);
+
+        assert_eq!(
+            program.memory_diff().hexdump(),
+            [
+                "0000: *3500     9    10   *70     2     3    11     0",
+                "        (1)               (3)",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn skips_rows_without_changes_in_the_hexdump() {
+        let before = (0..32).collect::<Vec<i64>>();
+        let mut after = before.clone();
+        after[25] = 0;
+
+        assert_eq!(
+            MemoryDiff::between(&before, &after).hexdump(),
+            [
+                "...",
+                "0024:   24   *0   26   27   28   29   30   31",
+                "           (25)",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn names_changed_cells_that_have_symbols() {
+        let symbols = "result 0\nnoun 1\nverb 2".parse::<Symbols>().unwrap();
+        let diff =
+            MemoryDiff::between(&[1, 0, 0, 3, 99], &[2, 12, 2, 3, 99]).with_symbols(&symbols);
+
+        assert_eq!(
+            diff.to_string(),
+            "@0..3: 1, 0, 0 -> 2, 12, 2  # result, noun, verb\n"
+        );
+        assert_eq!(
+            diff.hexdump(),
+            [
+                "0000:   *2  *12   *2    3   99",
+                "       (1)  (0)  (0)",
+                "result @0: 1 -> 2",
+                "noun @1: 0 -> 12",
+                "verb @2: 0 -> 2",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn is_empty_for_a_program_that_does_not_write() {
+        let mut program = "3,0,4,0,99".parse::<Program>().unwrap();
+        let pristine = "3,0,4,0,99".parse::<Program>().unwrap();
+
+        program.run(vec![3]);
+
+        assert!(program.memory_diff().is_empty());
+        assert!(pristine.memory_diff().is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use super::{Instruction, Mode, Symbols};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Operand {
+    pub mode: Mode,
+    pub value: i64,
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.mode {
+            Mode::Position => write!(f, "[{}]", self.value),
+            Mode::Immediate => write!(f, "{}", self.value),
+            Mode::Relative if self.value < 0 => write!(f, "[rb-{}]", -self.value),
+            Mode::Relative => write!(f, "[rb+{}]", self.value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub address: usize,
+    pub instruction: Instruction,
+    pub operands: Vec<Operand>,
+}
+
+impl Decoded {
+    // Returns `None` for an unknown opcode or an instruction whose
+    // parameters would run past the end of memory.
+    pub fn at(code: &[i64], address: usize) -> Option<Decoded> {
+        let instruction = Instruction::decode(*code.get(address)?)?;
+        let params = code.get(address + 1..address + instruction.width())?;
+
+        let operands = instruction
+            .modes()
+            .iter()
+            .zip(params)
+            .map(|(&mode, &value)| Operand { mode, value })
+            .collect();
+
+        Some(Decoded {
+            address,
+            instruction,
+            operands,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.instruction.width()
+    }
+
+    pub fn next(&self) -> usize {
+        self.address + self.width()
+    }
+
+    pub fn write_address(&self) -> Option<usize> {
+        let param = self.instruction.write_param()?;
+        let operand = self.operands[param];
+
+        match operand.mode {
+            Mode::Position if operand.value >= 0 => Some(operand.value as usize),
+            Mode::Position => None,
+            Mode::Immediate => Some(self.address + 1 + param),
+            // Depends on the relative base at the time it runs.
+            Mode::Relative => None,
+        }
+    }
+
+    // Like the plain display, but position operands with a name show it.
+    pub fn display_with(&self, symbols: &Symbols) -> String {
+        let operands = self
+            .operands
+            .iter()
+            .map(|operand| match operand.mode {
+                Mode::Position if operand.value >= 0 => {
+                    match symbols.name_of(operand.value as usize) {
+                        Some(name) => format!("[{}]", name),
+                        None => operand.to_string(),
+                    }
+                }
+                _ => operand.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if operands.is_empty() {
+            self.instruction.mnemonic().to_string()
+        } else {
+            format!("{} {}", self.instruction.mnemonic(), operands)
+        }
+    }
+}
+
+impl fmt::Display for Decoded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.instruction.mnemonic())?;
+
+        for (i, operand) in self.operands.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+
+            write!(f, "{}{}", separator, operand)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    Instruction(Decoded),
+    Data { address: usize, value: i64 },
+}
+
+impl Line {
+    pub fn address(&self) -> usize {
+        match self {
+            Line::Instruction(decoded) => decoded.address,
+            Line::Data { address, .. } => *address,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            Line::Instruction(decoded) => decoded.width(),
+            Line::Data { .. } => 1,
+        }
+    }
+}
+
+// A linear sweep from address zero: anything that doesn't decode is shown as
+// a single data cell and decoding carries on from the next address.
+pub fn disassemble(code: &[i64]) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut address = 0;
+
+    while address < code.len() {
+        let line = match Decoded::at(code, address) {
+            Some(decoded) => Line::Instruction(decoded),
+            None => Line::Data {
+                address,
+                value: code[address],
+            },
+        };
+
+        address += line.width();
+        lines.push(line);
+    }
+
+    lines
+}
+
+pub fn listing(code: &[i64]) -> String {
+    listing_with_symbols(code, &Symbols::new())
+}
+
+// Names operands that refer to a symbol, and notes which symbols start in
+// each line's cells.
+pub fn listing_with_symbols(code: &[i64], symbols: &Symbols) -> String {
+    disassemble(code)
+        .iter()
+        .map(|line| {
+            let start = line.address();
+            let raw = code[start..start + line.width()]
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            let text = match line {
+                Line::Instruction(decoded) => decoded.display_with(symbols),
+                Line::Data { .. } => "data".to_string(),
+            };
+
+            let names = symbols.starting_in(start, start + line.width());
+
+            if names.is_empty() {
+                format!("{:04}  {:<24} {}\n", start, raw, text)
+            } else {
+                format!(
+                    "{:04}  {:<24} {:<24} # {}\n",
+                    start,
+                    raw,
+                    text,
+                    names.join(", ")
+                )
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_operands_with_their_modes() {
+        let decoded = Decoded::at(&[1002, 4, 3, 4, 33], 0).unwrap();
+
+        assert_eq!(decoded.to_string(), "mul [4], 3, [4]");
+        assert_eq!(decoded.next(), 4);
+        assert_eq!(decoded.write_address(), Some(4));
+    }
+
+    #[test]
+    fn does_not_decode_past_the_end_of_memory() {
+        assert_eq!(Decoded::at(&[1, 0, 0], 0), None);
+        assert_eq!(Decoded::at(&[42], 0), None);
+    }
+
+    #[test]
+    fn names_operands_and_notes_symbols_in_listings() {
+        let symbols = "noun 1\nverb 2\ntotal 9".parse::<Symbols>().unwrap();
+
+        assert_eq!(
+            listing_with_symbols(&[1, 9, 10, 3, 99, 0, 0, 0, 0, 30, 40], &symbols),
+            [
+                "0000  1,9,10,3                 add [total], [10], [3]   # noun, verb",
+                "0004  99                       halt",
+                "0005  0                        data",
+                "0006  0                        data",
+                "0007  0                        data",
+                "0008  0                        data",
+                "0009  30                       data                     # total",
+                "0010  40                       data",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn lists_instructions_and_data() {
+        assert_eq!(
+            listing(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]),
+            [
+                "0000  1,9,10,3                 add [9], [10], [3]",
+                "0004  2,3,11,0                 mul [3], [11], [0]",
+                "0008  99                       halt",
+                "0009  30                       data",
+                "0010  40                       data",
+                "0011  50                       data",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+}
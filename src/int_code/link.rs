@@ -0,0 +1,435 @@
+use super::{Instruction, Mode, Program};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+// A piece of code that can be placed anywhere. Every label it defines is a
+// symbol other fragments can refer to, and every cell holding an address is
+// listed in `relocations` so the linker can fix it up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    pub name: String,
+    pub code: Vec<i64>,
+    // Offsets into `code`.
+    pub symbols: BTreeMap<String, usize>,
+    pub relocations: Vec<Relocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    pub at: usize,
+    pub target: Target,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    // The cell holds an offset into its own fragment.
+    Local,
+    // The cell holds an offset from a symbol defined in any fragment.
+    Symbol(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkErrorKind {
+    UnknownMnemonic(String),
+    InvalidOperand(String),
+    WrongOperandCount(String),
+    DuplicateSymbol(String),
+    UndefinedSymbol(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkError {
+    pub kind: LinkErrorKind,
+    pub fragment: String,
+    // Set for errors in a fragment's assembly source.
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.fragment)?;
+
+        if let Some(line) = self.line {
+            write!(f, ", line {}", line)?;
+        }
+
+        match &self.kind {
+            LinkErrorKind::UnknownMnemonic(mnemonic) => {
+                write!(f, ": unknown mnemonic `{}`", mnemonic)
+            }
+            LinkErrorKind::InvalidOperand(operand) => write!(f, ": invalid operand `{}`", operand),
+            LinkErrorKind::WrongOperandCount(mnemonic) => {
+                write!(f, ": wrong number of operands for `{}`", mnemonic)
+            }
+            LinkErrorKind::DuplicateSymbol(symbol) => {
+                write!(f, ": `{}` is defined more than once", symbol)
+            }
+            LinkErrorKind::UndefinedSymbol(symbol) => write!(f, ": `{}` is never defined", symbol),
+        }
+    }
+}
+
+impl Error for LinkError {}
+
+// The opcode for each mnemonic the disassembler prints, with every mode
+// left as position.
+const MNEMONICS: [(&str, i64); 10] = [
+    ("add", 1),
+    ("mul", 2),
+    ("in", 3),
+    ("out", 4),
+    ("jnz", 5),
+    ("jz", 6),
+    ("lt", 7),
+    ("eq", 8),
+    ("arb", 9),
+    ("halt", 99),
+];
+
+impl Fragment {
+    // Assembles the same syntax the disassembler prints: `[x]` is position
+    // mode, `[rb+x]` relative and a bare `x` immediate. An address can be a
+    // number, or a label with an optional `+n` or `-n`. `data` lays out
+    // plain values, `name:` defines a label at the next cell and `#` starts
+    // a comment.
+    pub fn assemble(name: &str, source: &str) -> Result<Fragment, LinkError> {
+        let mut fragment = Fragment {
+            name: name.to_string(),
+            code: Vec::new(),
+            symbols: BTreeMap::new(),
+            relocations: Vec::new(),
+        };
+
+        // Cells referring to a label, resolved once every label is known.
+        let mut references = Vec::new();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let error = |kind| LinkError {
+                kind,
+                fragment: name.to_string(),
+                line: Some(line_index + 1),
+            };
+
+            let mut text = line.split('#').next().unwrap().trim();
+
+            while let Some(colon) = text.find(':') {
+                let label = text[..colon].trim();
+
+                if !is_symbol(label) {
+                    return Err(error(LinkErrorKind::InvalidOperand(label.to_string())));
+                }
+
+                if fragment
+                    .symbols
+                    .insert(label.to_string(), fragment.code.len())
+                    .is_some()
+                {
+                    return Err(error(LinkErrorKind::DuplicateSymbol(label.to_string())));
+                }
+
+                text = text[colon + 1..].trim();
+            }
+
+            if text.is_empty() {
+                continue;
+            }
+
+            let (mnemonic, rest) = text.split_at(text.find(' ').unwrap_or(text.len()));
+            let operands = rest
+                .split(',')
+                .map(str::trim)
+                .filter(|operand| !operand.is_empty())
+                .collect::<Vec<_>>();
+
+            let op_code = if mnemonic == "data" {
+                None
+            } else {
+                let op_code = MNEMONICS
+                    .iter()
+                    .find(|(m, _)| *m == mnemonic)
+                    .map(|&(_, op_code)| op_code)
+                    .ok_or_else(|| error(LinkErrorKind::UnknownMnemonic(mnemonic.to_string())))?;
+
+                if Instruction::from_i64(op_code).width() != operands.len() + 1 {
+                    return Err(error(LinkErrorKind::WrongOperandCount(
+                        mnemonic.to_string(),
+                    )));
+                }
+
+                fragment.code.push(op_code);
+                Some(fragment.code.len() - 1)
+            };
+
+            for (i, operand) in operands.iter().enumerate() {
+                let (mode, address) = parse_operand(operand)
+                    .ok_or_else(|| error(LinkErrorKind::InvalidOperand(operand.to_string())))?;
+
+                if op_code.is_none() && mode != Mode::Immediate {
+                    return Err(error(LinkErrorKind::InvalidOperand(operand.to_string())));
+                }
+
+                if let Some(at) = op_code {
+                    fragment.code[at] += mode_digit(mode) * 10_i64.pow(i as u32 + 2);
+                }
+
+                match address {
+                    Address::Number(value) => fragment.code.push(value),
+                    Address::Symbol(symbol, offset) => {
+                        references.push((fragment.code.len(), symbol));
+                        fragment.code.push(offset);
+                    }
+                }
+            }
+        }
+
+        for (at, symbol) in references {
+            let target = match fragment.symbols.get(&symbol) {
+                Some(&offset) => {
+                    fragment.code[at] += offset as i64;
+                    Target::Local
+                }
+                None => Target::Symbol(symbol),
+            };
+
+            fragment.relocations.push(Relocation { at, target });
+        }
+
+        Ok(fragment)
+    }
+}
+
+enum Address {
+    Number(i64),
+    Symbol(String, i64),
+}
+
+fn is_symbol(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+fn mode_digit(mode: Mode) -> i64 {
+    match mode {
+        Mode::Position => 0,
+        Mode::Immediate => 1,
+        Mode::Relative => 2,
+    }
+}
+
+fn parse_operand(operand: &str) -> Option<(Mode, Address)> {
+    let inner = operand
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'));
+
+    match inner {
+        Some(inner) if inner.starts_with("rb+") || inner.starts_with("rb-") => {
+            let offset = inner[2..].strip_prefix('+').unwrap_or(&inner[2..]);
+
+            Some((Mode::Relative, Address::Number(offset.parse().ok()?)))
+        }
+        Some(inner) => Some((Mode::Position, parse_address(inner.trim())?)),
+        None => Some((Mode::Immediate, parse_address(operand)?)),
+    }
+}
+
+fn parse_address(text: &str) -> Option<Address> {
+    if let Ok(value) = text.parse() {
+        return Some(Address::Number(value));
+    }
+
+    let split = text.find(['+', '-']).unwrap_or(text.len());
+    let symbol = text[..split].trim();
+
+    if !is_symbol(symbol) {
+        return None;
+    }
+
+    let offset = match text[split..].trim() {
+        "" => 0,
+        rest => {
+            let offset = rest[1..].trim().parse::<i64>().ok()?;
+
+            if rest.starts_with('-') {
+                -offset
+            } else {
+                offset
+            }
+        }
+    };
+
+    Some(Address::Symbol(symbol.to_string(), offset))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Linked {
+    pub program: Program,
+    // Every symbol, at its final address.
+    pub symbols: BTreeMap<String, usize>,
+}
+
+// Lays the fragments out one after another, in order, so the first one is
+// where the program starts, and fixes up every address.
+pub fn link(fragments: &[Fragment]) -> Result<Linked, LinkError> {
+    let mut symbols = BTreeMap::new();
+    let mut bases = Vec::new();
+    let mut base = 0;
+
+    for fragment in fragments {
+        for (symbol, offset) in &fragment.symbols {
+            if symbols.insert(symbol.clone(), base + offset).is_some() {
+                return Err(LinkError {
+                    kind: LinkErrorKind::DuplicateSymbol(symbol.clone()),
+                    fragment: fragment.name.clone(),
+                    line: None,
+                });
+            }
+        }
+
+        bases.push(base);
+        base += fragment.code.len();
+    }
+
+    let mut code = Vec::with_capacity(base);
+
+    for (fragment, &base) in fragments.iter().zip(&bases) {
+        let mut fragment_code = fragment.code.clone();
+
+        for relocation in &fragment.relocations {
+            let address = match &relocation.target {
+                Target::Local => base,
+                Target::Symbol(symbol) => *symbols.get(symbol).ok_or_else(|| LinkError {
+                    kind: LinkErrorKind::UndefinedSymbol(symbol.clone()),
+                    fragment: fragment.name.clone(),
+                    line: None,
+                })?,
+            };
+
+            fragment_code[relocation.at] += address as i64;
+        }
+
+        code.extend(fragment_code);
+    }
+
+    Ok(Linked {
+        program: Program::new(code),
+        symbols,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assemble(source: &str) -> Fragment {
+        Fragment::assemble("test", source).unwrap()
+    }
+
+    #[test]
+    fn assembles_what_the_disassembler_prints() {
+        let fragment = assemble("add [4], 3, [rb-2]\nmul [rb+1], -1, [0]\nhalt");
+
+        assert_eq!(fragment.code, vec![21001, 4, 3, -2, 1202, 1, -1, 0, 99]);
+        assert!(fragment.relocations.is_empty());
+    }
+
+    #[test]
+    fn records_relocations_for_labels() {
+        let fragment = assemble(
+            "
+            start:
+                in [value]
+                jz [value], done
+                out [value+1]
+            done: halt
+            value: data 7, other
+            ",
+        );
+
+        assert_eq!(fragment.code, vec![3, 8, 1006, 8, 7, 4, 9, 99, 7, 0]);
+        assert_eq!(fragment.symbols["start"], 0);
+        assert_eq!(fragment.symbols["done"], 7);
+        assert_eq!(fragment.symbols["value"], 8);
+        assert_eq!(
+            fragment.relocations,
+            vec![
+                Relocation {
+                    at: 1,
+                    target: Target::Local
+                },
+                Relocation {
+                    at: 3,
+                    target: Target::Local
+                },
+                Relocation {
+                    at: 4,
+                    target: Target::Local
+                },
+                Relocation {
+                    at: 6,
+                    target: Target::Local
+                },
+                Relocation {
+                    at: 9,
+                    target: Target::Symbol("other".to_string())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn links_fragments_and_fixes_up_addresses() {
+        let main = assemble("out [shared.value]\njnz 1, finish");
+        let shared = assemble("shared.value: data 42\nfinish: out [shared.value-0]\nhalt");
+
+        let linked = link(&[main, shared]).unwrap();
+
+        assert_eq!(linked.symbols["shared.value"], 5);
+        assert_eq!(linked.symbols["finish"], 6);
+        assert_eq!(linked.program.code, vec![4, 5, 1105, 1, 6, 42, 4, 5, 99]);
+        assert_eq!(linked.program.clone().run(vec![]), vec![42, 42]);
+    }
+
+    #[test]
+    fn reports_assembly_errors_with_lines() {
+        let error = |source| Fragment::assemble("broken", source).unwrap_err();
+
+        assert_eq!(
+            error("halt\nnop"),
+            LinkError {
+                kind: LinkErrorKind::UnknownMnemonic("nop".to_string()),
+                fragment: "broken".to_string(),
+                line: Some(2),
+            }
+        );
+        assert_eq!(
+            error("add 1, 2").kind,
+            LinkErrorKind::WrongOperandCount("add".to_string())
+        );
+        assert_eq!(
+            error("out [x y]").kind,
+            LinkErrorKind::InvalidOperand("[x y]".to_string())
+        );
+        assert_eq!(
+            error("a: halt\na: halt").to_string(),
+            "broken, line 2: `a` is defined more than once"
+        );
+    }
+
+    #[test]
+    fn reports_symbols_the_linker_cannot_resolve() {
+        let first = assemble("x: jnz 1, missing");
+        let second = assemble("x: halt");
+
+        assert_eq!(
+            link(std::slice::from_ref(&first)).unwrap_err().kind,
+            LinkErrorKind::UndefinedSymbol("missing".to_string())
+        );
+        assert_eq!(
+            link(&[first, second]).unwrap_err().kind,
+            LinkErrorKind::DuplicateSymbol("x".to_string())
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+
+const PAGE_SIZE: usize = 64;
+
+// Far more than any program needs, while small enough that the page table
+// and a flat copy of the memory always fit. Writing past it panics rather
+// than running the process out of memory.
+const MAX_LEN: usize = 1 << 24;
+
+type Page = [i64; PAGE_SIZE];
+
+// A program's memory: a read-only base image shared by every copy of the
+// program, plus private copies of only the pages that have been written to.
+//
+// Cloning just bumps two reference counts. The first write after a clone
+// copies the page table and the page being written; the rest stay shared.
+//
+// Reading past the end gives zero, and writing past it grows the memory, up
+// to `MAX_LEN` cells.
+#[derive(Clone)]
+pub struct Memory {
+    base: Rc<[i64]>,
+    pages: Rc<Vec<Option<Rc<Page>>>>,
+    len: usize,
+}
+
+impl Memory {
+    pub fn new(code: Vec<i64>) -> Memory {
+        Memory::with_base(code.into())
+    }
+
+    pub fn with_base(base: Rc<[i64]>) -> Memory {
+        let page_count = base.len().div_ceil(PAGE_SIZE);
+
+        Memory {
+            len: base.len(),
+            base,
+            pages: Rc::new(vec![None; page_count]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, address: usize) -> Option<i64> {
+        if address < self.len() {
+            Some(self[address])
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i64> + '_ {
+        (0..self.len()).map(move |address| self[address])
+    }
+
+    pub fn to_vec(&self) -> Vec<i64> {
+        self.iter().collect()
+    }
+
+    // The memory as it was loaded, before anything wrote to it.
+    pub fn base(&self) -> &Rc<[i64]> {
+        &self.base
+    }
+
+    pub fn dirty_pages(&self) -> usize {
+        self.pages.iter().filter(|page| page.is_some()).count()
+    }
+
+    // Writes back a flat copy of the memory, only touching the cells that
+    // actually differ so untouched pages stay shared.
+    pub fn update_from(&mut self, cells: &[i64]) {
+        for (address, &value) in cells.iter().enumerate() {
+            if self[address] != value {
+                self[address] = value;
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Memory::with_base(self.base.clone());
+    }
+
+    fn page(&self, address: usize) -> Option<&Page> {
+        self.pages.get(address / PAGE_SIZE)?.as_deref()
+    }
+}
+
+impl Index<usize> for Memory {
+    type Output = i64;
+
+    fn index(&self, address: usize) -> &i64 {
+        match self.page(address) {
+            Some(page) => &page[address % PAGE_SIZE],
+            None => self.base.get(address).unwrap_or(&0),
+        }
+    }
+}
+
+impl IndexMut<usize> for Memory {
+    fn index_mut(&mut self, address: usize) -> &mut i64 {
+        assert!(
+            address < MAX_LEN,
+            "Write to {} is past the memory limit of {} cells",
+            address,
+            MAX_LEN
+        );
+
+        if address >= self.len {
+            self.len = address + 1;
+        }
+
+        let base = &self.base;
+        let pages = Rc::make_mut(&mut self.pages);
+
+        if pages.len() <= address / PAGE_SIZE {
+            pages.resize(address / PAGE_SIZE + 1, None);
+        }
+
+        let page = pages[address / PAGE_SIZE].get_or_insert_with(|| {
+            let start = (address / PAGE_SIZE * PAGE_SIZE).min(base.len());
+            let cells = &base[start..(start + PAGE_SIZE).min(base.len())];
+
+            let mut page = [0; PAGE_SIZE];
+            page[..cells.len()].copy_from_slice(cells);
+
+            Rc::new(page)
+        });
+
+        &mut Rc::make_mut(page)[address % PAGE_SIZE]
+    }
+}
+
+impl From<Vec<i64>> for Memory {
+    fn from(code: Vec<i64>) -> Memory {
+        Memory::new(code)
+    }
+}
+
+impl PartialEq for Memory {
+    fn eq(&self, other: &Memory) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for Memory {}
+
+impl PartialEq<Vec<i64>> for Memory {
+    fn eq(&self, other: &Vec<i64>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+impl fmt::Debug for Memory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_everything_until_written() {
+        let original = Memory::new((0..200).collect());
+        let mut fork = original.clone();
+
+        assert!(Rc::ptr_eq(&original.pages, &fork.pages));
+
+        fork[70] = -1;
+
+        assert_eq!(fork[70], -1);
+        assert_eq!(original[70], 70);
+        assert_eq!(fork.dirty_pages(), 1);
+        assert_eq!(original.dirty_pages(), 0);
+        assert!(Rc::ptr_eq(original.base(), fork.base()));
+    }
+
+    #[test]
+    fn forks_of_a_fork_only_copy_the_pages_they_write() {
+        let mut parent = Memory::new(vec![0; 200]);
+        parent[0] = 1;
+
+        let mut child = parent.clone();
+        child[150] = 2;
+
+        assert!(Rc::ptr_eq(
+            parent.pages[0].as_ref().unwrap(),
+            child.pages[0].as_ref().unwrap()
+        ));
+        assert_eq!(parent.to_vec()[150], 0);
+        assert_eq!(child.to_vec()[..2], [1, 0]);
+    }
+
+    #[test]
+    fn resets_to_the_base_image() {
+        let mut memory = Memory::new(vec![1, 2, 3]);
+        memory[1] = 7;
+
+        assert_eq!(memory, vec![1, 7, 3]);
+
+        memory.reset();
+
+        assert_eq!(memory, vec![1, 2, 3]);
+        assert_eq!(memory.dirty_pages(), 0);
+    }
+
+    #[test]
+    fn reads_past_the_end_as_zero_and_grows_when_written_there() {
+        let mut memory = Memory::new(vec![1, 2, 3]);
+
+        assert_eq!(memory[1000], 0);
+        assert_eq!(memory.len(), 3);
+
+        memory[1000] = 4;
+
+        assert_eq!(memory.len(), 1001);
+        assert_eq!(memory[1000], 4);
+        assert_eq!(memory[500], 0);
+        assert_eq!(memory.dirty_pages(), 1);
+
+        memory.reset();
+
+        assert_eq!(memory, vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Write to 1152921504606846976 is past the memory limit")]
+    fn refuses_to_grow_past_the_limit() {
+        let mut memory = Memory::new(vec![1, 2, 3]);
+
+        memory[1 << 60] = 1;
+    }
+}
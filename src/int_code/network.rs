@@ -0,0 +1,225 @@
+use super::{Program, Step};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::iter;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MachineId(usize);
+
+impl fmt::Display for MachineId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Halted,
+    // Every machine that hadn't halted was waiting for input nobody will send.
+    Deadlocked(Vec<MachineId>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub status: Status,
+    pub sinks: BTreeMap<MachineId, Vec<i64>>,
+}
+
+#[derive(Debug, Clone)]
+struct Machine {
+    program: Program,
+    inputs: VecDeque<i64>,
+    outputs: Vec<i64>,
+    targets: Vec<MachineId>,
+    halted: bool,
+}
+
+// A set of programs whose outputs are wired to each other's inputs. Each
+// output is copied to every machine it's connected to, and cycles are fine.
+#[derive(Debug, Clone, Default)]
+pub struct Network {
+    machines: Vec<Machine>,
+    sinks: Vec<MachineId>,
+}
+
+impl Network {
+    pub fn new() -> Network {
+        Network::default()
+    }
+
+    pub fn add_machine<I>(&mut self, program: Program, inputs: I) -> MachineId
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        self.machines.push(Machine {
+            program,
+            inputs: inputs.into_iter().collect(),
+            outputs: Vec::new(),
+            targets: Vec::new(),
+            halted: false,
+        });
+
+        MachineId(self.machines.len() - 1)
+    }
+
+    pub fn connect(&mut self, from: MachineId, to: MachineId) {
+        self.machines[from.0].targets.push(to);
+    }
+
+    pub fn push_input(&mut self, to: MachineId, input: i64) {
+        self.machines[to.0].inputs.push_back(input);
+    }
+
+    // Sinks are the machines whose outputs are reported at the end. Without
+    // any, every machine that isn't connected onwards is treated as one.
+    pub fn mark_sink(&mut self, id: MachineId) {
+        self.sinks.push(id);
+    }
+
+    pub fn outputs(&self, id: MachineId) -> &[i64] {
+        &self.machines[id.0].outputs
+    }
+
+    pub fn run(&mut self) -> Outcome {
+        loop {
+            let mut progressed = false;
+
+            for i in 0..self.machines.len() {
+                progressed |= self.run_machine(i);
+            }
+
+            if !progressed {
+                break;
+            }
+        }
+
+        let blocked = self
+            .ids()
+            .filter(|id| !self.machines[id.0].halted)
+            .collect::<Vec<_>>();
+
+        let status = if blocked.is_empty() {
+            Status::Halted
+        } else {
+            Status::Deadlocked(blocked)
+        };
+
+        Outcome {
+            status,
+            sinks: self
+                .sink_ids()
+                .into_iter()
+                .map(|id| (id, self.outputs(id).to_vec()))
+                .collect(),
+        }
+    }
+
+    // Runs one machine until it halts or runs out of input, and returns
+    // whether it did anything.
+    fn run_machine(&mut self, i: usize) -> bool {
+        let mut progressed = false;
+
+        while !self.machines[i].halted {
+            let Machine {
+                program, inputs, ..
+            } = &mut self.machines[i];
+
+            let step = program.step(&mut iter::from_fn(|| inputs.pop_front()));
+
+            match step {
+                Step::Executed => {}
+                Step::Output(output) => {
+                    self.machines[i].outputs.push(output);
+
+                    for target in self.machines[i].targets.clone() {
+                        self.push_input(target, output);
+                    }
+                }
+                Step::AwaitingInput => return progressed,
+                Step::Halted => self.machines[i].halted = true,
+            }
+
+            progressed = true;
+        }
+
+        progressed
+    }
+
+    fn ids(&self) -> impl Iterator<Item = MachineId> {
+        (0..self.machines.len()).map(MachineId)
+    }
+
+    fn sink_ids(&self) -> Vec<MachineId> {
+        if !self.sinks.is_empty() {
+            return self.sinks.clone();
+        }
+
+        self.ids()
+            .filter(|id| self.machines[id.0].targets.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn echo() -> Program {
+        "3,0,4,0,99".parse::<Program>().unwrap()
+    }
+
+    #[test]
+    fn copies_outputs_to_every_connected_machine() {
+        let mut network = Network::new();
+
+        let a = network.add_machine(echo(), vec![5]);
+        let b = network.add_machine(echo(), vec![]);
+        let c = network.add_machine(echo(), vec![]);
+
+        network.connect(a, b);
+        network.connect(a, c);
+
+        let outcome = network.run();
+
+        assert_eq!(outcome.status, Status::Halted);
+        assert_eq!(
+            outcome.sinks.into_iter().collect::<Vec<_>>(),
+            vec![(b, vec![5]), (c, vec![5])]
+        );
+    }
+
+    #[test]
+    fn reports_a_deadlock_when_machines_wait_on_each_other() {
+        let mut network = Network::new();
+
+        let a = network.add_machine(echo(), vec![]);
+        let b = network.add_machine(echo(), vec![]);
+        let c = network.add_machine(echo(), vec![1]);
+
+        network.connect(a, b);
+        network.connect(b, a);
+        network.mark_sink(c);
+
+        let outcome = network.run();
+
+        assert_eq!(outcome.status, Status::Deadlocked(vec![a, b]));
+        assert_eq!(outcome.sinks[&c], vec![1]);
+    }
+
+    #[test]
+    fn runs_a_chain_in_any_declaration_order() {
+        let mut network = Network::new();
+        let double = "3,9,1002,9,2,9,4,9,99,0".parse::<Program>().unwrap();
+
+        let last = network.add_machine(double.clone(), vec![]);
+        let middle = network.add_machine(double.clone(), vec![]);
+        let first = network.add_machine(double, vec![3]);
+
+        network.connect(first, middle);
+        network.connect(middle, last);
+        network.mark_sink(last);
+
+        assert_eq!(network.run().sinks[&last], vec![24]);
+        assert_eq!(network.outputs(middle), &[12]);
+    }
+}
@@ -0,0 +1,601 @@
+use super::disassemble::{Decoded, Operand};
+use super::{Instruction, Mode, Program};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rewrite {
+    pub pass: usize,
+    pub address: usize,
+    pub before: String,
+    pub after: Option<String>,
+}
+
+impl fmt::Display for Rewrite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let after = self.after.as_deref().unwrap_or("(removed)");
+
+        write!(
+            f,
+            "pass {}, {:04}: {} -> {}",
+            self.pass, self.address, self.before, after
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimized {
+    pub program: Program,
+    pub rewrites: Vec<Rewrite>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub inputs: Vec<i64>,
+    pub expected: Vec<i64>,
+    pub actual: Vec<i64>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "inputs {:?} gave {:?}, expected {:?}",
+            self.inputs, self.actual, self.expected
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    Remove,
+    Replace(Vec<i64>),
+}
+
+// Rewrites no-ops and constant expressions until nothing else changes.
+//
+// Only instructions whose cells no reachable instruction reads or writes are
+// touched. Removing instructions moves everything after them, so that only
+// happens when every jump target is known ahead of time and no instruction
+// overwrites code; otherwise rewrites keep the same width.
+pub fn optimize(program: &Program) -> Optimized {
+    let mut code = program.code.to_vec();
+    let mut rewrites = Vec::new();
+
+    for pass in 1.. {
+        let (next, pass_rewrites) = optimize_pass(&code, pass);
+
+        if pass_rewrites.is_empty() {
+            break;
+        }
+
+        code = next;
+        rewrites.extend(pass_rewrites);
+    }
+
+    Optimized {
+        program: Program::new(code),
+        rewrites,
+    }
+}
+
+// Runs both programs on each set of inputs and reports the first difference
+// in their outputs.
+pub fn verify(
+    original: &Program,
+    optimized: &Program,
+    input_sets: &[Vec<i64>],
+) -> Result<(), Mismatch> {
+    for inputs in input_sets {
+        let expected = original.clone().run(inputs.clone());
+        let actual = optimized.clone().run(inputs.clone());
+
+        if expected != actual {
+            return Err(Mismatch {
+                inputs: inputs.clone(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn optimize_pass(code: &[i64], pass: usize) -> (Vec<i64>, Vec<Rewrite>) {
+    // Anything not found by following the control flow could read or write
+    // any cell, so there is nothing that is provably safe to change.
+    let reachable = match follow(code) {
+        Some(reachable) => reachable,
+        None => return (code.to_vec(), vec![]),
+    };
+
+    let written = reachable
+        .iter()
+        .filter_map(|decoded| decoded.write_address())
+        .collect::<BTreeSet<_>>();
+
+    let read = reachable
+        .iter()
+        .flat_map(|decoded| decoded.operands.iter())
+        .filter(|operand| operand.mode == Mode::Position)
+        .map(|operand| operand.value as usize);
+
+    let touched = written.iter().copied().chain(read).collect::<BTreeSet<_>>();
+
+    let can_move_code = reachable.iter().all(|decoded| {
+        let static_target = match decoded.instruction {
+            Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_) => {
+                decoded.operands[1].mode == Mode::Immediate
+            }
+            _ => true,
+        };
+
+        static_target && !cells(decoded).any(|cell| written.contains(&cell))
+    });
+
+    let mut actions = BTreeMap::new();
+
+    for decoded in &reachable {
+        if cells(decoded).any(|cell| touched.contains(&cell)) {
+            continue;
+        }
+
+        match action(decoded) {
+            Some(Action::Remove) if !can_move_code => {}
+            Some(action) => {
+                actions.insert(decoded.address, (decoded.clone(), action));
+            }
+            None => {}
+        }
+    }
+
+    let rewrites = actions
+        .values()
+        .map(|(decoded, action)| Rewrite {
+            pass,
+            address: decoded.address,
+            before: decoded.to_string(),
+            after: match action {
+                Action::Remove => None,
+                Action::Replace(cells) => Decoded::at(cells, 0).map(|d| d.to_string()),
+            },
+        })
+        .collect();
+
+    (rebuild(code, &reachable, &actions, can_move_code), rewrites)
+}
+
+// Every instruction reachable from the start, found without tracking what
+// memory holds from one visit to the next, as the checker does. A jump is
+// only resolved through a cell that nothing reachable writes to, starting
+// from the guess that nothing is written and following more paths as
+// writes turn up. Gives up on jumps it can't resolve, relative parameters,
+// code that is modified and anything that doesn't decode.
+fn follow(code: &[i64]) -> Option<Vec<Decoded>> {
+    let mut written = BTreeSet::new();
+
+    loop {
+        let reachable = follow_with(code, &written)?;
+        let writes = reachable
+            .iter()
+            .filter_map(|decoded| decoded.write_address())
+            .collect::<BTreeSet<_>>();
+
+        if reachable
+            .iter()
+            .any(|decoded| cells(decoded).any(|cell| writes.contains(&cell)))
+        {
+            return None;
+        }
+
+        if writes.is_subset(&written) {
+            return Some(reachable);
+        }
+
+        written.extend(writes);
+    }
+}
+
+fn follow_with(code: &[i64], written: &BTreeSet<usize>) -> Option<Vec<Decoded>> {
+    let value = |operand: Operand| match operand.mode {
+        Mode::Immediate => Some(operand.value),
+        Mode::Position => usize::try_from(operand.value)
+            .ok()
+            .filter(|cell| !written.contains(cell))
+            .and_then(|cell| code.get(cell).copied()),
+        Mode::Relative => None,
+    };
+
+    let mut reachable = BTreeMap::new();
+    let mut pending = vec![0];
+
+    while let Some(address) = pending.pop() {
+        if reachable.contains_key(&address) {
+            continue;
+        }
+
+        let decoded = Decoded::at(code, address)?;
+
+        if decoded
+            .operands
+            .iter()
+            .any(|operand| operand.mode == Mode::Relative)
+        {
+            return None;
+        }
+
+        let taken_when = match decoded.instruction {
+            Instruction::JumpIfTrue(_) => true,
+            Instruction::JumpIfFalse(_) => false,
+            Instruction::Halt => {
+                reachable.insert(address, decoded);
+                continue;
+            }
+            _ => {
+                pending.push(decoded.next());
+                reachable.insert(address, decoded);
+                continue;
+            }
+        };
+
+        let condition = value(decoded.operands[0]);
+        let always_taken = condition.is_some_and(|x| (x != 0) == taken_when);
+        let never_taken = condition.is_some_and(|x| (x != 0) != taken_when);
+
+        if !always_taken {
+            pending.push(decoded.next());
+        }
+
+        if !never_taken {
+            match value(decoded.operands[1]) {
+                Some(target) if target >= 0 && (target as usize) < code.len() => {
+                    pending.push(target as usize)
+                }
+                _ => return None,
+            }
+        }
+
+        reachable.insert(address, decoded);
+    }
+
+    Some(reachable.into_values().collect())
+}
+
+fn action(decoded: &Decoded) -> Option<Action> {
+    let args = &decoded.operands;
+
+    let action = match decoded.instruction {
+        Instruction::Add(_) | Instruction::Multiply(_) => {
+            let is_multiply = matches!(decoded.instruction, Instruction::Multiply(_));
+            let identity = if is_multiply { 1 } else { 0 };
+
+            match (constant(args[0]), constant(args[1])) {
+                (Some(x), Some(y)) => store(decoded, evaluate(decoded, x, y)?),
+                (Some(0), _) | (_, Some(0)) if is_multiply => store(decoded, 0),
+                (Some(x), _) if x == identity => copy(decoded, args[1]),
+                (_, Some(y)) if y == identity => copy(decoded, args[0]),
+                _ => return None,
+            }
+        }
+
+        Instruction::LessThan(_) | Instruction::Equals(_) => {
+            let same_cell = args[0] == args[1] && args[0].mode == Mode::Position;
+
+            match (constant(args[0]), constant(args[1])) {
+                (Some(x), Some(y)) => store(decoded, evaluate(decoded, x, y)?),
+                _ if same_cell => store(decoded, evaluate(decoded, 0, 0)?),
+                _ => return None,
+            }
+        }
+
+        Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_) => {
+            let jumps_when_non_zero = matches!(decoded.instruction, Instruction::JumpIfTrue(_));
+
+            match (constant(args[0]), constant(args[1])) {
+                (_, Some(target)) if target == decoded.next() as i64 => Action::Remove,
+                (Some(x), _) if (x != 0) != jumps_when_non_zero => Action::Remove,
+                _ => return None,
+            }
+        }
+
+        _ => return None,
+    };
+
+    match &action {
+        Action::Replace(cells) if *cells == encode(decoded) => None,
+        _ => Some(action),
+    }
+}
+
+// `None` when the result overflows, which is left for the program to do.
+fn evaluate(decoded: &Decoded, x: i64, y: i64) -> Option<i64> {
+    match decoded.instruction {
+        Instruction::Add(_) => x.checked_add(y),
+        Instruction::Multiply(_) => x.checked_mul(y),
+        Instruction::LessThan(_) => Some((x < y) as i64),
+        _ => Some((x == y) as i64),
+    }
+}
+
+fn store(decoded: &Decoded, value: i64) -> Action {
+    copy(
+        decoded,
+        Operand {
+            mode: Mode::Immediate,
+            value,
+        },
+    )
+}
+
+// `add from, 0, dest`, or nothing at all when that copies a cell to itself.
+fn copy(decoded: &Decoded, from: Operand) -> Action {
+    let dest = decoded.operands[2];
+
+    if from == dest && from.mode == Mode::Position {
+        return Action::Remove;
+    }
+
+    let op_code = 1 + 100 * mode_digit(from.mode) + 1000 + 10000 * mode_digit(dest.mode);
+
+    Action::Replace(vec![op_code, from.value, 0, dest.value])
+}
+
+fn encode(decoded: &Decoded) -> Vec<i64> {
+    let op_code = match decoded.instruction {
+        Instruction::Add(_) => 1,
+        Instruction::Multiply(_) => 2,
+        Instruction::ReadInput(_) => 3,
+        Instruction::WriteOutput(_) => 4,
+        Instruction::JumpIfTrue(_) => 5,
+        Instruction::JumpIfFalse(_) => 6,
+        Instruction::LessThan(_) => 7,
+        Instruction::Equals(_) => 8,
+        Instruction::AdjustRelativeBase(_) => 9,
+        Instruction::Halt => 99,
+    };
+
+    let modes = decoded
+        .operands
+        .iter()
+        .zip(&[100, 1000, 10000])
+        .map(|(operand, unit)| unit * mode_digit(operand.mode))
+        .sum::<i64>();
+
+    std::iter::once(op_code + modes)
+        .chain(decoded.operands.iter().map(|operand| operand.value))
+        .collect()
+}
+
+fn mode_digit(mode: Mode) -> i64 {
+    match mode {
+        Mode::Position => 0,
+        Mode::Immediate => 1,
+        Mode::Relative => 2,
+    }
+}
+
+fn constant(operand: Operand) -> Option<i64> {
+    match operand.mode {
+        Mode::Immediate => Some(operand.value),
+        Mode::Position | Mode::Relative => None,
+    }
+}
+
+fn cells(decoded: &Decoded) -> impl Iterator<Item = usize> {
+    decoded.address..decoded.next()
+}
+
+fn rebuild(
+    code: &[i64],
+    reachable: &[Decoded],
+    actions: &BTreeMap<usize, (Decoded, Action)>,
+    can_move_code: bool,
+) -> Vec<i64> {
+    let mut patched = code.to_vec();
+
+    for (&address, (decoded, action)) in actions {
+        if let Action::Replace(cells) = action {
+            patched[address..decoded.next()].copy_from_slice(cells);
+        }
+    }
+
+    if !can_move_code {
+        return patched;
+    }
+
+    let removed = actions
+        .values()
+        .filter(|(_, action)| *action == Action::Remove)
+        .flat_map(|(decoded, _)| cells(decoded))
+        .collect::<BTreeSet<_>>();
+
+    // Where each old address ends up. A removed instruction does nothing, so
+    // anything that pointed at it can point at whatever followed it instead.
+    let mut new_address = vec![0; code.len() + 1];
+    for address in (0..code.len()).rev() {
+        new_address[address] = if removed.contains(&address) {
+            new_address[address + 1]
+        } else {
+            address - removed.range(..address).count()
+        };
+    }
+    new_address[code.len()] = code.len() - removed.len();
+
+    let relocate = |address: i64| -> i64 {
+        match address {
+            a if a < 0 => a,
+            a if (a as usize) < code.len() => new_address[a as usize] as i64,
+            a => a - removed.len() as i64,
+        }
+    };
+
+    for decoded in reachable {
+        if removed.contains(&decoded.address) {
+            continue;
+        }
+
+        let current = Decoded::at(&patched, decoded.address).unwrap();
+
+        for (i, operand) in current.operands.iter().enumerate() {
+            let is_jump_target = i == 1
+                && matches!(
+                    current.instruction,
+                    Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_)
+                );
+
+            if operand.mode == Mode::Position || is_jump_target {
+                patched[decoded.address + 1 + i] = relocate(operand.value);
+            }
+        }
+    }
+
+    patched
+        .into_iter()
+        .enumerate()
+        .filter(|(address, _)| !removed.contains(address))
+        .map(|(_, value)| value)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    fn optimized(input: &str) -> Optimized {
+        optimize(&input.parse::<Program>().unwrap())
+    }
+
+    #[test]
+    fn removes_no_ops_and_moves_the_code_after_them_up() {
+        // in [13]; mul [13], 1, [13]; jnz 1, 9; out [13]; halt
+        let original = "3,13,1002,13,1,13,1105,1,9,4,13,99,0,0"
+            .parse::<Program>()
+            .unwrap();
+
+        let result = optimize(&original);
+
+        assert_eq!(result.program.code, vec![3, 6, 4, 6, 99, 0, 0]);
+        assert_eq!(
+            result
+                .rewrites
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "pass 1, 0002: mul [13], 1, [13] -> (removed)",
+                "pass 1, 0006: jnz 1, 9 -> (removed)"
+            ]
+        );
+        assert_eq!(
+            verify(&original, &result.program, &[vec![4], vec![-7]]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn relocates_jump_targets_past_removed_code() {
+        // in [20]; add [20], 0, [20]; jz [20], 14; out [20]; jnz 1, 16;
+        // halt; 0; out 7; halt
+        let original = "3,20,1001,20,0,20,1006,20,14,4,20,1105,1,16,99,0,104,7,99,0,0"
+            .parse::<Program>()
+            .unwrap();
+
+        let result = optimize(&original);
+
+        assert!(result.program.code.len() < original.code.len());
+        assert_eq!(
+            verify(&original, &result.program, &[vec![0], vec![1], vec![5]]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn folds_constant_comparisons() {
+        let result = optimized("1107,3,5,9,1108,3,5,10,99,0,0");
+
+        assert_eq!(
+            result.program.code,
+            vec![1101, 1, 0, 9, 1101, 0, 0, 10, 99, 0, 0]
+        );
+    }
+
+    #[test]
+    fn leaves_cells_that_are_read_as_data_alone() {
+        // The no-op at 4 is also printed by the output at 8.
+        let mut result = optimized("1101,0,0,11,1002,11,1,11,4,4,99,0");
+
+        assert_eq!(result.program.code.to_vec()[4..8], [1002, 11, 1, 11]);
+        assert_eq!(result.program.run(vec![]), vec![1002]);
+    }
+
+    #[test]
+    fn only_rewrites_in_place_when_jumps_go_through_memory() {
+        // lt 3, 5, [13]; jnz [13], [14]; out 5; halt; out 6; halt; 0; 10
+        let original = "1107,3,5,13,5,13,14,104,5,99,104,6,99,0,10"
+            .parse::<Program>()
+            .unwrap();
+
+        let result = optimize(&original);
+
+        assert_eq!(result.program.code.to_vec()[0..4], [1101, 1, 0, 13]);
+        assert_eq!(result.program.code.len(), original.code.len());
+        assert_eq!(verify(&original, &result.program, &[vec![]]), Ok(()));
+    }
+
+    #[test]
+    fn leaves_programs_it_cannot_fully_follow_alone() {
+        // The day five program only decodes once its input has been added
+        // to the instruction at 6.
+        let original = read_to_string("src/five.txt")
+            .unwrap()
+            .parse::<Program>()
+            .unwrap();
+
+        let result = optimize(&original);
+
+        assert_eq!(result.rewrites, vec![]);
+        assert_eq!(result.program.code, original.code);
+    }
+
+    #[test]
+    fn verification_reports_the_first_difference() {
+        let original = "3,9,4,9,99,0,0,0,0,0".parse::<Program>().unwrap();
+        let broken = "3,9,104,0,99,0,0,0,0,0".parse::<Program>().unwrap();
+
+        let mismatch = verify(&original, &broken, &[vec![0], vec![3]]).unwrap_err();
+
+        assert_eq!(mismatch.to_string(), "inputs [3] gave [0], expected [3]");
+    }
+
+    #[test]
+    fn keeps_code_reached_only_when_a_cell_has_changed() {
+        // The jz at 4 jumps to 12 while [23] is 0, and falls through to the
+        // out at 7 once the code at 12 has set it to 1 and jumped back.
+        let original = "1101,0,0,23,1006,23,12,4,24,99,0,0,1002,24,1,24,1101,1,0,23,1105,1,4,0,42"
+            .parse::<Program>()
+            .unwrap();
+
+        let result = optimize(&original);
+
+        assert_eq!(original.clone().run(vec![]), vec![42]);
+        assert_eq!(verify(&original, &result.program, &[vec![]]), Ok(()));
+    }
+
+    #[test]
+    fn leaves_arithmetic_that_overflows_alone() {
+        for code in [
+            vec![1101, i64::MAX, 1, 5, 99, 0],
+            vec![1102, i64::MAX, 2, 5, 99, 0],
+        ] {
+            let original = Program::new(code);
+
+            let result = optimize(&original);
+
+            assert_eq!(result.rewrites, vec![]);
+            assert_eq!(result.program.code, original.code);
+        }
+    }
+}
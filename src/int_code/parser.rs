@@ -0,0 +1,156 @@
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    InvalidNumber(ParseIntError),
+    MissingNumber,
+    NoCode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub line: usize,
+    pub column: usize,
+    pub token_index: usize,
+    pub token: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::InvalidNumber(err) => write!(
+                f,
+                "line {}, column {}: invalid number `{}` at token {} ({})",
+                self.line, self.column, self.token, self.token_index, err
+            ),
+            ParseErrorKind::MissingNumber => write!(
+                f,
+                "line {}, column {}: missing number at token {}",
+                self.line, self.column, self.token_index
+            ),
+            ParseErrorKind::NoCode => write!(f, "no Intcode found in input"),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ParseErrorKind::InvalidNumber(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// Accepts the comma separated text from the puzzle inputs, along with line
+// breaks between values, whitespace around them, trailing commas and `#`
+// comments running to the end of a line.
+pub fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    let mut code = Vec::new();
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line = line.split('#').next().unwrap();
+        let tokens = line.split(',').collect::<Vec<_>>();
+        let mut column = 1;
+
+        for (i, raw_token) in tokens.iter().enumerate() {
+            let token = raw_token.trim();
+            let leading_whitespace = raw_token.len() - raw_token.trim_start().len();
+            let token_column = column + raw_token[..leading_whitespace].chars().count();
+
+            column += raw_token.chars().count() + 1;
+
+            let is_last_on_line = i == tokens.len() - 1;
+
+            if token.is_empty() && is_last_on_line {
+                continue;
+            }
+
+            let error = |kind| ParseError {
+                kind,
+                line: line_index + 1,
+                column: token_column,
+                token_index: code.len(),
+                token: token.to_string(),
+            };
+
+            if token.is_empty() {
+                return Err(error(ParseErrorKind::MissingNumber));
+            }
+
+            match token.parse::<i64>() {
+                Ok(value) => code.push(value),
+                Err(err) => return Err(error(ParseErrorKind::InvalidNumber(err))),
+            }
+        }
+    }
+
+    if code.is_empty() {
+        return Err(ParseError {
+            kind: ParseErrorKind::NoCode,
+            line: 1,
+            column: 1,
+            token_index: 0,
+            token: String::new(),
+        });
+    }
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_comma_separated_values() {
+        assert_eq!(parse("1,0,0,3,99"), Ok(vec![1, 0, 0, 3, 99]));
+    }
+
+    #[test]
+    fn tolerates_whitespace_newlines_and_a_trailing_comma() {
+        assert_eq!(
+            parse("  1, 0 ,0,\r\n3,\n\n 99,\n"),
+            Ok(vec![1, 0, 0, 3, 99])
+        );
+    }
+
+    #[test]
+    fn ignores_comments() {
+        let input = "# adds two numbers\n1,5,6,0, # add\n99, # halt\n";
+
+        assert_eq!(parse(input), Ok(vec![1, 5, 6, 0, 99]));
+    }
+
+    #[test]
+    fn reports_the_position_of_an_invalid_token() {
+        let err = parse("1,2,3\n4, five,6").unwrap_err();
+
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 4);
+        assert_eq!(err.token_index, 4);
+        assert_eq!(err.token, "five");
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 4: invalid number `five` at token 4 (invalid digit found in string)"
+        );
+    }
+
+    #[test]
+    fn reports_a_missing_number_between_commas() {
+        let err = parse("1,,3").unwrap_err();
+
+        assert_eq!(err.kind, ParseErrorKind::MissingNumber);
+        assert_eq!((err.line, err.column, err.token_index), (1, 3, 1));
+    }
+
+    #[test]
+    fn rejects_input_without_any_code() {
+        let err = parse("\n# nothing here\n").unwrap_err();
+
+        assert_eq!(err.kind, ParseErrorKind::NoCode);
+    }
+}
@@ -0,0 +1,293 @@
+use super::Fragment;
+
+// Routines ready to link after a program's own code. They all use the same
+// convention: the caller stores the arguments in the routine's named cells,
+// stores where to carry on in `<routine>.ret` and jumps to the routine, which
+// jumps back through that cell when it's done:
+//
+//     add 6, 0, [multiply.a]
+//     add 7, 0, [multiply.b]
+//     add back, 0, [multiply.ret]
+//     jnz 1, multiply
+//     back: out [multiply.result]
+//
+// None of them are reentrant, since the arguments live in fixed cells.
+pub fn library() -> Vec<Fragment> {
+    [
+        ("print_int", PRINT_INT),
+        ("multiply", MULTIPLY),
+        ("compare", COMPARE),
+        ("copy", COPY),
+    ]
+    .iter()
+    .map(|(name, source)| Fragment::assemble(name, source).unwrap())
+    .collect()
+}
+
+// Outputs `print_int.n` as ASCII decimal digits, with a leading `-` if it's
+// negative. There's no division, so each digit is counted by subtracting
+// its power of ten until the number would go negative.
+const PRINT_INT: &str = "
+print_int:
+    add 0, 0, [print_int.started]
+    add print_int.powers, 0, [print_int.load+1]
+    lt [print_int.n], 0, [print_int.flag]
+    jz [print_int.flag], print_int.load
+    out 45
+    mul [print_int.n], -1, [print_int.n]
+print_int.load:
+    add [0], 0, [print_int.power]       # patched to walk the table
+    jz [print_int.power], print_int.end
+    add 0, 0, [print_int.digit]
+print_int.count:
+    add [print_int.n], [print_int.power], [print_int.rest]
+    lt [print_int.rest], 0, [print_int.flag]
+    jnz [print_int.flag], print_int.emit
+    add [print_int.rest], 0, [print_int.n]
+    add [print_int.digit], 1, [print_int.digit]
+    jnz 1, print_int.count
+print_int.emit:
+    # Leading zeros are skipped.
+    add [print_int.started], [print_int.digit], [print_int.flag]
+    jz [print_int.flag], print_int.next
+    add [print_int.digit], 48, [print_int.digit]
+    out [print_int.digit]
+    add 1, 0, [print_int.started]
+print_int.next:
+    add [print_int.load+1], 1, [print_int.load+1]
+    jnz 1, print_int.load
+print_int.end:
+    jnz [print_int.started], [print_int.ret]
+    out 48
+    jnz 1, [print_int.ret]
+
+print_int.n: data 0
+print_int.ret: data 0
+print_int.started: data 0
+print_int.flag: data 0
+print_int.power: data 0
+print_int.digit: data 0
+print_int.rest: data 0
+# Negated, so they can be added, and ending with 0.
+print_int.powers:
+    data -1000000000000000000, -100000000000000000, -10000000000000000
+    data -1000000000000000, -100000000000000, -10000000000000, -1000000000000
+    data -100000000000, -10000000000, -1000000000, -100000000, -10000000
+    data -1000000, -100000, -10000, -1000, -100, -10, -1, 0
+";
+
+// Sets `multiply.result` to `multiply.a` added to itself `multiply.b` times,
+// so `multiply.b` mustn't be negative. Counts `multiply.b` down to zero.
+const MULTIPLY: &str = "
+multiply:
+    add 0, 0, [multiply.result]
+multiply.loop:
+    lt 0, [multiply.b], [multiply.flag]
+    jz [multiply.flag], [multiply.ret]
+    add [multiply.result], [multiply.a], [multiply.result]
+    add [multiply.b], -1, [multiply.b]
+    jnz 1, multiply.loop
+
+multiply.a: data 0
+multiply.b: data 0
+multiply.ret: data 0
+multiply.result: data 0
+multiply.flag: data 0
+";
+
+// Sets `compare.result` to -1, 0 or 1 as `compare.a` is less than, equal to
+// or greater than `compare.b`.
+const COMPARE: &str = "
+compare:
+    lt [compare.a], [compare.b], [compare.flag]
+    jz [compare.flag], compare.not_less
+    add -1, 0, [compare.result]
+    jnz 1, [compare.ret]
+compare.not_less:
+    lt [compare.b], [compare.a], [compare.result]
+    jnz 1, [compare.ret]
+
+compare.a: data 0
+compare.b: data 0
+compare.ret: data 0
+compare.result: data 0
+compare.flag: data 0
+";
+
+// Copies `copy.len` cells from the address in `copy.from` to the address in
+// `copy.to`, front to back, by patching the addresses of a single add. Moves
+// `copy.from` and `copy.to` past the cells copied.
+const COPY: &str = "
+copy:
+    lt 0, [copy.len], [copy.flag]
+    jz [copy.flag], [copy.ret]
+    add [copy.from], 0, [copy.move+1]
+    add [copy.to], 0, [copy.move+3]
+copy.move:
+    add [0], 0, [0]
+    add [copy.from], 1, [copy.from]
+    add [copy.to], 1, [copy.to]
+    add [copy.len], -1, [copy.len]
+    jnz 1, copy
+
+copy.from: data 0
+copy.to: data 0
+copy.len: data 0
+copy.ret: data 0
+copy.flag: data 0
+";
+
+#[cfg(test)]
+mod tests {
+    use super::super::{link, Program};
+    use super::*;
+
+    // Links a main program in front of the whole library.
+    fn build(main: &str) -> Program {
+        let mut fragments = vec![Fragment::assemble("main", main).unwrap()];
+        fragments.extend(library());
+
+        link(&fragments).unwrap().program
+    }
+
+    fn printed(program: &mut Program, inputs: Vec<i64>) -> String {
+        program
+            .run(inputs)
+            .into_iter()
+            .map(|c| c as u8 as char)
+            .collect()
+    }
+
+    #[test]
+    fn prints_integers_in_decimal() {
+        let mut program = build(
+            "
+            loop:
+                in [print_int.n]
+                add next, 0, [print_int.ret]
+                jnz 1, print_int
+            next:
+                out 10
+                jnz 1, loop
+            ",
+        );
+
+        for (n, text) in [
+            (0, "0\n"),
+            (7, "7\n"),
+            (10, "10\n"),
+            (-305, "-305\n"),
+            (1_000_000_000_000_000_000, "1000000000000000000\n"),
+            (i64::MAX, "9223372036854775807\n"),
+        ] {
+            program.reset();
+
+            let mut inputs = vec![n].into_iter();
+            let mut output = String::new();
+
+            while let Some(c) = program.run_until_next_output(&mut inputs) {
+                output.push(c as u8 as char);
+
+                if c == 10 {
+                    break;
+                }
+            }
+
+            assert_eq!(output, text);
+        }
+    }
+
+    #[test]
+    fn multiplies_by_repeated_addition() {
+        let mut program = build(
+            "
+                in [multiply.a]
+                in [multiply.b]
+                add done, 0, [multiply.ret]
+                jnz 1, multiply
+            done:
+                out [multiply.result]
+                halt
+            ",
+        );
+
+        for (a, b) in [(6, 7), (-4, 3), (5, 0), (0, 9)] {
+            program.reset();
+            assert_eq!(program.run(vec![a, b]), vec![a * b]);
+        }
+    }
+
+    #[test]
+    fn compares_two_numbers() {
+        let mut program = build(
+            "
+                in [compare.a]
+                in [compare.b]
+                add done, 0, [compare.ret]
+                jnz 1, compare
+            done:
+                out [compare.result]
+                halt
+            ",
+        );
+
+        for (a, b, result) in [(1, 2, -1), (2, 2, 0), (3, -2, 1)] {
+            program.reset();
+            assert_eq!(program.run(vec![a, b]), vec![result]);
+        }
+    }
+
+    #[test]
+    fn copies_a_block_of_memory() {
+        let mut program = build(
+            "
+                add source, 0, [copy.from]
+                add target, 0, [copy.to]
+                add 3, 0, [copy.len]
+                add done, 0, [copy.ret]
+                jnz 1, copy
+            done:
+                out [target]
+                out [target+1]
+                out [target+2]
+                out [target+3]
+                halt
+            source: data 11, 22, 33, 44
+            target: data 0, 0, 0, 0
+            ",
+        );
+
+        assert_eq!(program.run(vec![]), vec![11, 22, 33, 0]);
+    }
+
+    #[test]
+    fn routines_can_call_each_other_from_one_program() {
+        // Prints a times table row, one product per line.
+        let mut program = build(
+            "
+                in [row]
+                add 1, 0, [column]
+            loop:
+                add [row], 0, [multiply.a]
+                add [column], 0, [multiply.b]
+                add multiplied, 0, [multiply.ret]
+                jnz 1, multiply
+            multiplied:
+                add [multiply.result], 0, [print_int.n]
+                add printed, 0, [print_int.ret]
+                jnz 1, print_int
+            printed:
+                out 10
+                add [column], 1, [column]
+                lt 5, [column], [done]
+                jz [done], loop
+                halt
+            row: data 0
+            column: data 0
+            done: data 0
+            ",
+        );
+
+        assert_eq!(printed(&mut program, vec![12]), "12\n24\n36\n48\n60\n");
+    }
+}
@@ -0,0 +1,478 @@
+use super::{Program, Step};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::path::Path;
+use std::str::FromStr;
+
+const HEADER: &str = "# intcode session";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    Input(i64),
+    Output(i64),
+    // The program asked for input nobody gave it, which is where an
+    // interactive session that was abandoned part way through ends.
+    AwaitingInput,
+    Halted,
+}
+
+// `step` is how many instructions had run when the event happened, counting
+// the instruction that caused it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub step: u64,
+    pub kind: EventKind,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            EventKind::Input(value) => write!(f, "{} in {}", self.step, value),
+            EventKind::Output(value) => write!(f, "{} out {}", self.step, value),
+            EventKind::AwaitingInput => write!(f, "{} wait", self.step),
+            EventKind::Halted => write!(f, "{} halt", self.step),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    pub fn inputs(&self) -> Vec<i64> {
+        self.events
+            .iter()
+            .filter_map(|event| match event.kind {
+                EventKind::Input(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn outputs(&self) -> Vec<i64> {
+        self.events
+            .iter()
+            .filter_map(|event| match event.kind {
+                EventKind::Output(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Recording> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+// One event per line after a header, e.g. `12 in 5` or `40 out 7`.
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogErrorKind {
+    InvalidNumber(ParseIntError),
+    MissingField,
+    UnknownEvent(String),
+    ExtraField(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogError {
+    pub kind: LogErrorKind,
+    pub line: usize,
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            LogErrorKind::InvalidNumber(err) => {
+                write!(f, "invalid number on line {}: {}", self.line, err)
+            }
+            LogErrorKind::MissingField => write!(f, "incomplete event on line {}", self.line),
+            LogErrorKind::UnknownEvent(name) => {
+                write!(f, "unknown event {:?} on line {}", name, self.line)
+            }
+            LogErrorKind::ExtraField(field) => {
+                write!(f, "unexpected {:?} on line {}", field, self.line)
+            }
+        }
+    }
+}
+
+impl Error for LogError {}
+
+impl FromStr for Recording {
+    type Err = LogError;
+
+    fn from_str(log: &str) -> Result<Recording, LogError> {
+        let mut events = Vec::new();
+
+        for (i, line) in log.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |kind| LogError { kind, line: i + 1 };
+            let number = |field: Option<&str>| -> Result<i64, LogError> {
+                field
+                    .ok_or_else(|| error(LogErrorKind::MissingField))?
+                    .parse()
+                    .map_err(|err| error(LogErrorKind::InvalidNumber(err)))
+            };
+
+            let mut fields = line.split_whitespace();
+            let step = fields
+                .next()
+                .ok_or_else(|| error(LogErrorKind::MissingField))?
+                .parse::<u64>()
+                .map_err(|err| error(LogErrorKind::InvalidNumber(err)))?;
+
+            let kind = match fields.next() {
+                Some("in") => EventKind::Input(number(fields.next())?),
+                Some("out") => EventKind::Output(number(fields.next())?),
+                Some("wait") => EventKind::AwaitingInput,
+                Some("halt") => EventKind::Halted,
+                Some(name) => return Err(error(LogErrorKind::UnknownEvent(name.to_string()))),
+                None => return Err(error(LogErrorKind::MissingField)),
+            };
+
+            if let Some(field) = fields.next() {
+                return Err(error(LogErrorKind::ExtraField(field.to_string())));
+            }
+
+            events.push(Event { step, kind });
+        }
+
+        Ok(Recording { events })
+    }
+}
+
+// Steps a program while logging every input it consumes and every output it
+// produces.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    program: Program,
+    steps: u64,
+    recording: Recording,
+}
+
+impl Recorder {
+    pub fn new(program: Program) -> Recorder {
+        Recorder {
+            program,
+            steps: 0,
+            recording: Recording::default(),
+        }
+    }
+
+    pub fn step<I>(&mut self, inputs: &mut I) -> Step
+    where
+        I: Iterator<Item = i64>,
+    {
+        let mut consumed = None;
+        let step = self
+            .program
+            .step(&mut inputs.inspect(|input| consumed = Some(*input)));
+
+        if step != Step::AwaitingInput {
+            self.steps += 1;
+        }
+
+        let kind = match (step, consumed) {
+            (_, Some(input)) => Some(EventKind::Input(input)),
+            (Step::Output(output), _) => Some(EventKind::Output(output)),
+            (Step::AwaitingInput, _) => Some(EventKind::AwaitingInput),
+            (Step::Halted, _) => Some(EventKind::Halted),
+            (Step::Executed, None) => None,
+        };
+
+        if let Some(kind) = kind {
+            let event = Event {
+                step: self.steps,
+                kind,
+            };
+
+            // A machine polled for input again without anything happening
+            // in between only needs logging once.
+            if self.recording.events.last() != Some(&event) {
+                self.recording.events.push(event);
+            }
+        }
+
+        step
+    }
+
+    // Runs until the program halts or wants more input than it was given.
+    pub fn run<I>(&mut self, inputs: I) -> Vec<i64>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        let mut inputs = inputs.into_iter();
+        let mut outputs = Vec::new();
+
+        loop {
+            match self.step(&mut inputs) {
+                Step::Executed => {}
+                Step::Output(output) => outputs.push(output),
+                Step::AwaitingInput | Step::Halted => return outputs,
+            }
+        }
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+}
+
+// The first event that didn't happen the way it was recorded. `actual` is
+// `None` when the program ran past the recorded step without producing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub index: usize,
+    pub expected: Event,
+    pub actual: Option<Event>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.actual {
+            Some(actual) => write!(
+                f,
+                "event {}: expected `{}`, got `{}`",
+                self.index, self.expected, actual
+            ),
+            None => write!(
+                f,
+                "event {}: expected `{}`, but nothing happened by then",
+                self.index, self.expected
+            ),
+        }
+    }
+}
+
+impl Error for Divergence {}
+
+// Feeds each recorded input back in at the step it was read, and checks
+// that every event happens again at the same step. Holding inputs back until
+// then means a program that waited for input in an interactive session waits
+// again here. Stops once the recording runs out, so a replay of a session
+// that was cut short doesn't run the program forever.
+pub fn replay(program: Program, recording: &Recording) -> Result<Vec<i64>, Divergence> {
+    let mut recorder = Recorder::new(program);
+    let mut outputs = Vec::new();
+
+    for (index, &expected) in recording.events.iter().enumerate() {
+        let mut input = match expected.kind {
+            EventKind::Input(value) => Some(value),
+            _ => None,
+        };
+
+        loop {
+            let seen = recorder.recording.events.len();
+            let step = recorder.step(&mut std::iter::from_fn(|| input.take()));
+
+            if let Step::Output(output) = step {
+                outputs.push(output);
+            }
+
+            if let Some(&actual) = recorder.recording.events.get(seen) {
+                if actual != expected {
+                    return Err(Divergence {
+                        index,
+                        expected,
+                        actual: Some(actual),
+                    });
+                }
+
+                break;
+            }
+
+            if recorder.steps >= expected.step {
+                return Err(Divergence {
+                    index,
+                    expected,
+                    actual: None,
+                });
+            }
+        }
+    }
+
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPARE_TO_EIGHT: &str = "3,9,8,9,10,9,4,9,99,-1,8";
+
+    fn program(code: &str) -> Program {
+        code.parse::<Program>().unwrap()
+    }
+
+    fn record(code: &str, inputs: Vec<i64>) -> Recording {
+        let mut recorder = Recorder::new(program(code));
+
+        recorder.run(inputs);
+        recorder.into_recording()
+    }
+
+    #[test]
+    fn records_inputs_and_outputs_with_step_counts() {
+        let recording = record(COMPARE_TO_EIGHT, vec![8]);
+
+        assert_eq!(
+            recording.to_string(),
+            "# intcode session\n1 in 8\n3 out 1\n4 halt\n"
+        );
+        assert_eq!(recording.inputs(), vec![8]);
+        assert_eq!(recording.outputs(), vec![1]);
+    }
+
+    #[test]
+    fn records_where_an_interactive_session_stopped() {
+        let recording = record("3,0,4,0,3,0,99", vec![5]);
+
+        assert_eq!(
+            recording.to_string(),
+            "# intcode session\n1 in 5\n2 out 5\n2 wait\n"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_a_log_file() {
+        let recording = record(COMPARE_TO_EIGHT, vec![3]);
+        let path = std::env::temp_dir().join("int_code_session_round_trip.log");
+
+        recording.save(&path).unwrap();
+        let loaded = Recording::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, recording);
+    }
+
+    #[test]
+    fn reports_the_line_of_a_bad_log_entry() {
+        let err = "# intcode session\n1 in 8\n3 jump 4\n"
+            .parse::<Recording>()
+            .unwrap_err();
+
+        assert_eq!(err.line, 3);
+        assert_eq!(err.to_string(), "unknown event \"jump\" on line 3");
+    }
+
+    #[test]
+    fn rejects_negative_steps_and_extra_fields() {
+        let error = |log: &str| log.parse::<Recording>().unwrap_err();
+
+        assert!(matches!(
+            error("-1 in 5").kind,
+            LogErrorKind::InvalidNumber(_)
+        ));
+        assert_eq!(
+            error("1 in 5 6").kind,
+            LogErrorKind::ExtraField("6".to_string())
+        );
+        assert_eq!(
+            error("1 in 5\n2 halt now").to_string(),
+            "unexpected \"now\" on line 2"
+        );
+    }
+
+    #[test]
+    fn replays_a_recording_of_the_same_program() {
+        let recording = record(COMPARE_TO_EIGHT, vec![8]);
+
+        assert_eq!(replay(program(COMPARE_TO_EIGHT), &recording), Ok(vec![1]));
+    }
+
+    #[test]
+    fn replays_a_session_recorded_over_several_runs() {
+        let mut recorder = Recorder::new(program("3,9,4,9,3,9,4,9,99,0"));
+
+        recorder.run(vec![5]);
+        recorder.run(vec![6]);
+        let recording = recorder.into_recording();
+
+        assert_eq!(
+            recording.to_string(),
+            "# intcode session\n1 in 5\n2 out 5\n2 wait\n3 in 6\n4 out 6\n5 halt\n"
+        );
+        assert_eq!(
+            replay(program("3,9,4,9,3,9,4,9,99,0"), &recording),
+            Ok(vec![5, 6])
+        );
+    }
+
+    #[test]
+    fn flags_a_program_that_wants_input_earlier_than_recorded() {
+        let recording = record(COMPARE_TO_EIGHT, vec![8]);
+
+        // Outputs before reading, so it asks for input at a later step.
+        let changed = program("104,0,3,11,8,11,12,11,4,11,99,-1,8");
+
+        assert_eq!(
+            replay(changed, &recording).unwrap_err().to_string(),
+            "event 0: expected `1 in 8`, got `1 out 0`"
+        );
+    }
+
+    #[test]
+    fn flags_the_first_output_that_differs() {
+        let recording = record(COMPARE_TO_EIGHT, vec![8]);
+
+        // Compares with 7 instead of 8.
+        let changed = program("3,9,8,9,10,9,4,9,99,-1,7");
+
+        let divergence = replay(changed, &recording).unwrap_err();
+
+        assert_eq!(divergence.index, 1);
+        assert_eq!(
+            divergence.to_string(),
+            "event 1: expected `3 out 1`, got `3 out 0`"
+        );
+    }
+
+    #[test]
+    fn flags_events_that_happen_at_a_different_step() {
+        let recording = record(COMPARE_TO_EIGHT, vec![8]);
+
+        // An extra no-op before the output.
+        let changed = program("3,13,8,13,14,13,1101,0,0,15,4,13,99,-1,8,0");
+
+        assert_eq!(
+            replay(changed, &recording).unwrap_err().to_string(),
+            "event 1: expected `3 out 1`, but nothing happened by then"
+        );
+    }
+}
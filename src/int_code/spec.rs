@@ -0,0 +1,637 @@
+use super::{MemoryDiff, Program, Step};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::num::ParseIntError;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::str::FromStr;
+
+const DEFAULT_MAX_STEPS: usize = 1_000_000;
+
+// Regression cases kept as data rather than Rust. A spec lists programs,
+// each followed by the cases to run against a fresh copy of it:
+//
+//     # Day five's comparison example.
+//     program equal to eight
+//     code 3,9,8,9,10,9,4,9,99,-1,8
+//
+//     case eight is equal
+//     in 8
+//     out 1
+//
+// `code` lines are joined together, so long programs can be split up. A
+// case gives its inputs with `in`, and checks any of the outputs with `out`,
+// the whole of memory with `memory` or some cells starting at an address
+// with `at 3 70, 2`. Cases are stopped after `steps` instructions, a million
+// unless given.
+//
+// Every `.spec` file in `src/int_code/specs` is run by `cargo test`, one test
+// per case, so new cases only need adding to a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spec {
+    pub programs: Vec<SpecProgram>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecProgram {
+    pub name: String,
+    pub code: Vec<i64>,
+    pub cases: Vec<Case>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub name: String,
+    pub line: usize,
+    pub inputs: Vec<i64>,
+    pub outputs: Option<Vec<i64>>,
+    pub memory: Option<Vec<i64>>,
+    pub cells: Vec<(usize, Vec<i64>)>,
+    pub max_steps: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Outputs {
+        expected: Vec<i64>,
+        actual: Vec<i64>,
+    },
+    Memory {
+        expected: Vec<i64>,
+        actual: Vec<i64>,
+    },
+    Cells {
+        address: usize,
+        expected: Vec<i64>,
+        actual: Vec<i64>,
+    },
+    AwaitingInput {
+        address: usize,
+        outputs: Vec<i64>,
+    },
+    StepLimit {
+        steps: usize,
+    },
+    Panicked(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Outputs { expected, actual } => {
+                let first = expected
+                    .iter()
+                    .zip(actual)
+                    .position(|(a, b)| a != b)
+                    .unwrap_or_else(|| expected.len().min(actual.len()));
+
+                writeln!(f, "outputs differ from output {}:", first)?;
+                writeln!(f, "  expected: {}", join(expected))?;
+                write!(f, "  actual:   {}", join(actual))
+            }
+            Failure::Memory { expected, actual } => {
+                let diff = MemoryDiff::between(expected, actual);
+
+                writeln!(
+                    f,
+                    "memory differs ({} cells, expected {}):",
+                    actual.len(),
+                    expected.len()
+                )?;
+                write!(f, "{}{}", diff, diff.hexdump().trim_end())
+            }
+            Failure::Cells {
+                address,
+                expected,
+                actual,
+            } => {
+                writeln!(f, "cells from {} differ:", address)?;
+                writeln!(f, "  expected: {}", join(expected))?;
+                write!(f, "  actual:   {}", join(actual))
+            }
+            Failure::AwaitingInput { address, outputs } => write!(
+                f,
+                "ran out of input at {} after outputting {}",
+                address,
+                join(outputs)
+            ),
+            Failure::StepLimit { steps } => write!(f, "still running after {} steps", steps),
+            Failure::Panicked(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+impl Case {
+    pub fn run(&self, code: &[i64]) -> Result<(), Failure> {
+        let mut program = Program::new(code.to_vec());
+
+        let outputs = panic::catch_unwind(AssertUnwindSafe(|| self.execute(&mut program)))
+            .map_err(|payload| {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+
+                Failure::Panicked(message)
+            })??;
+
+        if let Some(expected) = &self.outputs {
+            if *expected != outputs {
+                return Err(Failure::Outputs {
+                    expected: expected.clone(),
+                    actual: outputs,
+                });
+            }
+        }
+
+        let memory = program.code.to_vec();
+
+        if let Some(expected) = &self.memory {
+            if *expected != memory {
+                return Err(Failure::Memory {
+                    expected: expected.clone(),
+                    actual: memory,
+                });
+            }
+        }
+
+        for (address, expected) in &self.cells {
+            let actual = (*address..address + expected.len())
+                .map(|i| memory.get(i).copied().unwrap_or(0))
+                .collect::<Vec<_>>();
+
+            if *expected != actual {
+                return Err(Failure::Cells {
+                    address: *address,
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute(&self, program: &mut Program) -> Result<Vec<i64>, Failure> {
+        let mut inputs = self.inputs.iter().copied();
+        let mut outputs = Vec::new();
+
+        for _ in 0..self.max_steps {
+            match program.step(&mut inputs) {
+                Step::Executed => {}
+                Step::Output(output) => outputs.push(output),
+                Step::AwaitingInput => {
+                    return Err(Failure::AwaitingInput {
+                        address: program.address(),
+                        outputs,
+                    })
+                }
+                Step::Halted => return Ok(outputs),
+            }
+        }
+
+        Err(Failure::StepLimit {
+            steps: self.max_steps,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseReport {
+    pub program: String,
+    pub case: String,
+    pub line: usize,
+    pub result: Result<(), Failure>,
+}
+
+impl fmt::Display for CaseReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {} / {}", self.line, self.program, self.case)?;
+
+        match &self.result {
+            Ok(()) => write!(f, " ... ok"),
+            Err(failure) => write!(f, " ... FAILED\n{}", failure),
+        }
+    }
+}
+
+impl Spec {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Spec, Box<dyn Error>> {
+        Ok(fs::read_to_string(path)?.parse()?)
+    }
+
+    // Every case is run, even after one fails.
+    pub fn run(&self) -> Vec<CaseReport> {
+        self.programs
+            .iter()
+            .flat_map(|program| {
+                program.cases.iter().map(move |case| CaseReport {
+                    program: program.name.clone(),
+                    case: case.name.clone(),
+                    line: case.line,
+                    result: case.run(&program.code),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecErrorKind {
+    InvalidNumber(ParseIntError),
+    UnknownDirective(String),
+    MissingName,
+    OutsideProgram,
+    OutsideCase,
+    NoCode,
+    NoExpectations,
+    NoValues,
+    DuplicateName(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    pub kind: SpecErrorKind,
+    pub line: usize,
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            SpecErrorKind::InvalidNumber(err) => write!(f, "invalid number ({})", err),
+            SpecErrorKind::UnknownDirective(directive) => {
+                write!(f, "unknown directive `{}`", directive)
+            }
+            SpecErrorKind::MissingName => write!(f, "programs and cases need a name"),
+            SpecErrorKind::OutsideProgram => write!(f, "expected a program first"),
+            SpecErrorKind::OutsideCase => write!(f, "expected a case first"),
+            SpecErrorKind::NoCode => write!(f, "program has no code"),
+            SpecErrorKind::NoExpectations => write!(f, "case checks nothing"),
+            SpecErrorKind::NoValues => write!(f, "`at` needs values to check"),
+            SpecErrorKind::DuplicateName(name) => write!(f, "`{}` is already used", name),
+        }
+    }
+}
+
+impl Error for SpecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            SpecErrorKind::InvalidNumber(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Spec {
+    type Err = SpecError;
+
+    fn from_str(text: &str) -> Result<Spec, SpecError> {
+        let mut spec = Spec::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let error = |kind| SpecError {
+                kind,
+                line: line_number,
+            };
+            let numbers = |text: &str| {
+                parse_numbers(text).map_err(|err| error(SpecErrorKind::InvalidNumber(err)))
+            };
+
+            let line = line.split('#').next().unwrap().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (directive, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let rest = rest.trim();
+
+            if directive == "program" {
+                check_last_program(&spec, line_number)?;
+
+                if rest.is_empty() {
+                    return Err(error(SpecErrorKind::MissingName));
+                }
+
+                if spec.programs.iter().any(|program| program.name == rest) {
+                    return Err(error(SpecErrorKind::DuplicateName(rest.to_string())));
+                }
+
+                spec.programs.push(SpecProgram {
+                    name: rest.to_string(),
+                    code: Vec::new(),
+                    cases: Vec::new(),
+                });
+
+                continue;
+            }
+
+            let program = spec
+                .programs
+                .last_mut()
+                .ok_or_else(|| error(SpecErrorKind::OutsideProgram))?;
+
+            match directive {
+                "code" => program.code.extend(numbers(rest)?),
+                "case" => {
+                    check_last_case(program)?;
+
+                    if program.code.is_empty() {
+                        return Err(error(SpecErrorKind::NoCode));
+                    }
+
+                    if rest.is_empty() {
+                        return Err(error(SpecErrorKind::MissingName));
+                    }
+
+                    if program.cases.iter().any(|case| case.name == rest) {
+                        return Err(error(SpecErrorKind::DuplicateName(rest.to_string())));
+                    }
+
+                    program.cases.push(Case {
+                        name: rest.to_string(),
+                        line: line_number,
+                        inputs: Vec::new(),
+                        outputs: None,
+                        memory: None,
+                        cells: Vec::new(),
+                        max_steps: DEFAULT_MAX_STEPS,
+                    });
+                }
+                _ => {
+                    let case = program
+                        .cases
+                        .last_mut()
+                        .ok_or_else(|| error(SpecErrorKind::OutsideCase))?;
+
+                    match directive {
+                        "in" => case.inputs.extend(numbers(rest)?),
+                        "out" => case
+                            .outputs
+                            .get_or_insert_with(Vec::new)
+                            .extend(numbers(rest)?),
+                        "memory" => case
+                            .memory
+                            .get_or_insert_with(Vec::new)
+                            .extend(numbers(rest)?),
+                        "at" => {
+                            let (address, values) = rest.split_once(' ').unwrap_or((rest, ""));
+                            let address = address
+                                .parse()
+                                .map_err(|err| error(SpecErrorKind::InvalidNumber(err)))?;
+                            let values = numbers(values)?;
+
+                            if values.is_empty() {
+                                return Err(error(SpecErrorKind::NoValues));
+                            }
+
+                            case.cells.push((address, values));
+                        }
+                        "steps" => {
+                            case.max_steps = rest
+                                .parse()
+                                .map_err(|err| error(SpecErrorKind::InvalidNumber(err)))?;
+                        }
+                        _ => {
+                            return Err(error(SpecErrorKind::UnknownDirective(
+                                directive.to_string(),
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+
+        check_last_program(&spec, text.lines().count() + 1)?;
+
+        Ok(spec)
+    }
+}
+
+// Called as the next program starts, or at the end of the spec.
+fn check_last_program(spec: &Spec, line: usize) -> Result<(), SpecError> {
+    match spec.programs.last() {
+        Some(program) if program.code.is_empty() => Err(SpecError {
+            kind: SpecErrorKind::NoCode,
+            line,
+        }),
+        Some(program) => check_last_case(program),
+        None => Ok(()),
+    }
+}
+
+fn check_last_case(program: &SpecProgram) -> Result<(), SpecError> {
+    match program.cases.last() {
+        Some(case) if case.outputs.is_none() && case.memory.is_none() && case.cells.is_empty() => {
+            Err(SpecError {
+                kind: SpecErrorKind::NoExpectations,
+                line: case.line,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+// `in` and `out` with nothing after them mean no values.
+fn parse_numbers(text: &str) -> Result<Vec<i64>, ParseIntError> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    text.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+fn join(values: &[i64]) -> String {
+    if values.is_empty() {
+        return "nothing".to_string();
+    }
+
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "
+        # Echoes its input.
+        program echo
+        code 3,0,
+        code 4,0,99
+
+        case five
+        in 5
+        out 5
+        memory 5,0,4,0,99
+
+        case seven, checking a cell
+        in 7
+        at 0 7, 0
+    ";
+
+    #[test]
+    fn parses_programs_and_cases() {
+        let spec = SPEC.parse::<Spec>().unwrap();
+
+        assert_eq!(spec.programs.len(), 1);
+        assert_eq!(spec.programs[0].code, vec![3, 0, 4, 0, 99]);
+
+        let cases = &spec.programs[0].cases;
+
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].line, 7);
+        assert_eq!(cases[0].inputs, vec![5]);
+        assert_eq!(cases[0].outputs, Some(vec![5]));
+        assert_eq!(cases[1].name, "seven, checking a cell");
+        assert_eq!(cases[1].outputs, None);
+        assert_eq!(cases[1].cells, vec![(0, vec![7, 0])]);
+    }
+
+    #[test]
+    fn runs_every_case() {
+        let reports = SPEC.parse::<Spec>().unwrap().run();
+
+        assert!(reports.iter().all(|report| report.result.is_ok()));
+        assert_eq!(
+            reports[1].to_string(),
+            "line 12: echo / seven, checking a cell ... ok"
+        );
+    }
+
+    #[test]
+    fn reports_failures_with_diffs() {
+        let spec = "
+            program adds
+            code 1101,2,3,0,4,0,99
+
+            case wrong output
+            out 6
+
+            case wrong memory
+            memory 5,2,3,0,4,0,98
+
+            case wrong cell
+            at 0 4
+
+            program reads
+            code 104,5,3,0,99
+
+            case without input
+            out 5
+        "
+        .parse::<Spec>()
+        .unwrap();
+
+        let failures = spec
+            .run()
+            .into_iter()
+            .map(|report| report.result.unwrap_err().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            failures[0],
+            "outputs differ from output 0:\n  expected: 6\n  actual:   5"
+        );
+        assert!(failures[1].starts_with("memory differs (7 cells, expected 7):\n@6: 98 -> 99\n"));
+        assert_eq!(
+            failures[2],
+            "cells from 0 differ:\n  expected: 4\n  actual:   5"
+        );
+        assert_eq!(failures[3], "ran out of input at 2 after outputting 5");
+    }
+
+    #[test]
+    fn stops_runaway_and_crashing_cases() {
+        let spec = "
+            program loops
+            code 1105,1,0
+
+            case forever
+            out
+            steps 50
+
+            program crashes
+            code 42
+
+            case straight away
+            out
+
+            program writes far past the end
+            code 1101,1,0,1152921504606846976,99
+
+            case straight away
+            out
+        "
+        .parse::<Spec>()
+        .unwrap();
+
+        let reports = spec.run();
+
+        assert_eq!(reports[0].result, Err(Failure::StepLimit { steps: 50 }));
+        assert_eq!(
+            reports[1].result,
+            Err(Failure::Panicked("Unexpected opcode: 42 at 0".to_string()))
+        );
+        assert_eq!(
+            reports[2].result,
+            Err(Failure::Panicked(
+                "Write to 1152921504606846976 is past the memory limit of 16777216 cells"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn reports_bad_specs() {
+        let error = |text: &str| text.parse::<Spec>().unwrap_err();
+
+        assert_eq!(error("code 1,2").kind, SpecErrorKind::OutsideProgram);
+        assert_eq!(error("program p\ncase c").kind, SpecErrorKind::NoCode);
+        assert_eq!(
+            error("program p\ncode 99\nin 1").kind,
+            SpecErrorKind::OutsideCase
+        );
+        assert_eq!(
+            error("program p\ncode 99\ncase c\nin 1\nprogram q").kind,
+            SpecErrorKind::NoExpectations
+        );
+        assert_eq!(error("program p\ncode 99\ncase c\nin 1").line, 3);
+        assert_eq!(
+            error("program p\ncode 99\ncase c\nout\ncase c").kind,
+            SpecErrorKind::DuplicateName("c".to_string())
+        );
+        assert_eq!(
+            error("program p\ncode 99\ncase c\nout\nprogram p").to_string(),
+            "line 5: `p` is already used"
+        );
+        assert_eq!(
+            error("program p\ncode 99\ncase c\nat 3").kind,
+            SpecErrorKind::NoValues
+        );
+        assert_eq!(
+            error("program p\ncode 99\ncase c\nexpect 1").kind,
+            SpecErrorKind::UnknownDirective("expect".to_string())
+        );
+        assert!(matches!(
+            error("program p\ncode 9x").kind,
+            SpecErrorKind::InvalidNumber(_)
+        ));
+        assert_eq!(
+            error("program").to_string(),
+            "line 1: programs and cases need a name"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use super::{Memory, Program, Step};
+
+// Gives up on running ahead after this many instructions. The specialized
+// program picks up exactly where the run left off, so it still behaves like
+// the original as long as it's given the inputs that weren't read as well.
+const MAX_STEPS: usize = 10_000_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Stop {
+    AwaitingInput,
+    Halted,
+    StepLimit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Specialized {
+    // Starts, and resets to, the point the run stopped at.
+    pub program: Program,
+    // Anything output along the way, which the specialized program won't
+    // output again.
+    pub outputs: Vec<i64>,
+    // Known inputs the run didn't read. When it hit the step limit, the
+    // specialized program needs these before any others.
+    pub unread: Vec<i64>,
+    pub steps: usize,
+    pub stop: Stop,
+}
+
+// Runs the program on the inputs known ahead of time, as far as it gets
+// before it needs one that isn't, and returns a program that starts from
+// there. Running it on the rest of the inputs behaves like running the
+// original on all of them, without repeating the work done up front.
+pub fn specialize(program: &Program, known_inputs: &[i64]) -> Specialized {
+    specialize_within(program, known_inputs, MAX_STEPS)
+}
+
+fn specialize_within(program: &Program, known_inputs: &[i64], max_steps: usize) -> Specialized {
+    let mut program = program.clone();
+    let mut inputs = known_inputs.iter().copied();
+    let mut outputs = Vec::new();
+    let mut steps = 0;
+
+    let stop = loop {
+        if steps == max_steps {
+            break Stop::StepLimit;
+        }
+
+        match program.step(&mut inputs) {
+            Step::Executed => {}
+            Step::Output(output) => outputs.push(output),
+            Step::AwaitingInput => break Stop::AwaitingInput,
+            Step::Halted => break Stop::Halted,
+        }
+
+        steps += 1;
+    };
+
+    program.code = Memory::new(program.code.to_vec());
+    program.entry = program.i;
+    program.entry_relative_base = program.relative_base;
+
+    Specialized {
+        program,
+        outputs,
+        unread: inputs.collect(),
+        steps,
+        stop,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads a phase, then adds ten times it to every signal until it's
+    // given a zero.
+    const AMPLIFIER: &str = "3,21,1002,21,10,21,3,22,1006,22,20,1,21,22,22,4,22,1105,1,6,99,0,0";
+
+    #[test]
+    fn starts_where_the_known_inputs_run_out() {
+        let program = AMPLIFIER.parse::<Program>().unwrap();
+
+        let specialized = specialize(&program, &[4]);
+
+        assert_eq!(specialized.stop, Stop::AwaitingInput);
+        assert_eq!(specialized.steps, 2);
+        assert_eq!(specialized.program.address(), 6);
+        assert_eq!(specialized.program.code[21], 40);
+        assert!(specialized.outputs.is_empty());
+    }
+
+    #[test]
+    fn behaves_like_the_original_on_the_remaining_inputs() {
+        let program = AMPLIFIER.parse::<Program>().unwrap();
+        let mut specialized = specialize(&program, &[4]).program;
+
+        for signals in [vec![1, 2, 0], vec![0], vec![-40, 5, 0]] {
+            let mut inputs = vec![4];
+            inputs.extend(&signals);
+
+            assert_eq!(
+                specialized.run(signals.clone()),
+                program.clone().run(inputs)
+            );
+
+            specialized.reset();
+        }
+    }
+
+    #[test]
+    fn keeps_outputs_and_relative_base_from_the_prologue() {
+        // arb 20; out 1; in [rb+0]; out [rb+0]; halt
+        let program = "109,20,104,1,203,0,204,0,99".parse::<Program>().unwrap();
+
+        let specialized = specialize(&program, &[]);
+
+        assert_eq!(specialized.outputs, vec![1]);
+        assert_eq!(specialized.program.relative_base(), 20);
+        assert_eq!(specialized.program.clone().run(vec![9]), vec![9]);
+    }
+
+    #[test]
+    fn stops_at_a_halt() {
+        let program = "3,0,4,0,99".parse::<Program>().unwrap();
+
+        let specialized = specialize(&program, &[5, 6]);
+
+        assert_eq!(specialized.stop, Stop::Halted);
+        assert_eq!(specialized.outputs, vec![5]);
+        assert_eq!(specialized.unread, vec![6]);
+        assert!(specialized.program.clone().run(vec![]).is_empty());
+    }
+
+    #[test]
+    fn hands_back_inputs_it_did_not_get_to() {
+        let program = AMPLIFIER.parse::<Program>().unwrap();
+        let inputs = [4, 1, 2, 0];
+
+        let specialized = specialize_within(&program, &inputs, 5);
+
+        assert_eq!(specialized.stop, Stop::StepLimit);
+        assert_eq!(specialized.steps, 5);
+        assert_eq!(specialized.unread, vec![2, 0]);
+        assert!(specialized.outputs.is_empty());
+
+        let mut outputs = specialized.outputs.clone();
+        outputs.extend(specialized.program.clone().run(specialized.unread));
+
+        assert_eq!(outputs, program.clone().run(inputs.to_vec()));
+    }
+}
@@ -0,0 +1,120 @@
+# Day five: input, output, parameter modes, jumps and comparisons.
+
+program echo
+code 3,0,4,0,99
+
+case a positive number
+in 5
+out 5
+
+case a negative number
+in -12
+out -12
+
+program immediate mode
+code 1002,4,3,4,33
+
+case patches in a halt
+out
+memory 1002,4,3,4,99
+
+program negative immediates
+code 1101,100,-1,4,0
+
+case patches in a halt
+memory 1101,100,-1,4,99
+
+program equal to eight, position mode
+code 3,9,8,9,10,9,4,9,99,-1,8
+
+case seven
+in 7
+out 0
+
+case eight
+in 8
+out 1
+
+case nine
+in 9
+out 0
+
+program less than eight, position mode
+code 3,9,7,9,10,9,4,9,99,-1,8
+
+case seven
+in 7
+out 1
+
+case eight
+in 8
+out 0
+
+program equal to eight, immediate mode
+code 3,3,1108,-1,8,3,4,3,99
+
+case eight
+in 8
+out 1
+
+case minus eight
+in -8
+out 0
+
+program less than eight, immediate mode
+code 3,3,1107,-1,8,3,4,3,99
+
+case seven
+in 7
+out 1
+
+case eight
+in 8
+out 0
+
+program jumps, position mode
+code 3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9
+
+case zero
+in 0
+out 0
+
+case one
+in 1
+out 1
+
+case negative
+in -5
+out 1
+
+program jumps, immediate mode
+code 3,3,1105,-1,9,1101,0,0,12,4,12,99,1
+
+case zero
+in 0
+out 0
+
+case one
+in 1
+out 1
+
+case negative
+in -5
+out 1
+
+program compared with eight
+code 3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31
+code 1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104
+code 999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99
+
+case below
+in 7
+out 999
+
+case equal
+in 8
+out 1000
+
+case above
+in 9
+out 1001
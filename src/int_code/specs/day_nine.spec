@@ -0,0 +1,27 @@
+# Day nine: relative mode and large numbers.
+
+program quine
+code 109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99
+
+case outputs a copy of itself
+out 109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99
+
+program sixteen digits
+code 1102,34915192,34915192,7,4,7,99,0
+
+case multiplies without overflowing
+out 1219070632396864
+
+program large immediate
+code 104,1125899906842624,99
+
+case outputs it
+out 1125899906842624
+
+program relative writes past the end
+code 109,1000,203,5,204,5,99
+
+case grows memory
+in 42
+out 42
+at 1005 42
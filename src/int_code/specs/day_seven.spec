@@ -0,0 +1,31 @@
+# Day seven: single amplifiers, given a phase setting then a signal.
+
+program multiplies the signal by ten and adds the phase
+code 3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0
+
+case first amplifier
+in 4, 0
+out 4
+
+case last amplifier
+in 0, 4321
+out 43210
+
+program adds the phase to ten times the signal, backwards
+code 3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0
+
+case first amplifier
+in 0, 0
+out 5
+
+case last amplifier
+in 4, 5432
+out 54321
+
+program feedback loop amplifier
+code 3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5
+
+case runs five rounds, one signal each
+in 9
+in 0, 1, 2, 3, 4
+out 5, 7, 9, 11, 13
@@ -0,0 +1,32 @@
+# Day two: adding and multiplying in position mode.
+
+program the worked example
+code 1,9,10,3,2,3,11,0,99,30,40,50
+
+case runs to completion
+memory 3500,9,10,70,2,3,11,0,99,30,40,50
+
+program small programs
+code 1,0,0,0,99
+
+case adds a cell to itself
+memory 2,0,0,0,99
+
+program multiplies
+code 2,3,0,3,99
+
+case into the program
+memory 2,3,0,6,99
+
+program multiplies past the halt
+code 2,4,4,5,99,0
+
+case into the last cell
+memory 2,4,4,5,99,9801
+
+program overwrites its own halt
+code 1,1,1,4,99,5,6,0,99
+
+case carries on into the new instruction
+memory 30,1,1,4,2,5,6,0,99
+at 0 30
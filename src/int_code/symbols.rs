@@ -0,0 +1,363 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Symbol {
+    pub fn contains(&self, address: usize) -> bool {
+        address >= self.start && address < self.start + self.len
+    }
+}
+
+// Names for addresses and ranges of a program's memory, so listings, traces,
+// errors and dumps can say `noun` rather than `1`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Symbols {
+    // Kept in address order.
+    symbols: Vec<Symbol>,
+}
+
+impl Symbols {
+    pub fn new() -> Symbols {
+        Symbols::default()
+    }
+
+    // Redefining a name moves it.
+    pub fn define(&mut self, name: &str, start: usize, len: usize) {
+        self.symbols.retain(|symbol| symbol.name != name);
+
+        let at = self
+            .symbols
+            .partition_point(|symbol| (symbol.start, symbol.len) <= (start, len));
+
+        self.symbols.insert(
+            at,
+            Symbol {
+                name: name.to_string(),
+                start,
+                len,
+            },
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+
+    pub fn address(&self, name: &str) -> Option<usize> {
+        self.get(name).map(|symbol| symbol.start)
+    }
+
+    // The narrowest symbol covering the address, so a name for one cell
+    // inside a named range wins over the range.
+    pub fn at(&self, address: usize) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.contains(address))
+            .min_by_key(|symbol| symbol.len)
+    }
+
+    // `noun` for the start of a symbol and `buffer+3` for a cell inside one.
+    pub fn name_of(&self, address: usize) -> Option<String> {
+        self.at(address).map(|symbol| match address - symbol.start {
+            0 => symbol.name.clone(),
+            offset => format!("{}+{}", symbol.name, offset),
+        })
+    }
+
+    // The address, followed by its name if it has one.
+    pub fn describe(&self, address: usize) -> String {
+        match self.name_of(address) {
+            Some(name) => format!("{} ({})", address, name),
+            None => address.to_string(),
+        }
+    }
+
+    // Names of symbols that start somewhere in `start..end`.
+    pub fn starting_in(&self, start: usize, end: usize) -> Vec<&str> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.start >= start && symbol.start < end)
+            .map(|symbol| symbol.name.as_str())
+            .collect()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Symbols> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    // `src/two.txt` keeps its symbols in `src/two.sym`.
+    pub fn sidecar_path<P: AsRef<Path>>(program_path: P) -> PathBuf {
+        program_path.as_ref().with_extension("sym")
+    }
+
+    // A program without a sidecar file just has no symbols.
+    pub fn load_sidecar<P: AsRef<Path>>(program_path: P) -> io::Result<Symbols> {
+        match Symbols::load(Symbols::sidecar_path(program_path)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Symbols::new()),
+            result => result,
+        }
+    }
+}
+
+// One symbol per line, e.g. `noun 1` or `buffer 100..110`.
+impl fmt::Display for Symbols {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for symbol in &self.symbols {
+            if symbol.len == 1 {
+                writeln!(f, "{} {}", symbol.name, symbol.start)?;
+            } else {
+                writeln!(
+                    f,
+                    "{} {}..{}",
+                    symbol.name,
+                    symbol.start,
+                    symbol.start + symbol.len
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolErrorKind {
+    InvalidNumber(ParseIntError),
+    MissingField,
+    InvalidName(String),
+    EmptyRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolError {
+    pub kind: SymbolErrorKind,
+    pub line: usize,
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            SymbolErrorKind::InvalidNumber(err) => {
+                write!(f, "invalid address on line {}: {}", self.line, err)
+            }
+            SymbolErrorKind::MissingField => write!(f, "incomplete symbol on line {}", self.line),
+            SymbolErrorKind::InvalidName(name) => {
+                write!(f, "invalid symbol name {:?} on line {}", name, self.line)
+            }
+            SymbolErrorKind::EmptyRange => write!(f, "empty range on line {}", self.line),
+        }
+    }
+}
+
+impl Error for SymbolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            SymbolErrorKind::InvalidNumber(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// Reading or writing a program's memory by a name it has no symbol for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownSymbol {
+    pub name: String,
+}
+
+impl fmt::Display for UnknownSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no symbol named {:?}", self.name)
+    }
+}
+
+impl Error for UnknownSymbol {}
+
+impl FromStr for Symbols {
+    type Err = SymbolError;
+
+    fn from_str(text: &str) -> Result<Symbols, SymbolError> {
+        let mut symbols = Symbols::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |kind| SymbolError { kind, line: i + 1 };
+            let number = |field: &str| {
+                field
+                    .parse::<usize>()
+                    .map_err(|err| error(SymbolErrorKind::InvalidNumber(err)))
+            };
+
+            let mut fields = line.split_whitespace();
+            let name = fields.next().unwrap();
+            let address = fields
+                .next()
+                .ok_or_else(|| error(SymbolErrorKind::MissingField))?;
+
+            if name.parse::<i64>().is_ok() || name.contains(['+', '[', ']', ',']) {
+                return Err(error(SymbolErrorKind::InvalidName(name.to_string())));
+            }
+
+            let (start, len) = match address.split_once("..") {
+                Some((start, end)) => {
+                    let (start, end) = (number(start)?, number(end)?);
+
+                    if end <= start {
+                        return Err(error(SymbolErrorKind::EmptyRange));
+                    }
+
+                    (start, end - start)
+                }
+                None => (number(address)?, 1),
+            };
+
+            symbols.define(name, start, len);
+        }
+
+        Ok(symbols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Program;
+    use super::*;
+
+    const SIDECAR: &str = "
+        # Day two's inputs.
+        result 0
+        noun 1
+        verb 2
+        buffer 100..110
+        header 100  # the first cell of the buffer
+    ";
+
+    #[test]
+    fn names_addresses_and_offsets_into_ranges() {
+        let symbols = SIDECAR.parse::<Symbols>().unwrap();
+
+        assert_eq!(symbols.address("verb"), Some(2));
+        assert_eq!(symbols.name_of(1), Some("noun".to_string()));
+        assert_eq!(symbols.name_of(100), Some("header".to_string()));
+        assert_eq!(symbols.name_of(103), Some("buffer+3".to_string()));
+        assert_eq!(symbols.name_of(110), None);
+        assert_eq!(symbols.describe(2), "2 (verb)");
+        assert_eq!(symbols.describe(50), "50");
+        assert_eq!(symbols.starting_in(0, 4), vec!["result", "noun", "verb"]);
+    }
+
+    #[test]
+    fn round_trips_through_the_sidecar_format() {
+        let symbols = SIDECAR.parse::<Symbols>().unwrap();
+
+        assert_eq!(
+            symbols.to_string(),
+            "result 0\nnoun 1\nverb 2\nheader 100\nbuffer 100..110\n"
+        );
+        assert_eq!(symbols.to_string().parse::<Symbols>().unwrap(), symbols);
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let error = |text: &str| text.parse::<Symbols>().unwrap_err();
+
+        assert_eq!(error("noun 1\nverb").kind, SymbolErrorKind::MissingField);
+        assert_eq!(error("noun 1\nverb").line, 2);
+        assert!(matches!(
+            error("noun x").kind,
+            SymbolErrorKind::InvalidNumber(_)
+        ));
+        assert_eq!(
+            error("12 3").kind,
+            SymbolErrorKind::InvalidName("12".to_string())
+        );
+        assert_eq!(error("buffer 10..10").kind, SymbolErrorKind::EmptyRange);
+        assert_eq!(error("buffer 10..5").to_string(), "empty range on line 1");
+    }
+
+    #[test]
+    fn loads_the_sidecar_next_to_a_program() {
+        let symbols = Symbols::load_sidecar("src/two.txt").unwrap();
+
+        assert_eq!(
+            Symbols::sidecar_path("src/two.txt"),
+            PathBuf::from("src/two.sym")
+        );
+        assert_eq!(symbols.address("noun"), Some(1));
+        assert_eq!(symbols.address("verb"), Some(2));
+
+        assert!(Symbols::load_sidecar("src/missing.txt").unwrap().is_empty());
+    }
+
+    #[test]
+    fn reads_and_writes_memory_by_name() {
+        let mut program = Program::new(vec![1, 5, 6, 0, 99, 30, 40]);
+        program.set_symbols("result 0\nnoun 5\nverb 6".parse().unwrap());
+
+        program.write_symbol("noun", 3).unwrap();
+        program.run(vec![]);
+
+        assert_eq!(program.read_symbol("result"), Ok(43));
+        assert_eq!(
+            program.write_symbol("missing", 1).unwrap_err().to_string(),
+            "no symbol named \"missing\""
+        );
+        assert_eq!(
+            program.read_symbol("missing"),
+            Err(UnknownSymbol {
+                name: "missing".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn traces_with_symbol_names() {
+        let mut program = Program::new(vec![1, 5, 6, 0, 99, 30, 40]);
+        program.set_symbols("result 0\nnoun 5\nverb 6".parse().unwrap());
+
+        program.write_symbol("noun", 3).unwrap();
+
+        assert_eq!(
+            program.trace(vec![]),
+            vec![
+                "0000  add [noun], [verb], [result]  -> result = 43",
+                "0004  halt",
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unexpected opcode: 42 at 3 (broken)")]
+    fn names_the_address_in_errors() {
+        // jnz 1, broken
+        let mut program = Program::new(vec![1105, 1, 3, 42]);
+        program.set_symbols("broken 3".parse().unwrap());
+
+        program.run(vec![]);
+    }
+}
@@ -0,0 +1,500 @@
+use super::disassemble::{Decoded, Line, Operand};
+use super::{disassemble, Decompiler, Instruction, Mode, Program, Step};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+
+// The signature of a function generated by `transpile`. It runs from `pc`
+// until the program halts, returning `Ok` with the address of the halt, or
+// until it reaches an instruction it has no translation for, returning `Err`
+// with that address so the caller can interpret it instead.
+pub type Compiled =
+    fn(&mut [i64], usize, &mut dyn FnMut() -> i64, &mut dyn FnMut(i64)) -> Result<usize, usize>;
+
+pub fn transpile(code: &[i64], name: &str) -> String {
+    let decompiler = Decompiler::new(code);
+    let written = decompiler.written();
+
+    // Code that is only reached through computed jumps isn't found by
+    // following the control flow, so also take whatever a linear sweep
+    // decodes. An extra match arm is harmless if nothing ever jumps to it.
+    let mut instructions = disassemble(code)
+        .into_iter()
+        .filter_map(|line| match line {
+            Line::Instruction(decoded) => Some((decoded.address, decoded)),
+            Line::Data { .. } => None,
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    for decoded in decompiler.reachable() {
+        instructions.insert(decoded.address, decoded);
+    }
+
+    let mut out = String::new();
+
+    out.push_str("// Generated by `int_code::transpile`. Instructions that are overwritten at\n");
+    out.push_str("// runtime, or that use the relative base, have no arm here and are left\n");
+    out.push_str("// to the interpreter, as is any instruction whose cells no longer hold\n");
+    out.push_str("// what they did when this was generated or that goes past the end of\n");
+    out.push_str("// memory.\n");
+    out.push_str("#[allow(unused_variables, clippy::all)]\n");
+    out.push_str(&format!("pub fn {}(\n", name));
+    out.push_str("    m: &mut [i64],\n");
+    out.push_str("    mut pc: usize,\n");
+    out.push_str("    input: &mut dyn FnMut() -> i64,\n");
+    out.push_str("    output: &mut dyn FnMut(i64),\n");
+    out.push_str(") -> Result<usize, usize> {\n");
+    out.push_str("    loop {\n");
+    out.push_str("        pc = match pc {\n");
+
+    // The relative base lives in the interpreter, so anything that uses it
+    // runs there.
+    for decoded in instructions.values() {
+        let uses_relative_base = matches!(decoded.instruction, Instruction::AdjustRelativeBase(_))
+            || decoded
+                .operands
+                .iter()
+                .any(|operand| operand.mode == Mode::Relative);
+
+        let negative_address = decoded.operands.iter().enumerate().any(|(i, operand)| {
+            operand.mode == Mode::Position
+                && operand.value < 0
+                && !written.contains(&(decoded.address + 1 + i))
+        });
+
+        if written.contains(&decoded.address) || uses_relative_base || negative_address {
+            continue;
+        }
+
+        out.push_str(&arm(code, decoded, &written));
+    }
+
+    out.push_str("            _ => return Err(pc),\n");
+    out.push_str("        };\n");
+    out.push_str("    }\n");
+    out.push_str("}\n");
+
+    out
+}
+
+pub fn run_compiled<I>(compiled: Compiled, program: &mut Program, inputs: I) -> Vec<i64>
+where
+    I: IntoIterator<Item = i64>,
+{
+    // The generated code can't see devices, so programs with any mapped run
+    // in the interpreter.
+    if program.has_devices() {
+        return program.run(inputs);
+    }
+
+    let mut inputs = inputs.into_iter();
+    let mut outputs = Vec::new();
+
+    // The generated code works on a flat slice, so it runs on a copy. While
+    // the interpreter takes over for a single instruction, only the cells
+    // that instruction uses are passed between the two, and the rest is
+    // written back once the program stops.
+    let mut code = program.code.to_vec();
+
+    loop {
+        let result = {
+            let mut input = || inputs.next().expect("No input given");
+            let mut output = |x| outputs.push(x);
+
+            compiled(&mut code, program.i, &mut input, &mut output)
+        };
+
+        let pc = match result {
+            Ok(halt) => {
+                program.code.update_from(&code);
+                program.i = halt;
+                return outputs;
+            }
+            Err(pc) => pc,
+        };
+
+        program.i = pc;
+
+        let cells = Decoded::at(&code, pc).map(|decoded| {
+            let addresses = decoded
+                .operands
+                .iter()
+                .filter_map(|operand| match operand.mode {
+                    Mode::Position => usize::try_from(operand.value).ok(),
+                    Mode::Relative => usize::try_from(program.relative_base + operand.value).ok(),
+                    Mode::Immediate => None,
+                });
+
+            (decoded.address..decoded.next())
+                .chain(addresses)
+                .collect::<Vec<_>>()
+        });
+
+        match &cells {
+            Some(cells) => {
+                for &address in cells.iter().filter(|&&address| address < code.len()) {
+                    if program.code[address] != code[address] {
+                        program.code[address] = code[address];
+                    }
+                }
+            }
+            // Let the interpreter report what's wrong with it.
+            None => program.code.update_from(&code),
+        }
+
+        let step = program.step(&mut inputs);
+
+        match &cells {
+            Some(cells) => {
+                let len = program.code.len();
+                code.resize(len, 0);
+
+                for &address in cells.iter().filter(|&&address| address < len) {
+                    code[address] = program.code[address];
+                }
+            }
+            None => code = program.code.to_vec(),
+        }
+
+        match step {
+            Step::Executed => {}
+            Step::Output(output) => outputs.push(output),
+            Step::AwaitingInput => panic!("No input given"),
+            Step::Halted => {
+                program.code.update_from(&code);
+                return outputs;
+            }
+        }
+    }
+}
+
+fn arm(code: &[i64], decoded: &Decoded, written: &BTreeSet<usize>) -> String {
+    let read = |i: usize| read(decoded, i, written);
+    let write = || {
+        let i = decoded.instruction.write_param().unwrap();
+
+        cell(decoded, i, written)
+    };
+    let next = decoded.next();
+
+    let body = match decoded.instruction {
+        Instruction::Add(_) => format!("{} = {} + {};\n{}", write(), read(0), read(1), next),
+        Instruction::Multiply(_) => {
+            format!("{} = {} * {};\n{}", write(), read(0), read(1), next)
+        }
+        Instruction::LessThan(_) => format!(
+            "{} = ({} < {}) as i64;\n{}",
+            write(),
+            read(0),
+            read(1),
+            next
+        ),
+        Instruction::Equals(_) => format!(
+            "{} = ({} == {}) as i64;\n{}",
+            write(),
+            read(0),
+            read(1),
+            next
+        ),
+        Instruction::ReadInput(_) => format!("{} = input();\n{}", write(), next),
+        Instruction::WriteOutput(_) => format!("output({});\n{}", read(0), next),
+        Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_) => {
+            let op = match decoded.instruction {
+                Instruction::JumpIfTrue(_) => "!=",
+                _ => "==",
+            };
+
+            jump(decoded, op, written)
+        }
+        Instruction::AdjustRelativeBase(_) => unreachable!(),
+        Instruction::Halt => format!("return Ok({});", decoded.address),
+    };
+
+    let mut out = format!("            {} => {{\n", decoded.address);
+
+    // Writes through computed addresses and the relative base aren't in
+    // `written`, so any cell baked into the arm might have been overwritten
+    // since. If one has, the interpreter runs whatever is there now. It also
+    // takes over for addresses past the end of memory, which it grows.
+    let mut guards = (decoded.address..decoded.next())
+        .filter(|cell| !written.contains(cell))
+        .map(|cell| format!("m[{}] != {}", cell, code[cell]))
+        .collect::<Vec<_>>();
+
+    for (i, operand) in decoded.operands.iter().enumerate() {
+        let param_cell = decoded.address + 1 + i;
+
+        let guard = match operand.mode {
+            Mode::Position if written.contains(&param_cell) => {
+                format!("m[{}] as usize >= m.len()", param_cell)
+            }
+            Mode::Position if operand.value as usize >= code.len() => {
+                format!("m.len() <= {}", operand.value)
+            }
+            _ => continue,
+        };
+
+        if !guards.contains(&guard) {
+            guards.push(guard);
+        }
+    }
+
+    out.push_str(&format!(
+        "                if {} {{\n                    return Err({});\n                }}\n",
+        guards.join(" || "),
+        decoded.address
+    ));
+
+    for line in body.lines() {
+        out.push_str(&format!("                {}\n", line));
+    }
+
+    out.push_str("            }\n");
+
+    out
+}
+
+fn jump(decoded: &Decoded, op: &str, written: &BTreeSet<usize>) -> String {
+    let target = match constant(decoded, 1, written) {
+        Some(t) if t >= 0 => t.to_string(),
+        _ => format!("({}) as usize", read(decoded, 1, written)),
+    };
+
+    match constant(decoded, 0, written) {
+        Some(x) if (op == "!=") == (x != 0) => target,
+        Some(_) => decoded.next().to_string(),
+        None => format!(
+            "if {} {} 0 {{\n    {}\n}} else {{\n    {}\n}}",
+            read(decoded, 0, written),
+            op,
+            target,
+            decoded.next()
+        ),
+    }
+}
+
+// An immediate parameter whose cell is never written, so its value can be
+// baked into the generated code.
+fn constant(decoded: &Decoded, i: usize, written: &BTreeSet<usize>) -> Option<i64> {
+    let Operand { mode, value } = decoded.operands[i];
+
+    if mode == Mode::Immediate && !written.contains(&(decoded.address + 1 + i)) {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn read(decoded: &Decoded, i: usize, written: &BTreeSet<usize>) -> String {
+    match constant(decoded, i, written) {
+        Some(value) => value.to_string(),
+        None => cell(decoded, i, written),
+    }
+}
+
+// The memory cell a parameter refers to, going through memory for the
+// parameter itself when something might have overwritten it.
+fn cell(decoded: &Decoded, i: usize, written: &BTreeSet<usize>) -> String {
+    let param_cell = decoded.address + 1 + i;
+    let Operand { mode, value } = decoded.operands[i];
+
+    match mode {
+        Mode::Immediate => format!("m[{}]", param_cell),
+        Mode::Position if written.contains(&param_cell) => {
+            format!("m[m[{}] as usize]", param_cell)
+        }
+        Mode::Position => format!("m[{}]", value),
+        Mode::Relative => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::{read_to_string, write};
+
+    // `in [5]` then `add 99, 0, [0]`, which with an input of 6 puts a halt
+    // over the `out 55` at 6 before it runs.
+    const OVERWRITES_CODE: &str = "3,5,1101,99,0,0,104,55,99";
+
+    // `in [1000]; out [1000]`, past the end of the program.
+    const GROWS_MEMORY: &str = "3,1000,4,1000,99";
+
+    // Day nine's quine, which outputs itself through the relative base and
+    // counts in a cell past its end.
+    const QUINE: &str = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+
+    mod golden {
+        pub mod two {
+            include!("transpiled/two.rs");
+        }
+
+        pub mod five {
+            include!("transpiled/five.rs");
+        }
+
+        pub mod seven {
+            include!("transpiled/seven.rs");
+        }
+
+        pub mod overwrites_code {
+            include!("transpiled/overwrites_code.rs");
+        }
+
+        pub mod grows_memory {
+            include!("transpiled/grows_memory.rs");
+        }
+
+        pub mod quine {
+            include!("transpiled/quine.rs");
+        }
+    }
+
+    fn load(day: &str) -> Program {
+        read_to_string(format!("src/{}.txt", day))
+            .unwrap()
+            .parse::<Program>()
+            .unwrap()
+    }
+
+    // Set `UPDATE_GOLDEN=1` to regenerate the files after changing the
+    // generator, then check the new behaviour with the other tests.
+    fn assert_golden_is_current(name: &str, program: &Program) {
+        let path = format!("src/int_code/transpiled/{}.rs", name);
+        let generated = transpile(&program.code.to_vec(), "run");
+
+        if env::var("UPDATE_GOLDEN").is_ok() {
+            write(&path, &generated).unwrap();
+        }
+
+        assert!(
+            read_to_string(&path).unwrap_or_default() == generated,
+            "{} is out of date, rerun the tests with UPDATE_GOLDEN=1",
+            path
+        );
+    }
+
+    #[test]
+    fn generated_code_is_up_to_date() {
+        assert_golden_is_current("two", &load("two"));
+        assert_golden_is_current("five", &load("five"));
+        assert_golden_is_current("seven", &load("seven"));
+        assert_golden_is_current("overwrites_code", &OVERWRITES_CODE.parse().unwrap());
+        assert_golden_is_current("grows_memory", &GROWS_MEMORY.parse().unwrap());
+        assert_golden_is_current("quine", &QUINE.parse().unwrap());
+    }
+
+    #[test]
+    fn day_two_matches_the_interpreter() {
+        let mut expected = load("two");
+        expected.code[1] = 12;
+        expected.code[2] = 2;
+
+        let mut compiled = expected.clone();
+
+        expected.run(vec![]);
+        run_compiled(golden::two::run, &mut compiled, vec![]);
+
+        assert_eq!(compiled.code, expected.code);
+        assert_eq!(compiled.address(), expected.address());
+    }
+
+    #[test]
+    fn day_five_matches_the_interpreter() {
+        for &input in &[1, 5] {
+            let mut compiled = load("five");
+
+            assert_eq!(
+                run_compiled(golden::five::run, &mut compiled, vec![input]),
+                load("five").run(vec![input])
+            );
+            assert_eq!(compiled.code, {
+                let mut expected = load("five");
+                expected.run(vec![input]);
+                expected.code
+            });
+        }
+    }
+
+    #[test]
+    fn day_seven_matches_the_interpreter() {
+        for phase in 0..10 {
+            // The feedback loop phases keep reading signals until they've
+            // been round the loop ten times.
+            let inputs = std::iter::once(phase).chain(17..40).collect::<Vec<_>>();
+
+            assert_eq!(
+                run_compiled(golden::seven::run, &mut load("seven"), inputs.clone()),
+                load("seven").run(inputs)
+            );
+        }
+    }
+
+    #[test]
+    fn leaves_overwritten_instructions_to_the_interpreter() {
+        let generated = transpile(&load("five").code.to_vec(), "run");
+
+        assert!(!generated.contains("            6 => {"));
+        assert!(generated.contains(
+            "            0 => {\n                if m[0] != 3 || m[1] != 225 {\n                    \
+             return Err(0);\n                }\n                m[225] = input();\n                2\n"
+        ));
+    }
+
+    #[test]
+    fn reads_overwritten_parameters_from_memory() {
+        let generated = transpile(&load("seven").code.to_vec(), "run");
+
+        assert!(generated.contains(
+            "                }\n                (m[m[8] as usize]) as usize\n            }\n"
+        ));
+    }
+
+    #[test]
+    fn hands_instructions_overwritten_through_computed_addresses_back() {
+        let program = OVERWRITES_CODE.parse::<Program>().unwrap();
+
+        for input in [5, 6, 7] {
+            let mut compiled = program.clone();
+
+            assert_eq!(
+                run_compiled(golden::overwrites_code::run, &mut compiled, vec![input]),
+                program.clone().run(vec![input])
+            );
+        }
+
+        assert!(
+            run_compiled(golden::overwrites_code::run, &mut program.clone(), vec![6]).is_empty()
+        );
+    }
+
+    #[test]
+    fn leaves_addresses_past_the_end_to_the_interpreter() {
+        let program = GROWS_MEMORY.parse::<Program>().unwrap();
+        let mut compiled = program.clone();
+
+        assert_eq!(
+            run_compiled(golden::grows_memory::run, &mut compiled, vec![7]),
+            vec![7]
+        );
+        assert_eq!(compiled.code, {
+            let mut expected = program.clone();
+            expected.run(vec![7]);
+            expected.code
+        });
+    }
+
+    #[test]
+    fn alternates_with_the_interpreter_for_relative_instructions() {
+        let program = QUINE.parse::<Program>().unwrap();
+        let mut compiled = program.clone();
+        let mut interpreted = program.clone();
+
+        let outputs = run_compiled(golden::quine::run, &mut compiled, vec![]);
+
+        assert_eq!(outputs, program.code.to_vec());
+        assert_eq!(outputs, interpreted.run(vec![]));
+        assert_eq!(compiled.code, interpreted.code);
+        assert_eq!(compiled.relative_base(), interpreted.relative_base());
+    }
+}
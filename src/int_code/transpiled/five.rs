@@ -0,0 +1,1365 @@
+// Generated by `int_code::transpile`. Instructions that are overwritten at
+// runtime, or that use the relative base, have no arm here and are left
+// to the interpreter, as is any instruction whose cells no longer hold
+// what they did when this was generated or that goes past the end of
+// memory.
+#[allow(unused_variables, clippy::all)]
+pub fn run(
+    m: &mut [i64],
+    mut pc: usize,
+    input: &mut dyn FnMut() -> i64,
+    output: &mut dyn FnMut(i64),
+) -> Result<usize, usize> {
+    loop {
+        pc = match pc {
+            0 => {
+                if m[0] != 3 || m[1] != 225 {
+                    return Err(0);
+                }
+                m[225] = input();
+                2
+            }
+            2 => {
+                if m[2] != 1 || m[3] != 225 || m[4] != 6 || m[5] != 6 {
+                    return Err(2);
+                }
+                m[6] = m[225] + m[6];
+                6
+            }
+            7 => {
+                if m[7] != 1 || m[8] != 238 || m[9] != 225 || m[10] != 104 {
+                    return Err(7);
+                }
+                m[104] = m[238] + m[225];
+                11
+            }
+            12 => {
+                if m[12] != 1101 || m[13] != 86 || m[14] != 8 || m[15] != 225 {
+                    return Err(12);
+                }
+                m[225] = 86 + 8;
+                16
+            }
+            16 => {
+                if m[16] != 1101 || m[17] != 82 || m[18] != 69 || m[19] != 225 {
+                    return Err(16);
+                }
+                m[225] = 82 + 69;
+                20
+            }
+            20 => {
+                if m[20] != 101 || m[21] != 36 || m[22] != 65 || m[23] != 224 {
+                    return Err(20);
+                }
+                m[224] = 36 + m[65];
+                24
+            }
+            24 => {
+                if m[24] != 1001 || m[25] != 224 || m[26] != -106 || m[27] != 224 {
+                    return Err(24);
+                }
+                m[224] = m[224] + -106;
+                28
+            }
+            28 => {
+                if m[28] != 4 || m[29] != 224 {
+                    return Err(28);
+                }
+                output(m[224]);
+                30
+            }
+            30 => {
+                if m[30] != 1002 || m[31] != 223 || m[32] != 8 || m[33] != 223 {
+                    return Err(30);
+                }
+                m[223] = m[223] * 8;
+                34
+            }
+            34 => {
+                if m[34] != 1001 || m[35] != 224 || m[36] != 5 || m[37] != 224 {
+                    return Err(34);
+                }
+                m[224] = m[224] + 5;
+                38
+            }
+            38 => {
+                if m[38] != 1 || m[39] != 223 || m[40] != 224 || m[41] != 223 {
+                    return Err(38);
+                }
+                m[223] = m[223] + m[224];
+                42
+            }
+            42 => {
+                if m[42] != 102 || m[43] != 52 || m[44] != 148 || m[45] != 224 {
+                    return Err(42);
+                }
+                m[224] = 52 * m[148];
+                46
+            }
+            46 => {
+                if m[46] != 101 || m[47] != -1144 || m[48] != 224 || m[49] != 224 {
+                    return Err(46);
+                }
+                m[224] = -1144 + m[224];
+                50
+            }
+            50 => {
+                if m[50] != 4 || m[51] != 224 {
+                    return Err(50);
+                }
+                output(m[224]);
+                52
+            }
+            52 => {
+                if m[52] != 1002 || m[53] != 223 || m[54] != 8 || m[55] != 223 {
+                    return Err(52);
+                }
+                m[223] = m[223] * 8;
+                56
+            }
+            56 => {
+                if m[56] != 101 || m[57] != 1 || m[58] != 224 || m[59] != 224 {
+                    return Err(56);
+                }
+                m[224] = 1 + m[224];
+                60
+            }
+            60 => {
+                if m[60] != 1 || m[61] != 224 || m[62] != 223 || m[63] != 223 {
+                    return Err(60);
+                }
+                m[223] = m[224] + m[223];
+                64
+            }
+            64 => {
+                if m[64] != 1102 || m[65] != 70 || m[66] != 45 || m[67] != 225 {
+                    return Err(64);
+                }
+                m[225] = 70 * 45;
+                68
+            }
+            68 => {
+                if m[68] != 1002 || m[69] != 143 || m[70] != 48 || m[71] != 224 {
+                    return Err(68);
+                }
+                m[224] = m[143] * 48;
+                72
+            }
+            72 => {
+                if m[72] != 1001 || m[73] != 224 || m[74] != -1344 || m[75] != 224 {
+                    return Err(72);
+                }
+                m[224] = m[224] + -1344;
+                76
+            }
+            76 => {
+                if m[76] != 4 || m[77] != 224 {
+                    return Err(76);
+                }
+                output(m[224]);
+                78
+            }
+            78 => {
+                if m[78] != 102 || m[79] != 8 || m[80] != 223 || m[81] != 223 {
+                    return Err(78);
+                }
+                m[223] = 8 * m[223];
+                82
+            }
+            82 => {
+                if m[82] != 101 || m[83] != 7 || m[84] != 224 || m[85] != 224 {
+                    return Err(82);
+                }
+                m[224] = 7 + m[224];
+                86
+            }
+            86 => {
+                if m[86] != 1 || m[87] != 223 || m[88] != 224 || m[89] != 223 {
+                    return Err(86);
+                }
+                m[223] = m[223] + m[224];
+                90
+            }
+            90 => {
+                if m[90] != 1101 || m[91] != 69 || m[92] != 75 || m[93] != 225 {
+                    return Err(90);
+                }
+                m[225] = 69 + 75;
+                94
+            }
+            94 => {
+                if m[94] != 1001 || m[95] != 18 || m[96] != 85 || m[97] != 224 {
+                    return Err(94);
+                }
+                m[224] = m[18] + 85;
+                98
+            }
+            98 => {
+                if m[98] != 1001 || m[99] != 224 || m[100] != -154 || m[101] != 224 {
+                    return Err(98);
+                }
+                m[224] = m[224] + -154;
+                102
+            }
+            102 => {
+                if m[102] != 4 || m[103] != 224 {
+                    return Err(102);
+                }
+                output(m[224]);
+                104
+            }
+            104 => {
+                if m[104] != 102 || m[105] != 8 || m[106] != 223 || m[107] != 223 {
+                    return Err(104);
+                }
+                m[223] = 8 * m[223];
+                108
+            }
+            108 => {
+                if m[108] != 101 || m[109] != 2 || m[110] != 224 || m[111] != 224 {
+                    return Err(108);
+                }
+                m[224] = 2 + m[224];
+                112
+            }
+            112 => {
+                if m[112] != 1 || m[113] != 224 || m[114] != 223 || m[115] != 223 {
+                    return Err(112);
+                }
+                m[223] = m[224] + m[223];
+                116
+            }
+            116 => {
+                if m[116] != 1101 || m[117] != 15 || m[118] != 59 || m[119] != 225 {
+                    return Err(116);
+                }
+                m[225] = 15 + 59;
+                120
+            }
+            120 => {
+                if m[120] != 1102 || m[121] != 67 || m[122] != 42 || m[123] != 224 {
+                    return Err(120);
+                }
+                m[224] = 67 * 42;
+                124
+            }
+            124 => {
+                if m[124] != 101 || m[125] != -2814 || m[126] != 224 || m[127] != 224 {
+                    return Err(124);
+                }
+                m[224] = -2814 + m[224];
+                128
+            }
+            128 => {
+                if m[128] != 4 || m[129] != 224 {
+                    return Err(128);
+                }
+                output(m[224]);
+                130
+            }
+            130 => {
+                if m[130] != 1002 || m[131] != 223 || m[132] != 8 || m[133] != 223 {
+                    return Err(130);
+                }
+                m[223] = m[223] * 8;
+                134
+            }
+            134 => {
+                if m[134] != 101 || m[135] != 3 || m[136] != 224 || m[137] != 224 {
+                    return Err(134);
+                }
+                m[224] = 3 + m[224];
+                138
+            }
+            138 => {
+                if m[138] != 1 || m[139] != 223 || m[140] != 224 || m[141] != 223 {
+                    return Err(138);
+                }
+                m[223] = m[223] + m[224];
+                142
+            }
+            142 => {
+                if m[142] != 1101 || m[143] != 28 || m[144] != 63 || m[145] != 225 {
+                    return Err(142);
+                }
+                m[225] = 28 + 63;
+                146
+            }
+            146 => {
+                if m[146] != 1101 || m[147] != 45 || m[148] != 22 || m[149] != 225 {
+                    return Err(146);
+                }
+                m[225] = 45 + 22;
+                150
+            }
+            150 => {
+                if m[150] != 1101 || m[151] != 90 || m[152] != 16 || m[153] != 225 {
+                    return Err(150);
+                }
+                m[225] = 90 + 16;
+                154
+            }
+            154 => {
+                if m[154] != 2 || m[155] != 152 || m[156] != 92 || m[157] != 224 {
+                    return Err(154);
+                }
+                m[224] = m[152] * m[92];
+                158
+            }
+            158 => {
+                if m[158] != 1001 || m[159] != 224 || m[160] != -1200 || m[161] != 224 {
+                    return Err(158);
+                }
+                m[224] = m[224] + -1200;
+                162
+            }
+            162 => {
+                if m[162] != 4 || m[163] != 224 {
+                    return Err(162);
+                }
+                output(m[224]);
+                164
+            }
+            164 => {
+                if m[164] != 102 || m[165] != 8 || m[166] != 223 || m[167] != 223 {
+                    return Err(164);
+                }
+                m[223] = 8 * m[223];
+                168
+            }
+            168 => {
+                if m[168] != 101 || m[169] != 7 || m[170] != 224 || m[171] != 224 {
+                    return Err(168);
+                }
+                m[224] = 7 + m[224];
+                172
+            }
+            172 => {
+                if m[172] != 1 || m[173] != 223 || m[174] != 224 || m[175] != 223 {
+                    return Err(172);
+                }
+                m[223] = m[223] + m[224];
+                176
+            }
+            176 => {
+                if m[176] != 1101 || m[177] != 45 || m[178] != 28 || m[179] != 224 {
+                    return Err(176);
+                }
+                m[224] = 45 + 28;
+                180
+            }
+            180 => {
+                if m[180] != 1001 || m[181] != 224 || m[182] != -73 || m[183] != 224 {
+                    return Err(180);
+                }
+                m[224] = m[224] + -73;
+                184
+            }
+            184 => {
+                if m[184] != 4 || m[185] != 224 {
+                    return Err(184);
+                }
+                output(m[224]);
+                186
+            }
+            186 => {
+                if m[186] != 1002 || m[187] != 223 || m[188] != 8 || m[189] != 223 {
+                    return Err(186);
+                }
+                m[223] = m[223] * 8;
+                190
+            }
+            190 => {
+                if m[190] != 101 || m[191] != 7 || m[192] != 224 || m[193] != 224 {
+                    return Err(190);
+                }
+                m[224] = 7 + m[224];
+                194
+            }
+            194 => {
+                if m[194] != 1 || m[195] != 224 || m[196] != 223 || m[197] != 223 {
+                    return Err(194);
+                }
+                m[223] = m[224] + m[223];
+                198
+            }
+            198 => {
+                if m[198] != 1 || m[199] != 14 || m[200] != 118 || m[201] != 224 {
+                    return Err(198);
+                }
+                m[224] = m[14] + m[118];
+                202
+            }
+            202 => {
+                if m[202] != 101 || m[203] != -67 || m[204] != 224 || m[205] != 224 {
+                    return Err(202);
+                }
+                m[224] = -67 + m[224];
+                206
+            }
+            206 => {
+                if m[206] != 4 || m[207] != 224 {
+                    return Err(206);
+                }
+                output(m[224]);
+                208
+            }
+            208 => {
+                if m[208] != 1002 || m[209] != 223 || m[210] != 8 || m[211] != 223 {
+                    return Err(208);
+                }
+                m[223] = m[223] * 8;
+                212
+            }
+            212 => {
+                if m[212] != 1001 || m[213] != 224 || m[214] != 2 || m[215] != 224 {
+                    return Err(212);
+                }
+                m[224] = m[224] + 2;
+                216
+            }
+            216 => {
+                if m[216] != 1 || m[217] != 223 || m[218] != 224 || m[219] != 223 {
+                    return Err(216);
+                }
+                m[223] = m[223] + m[224];
+                220
+            }
+            220 => {
+                if m[220] != 4 || m[221] != 223 {
+                    return Err(220);
+                }
+                output(m[223]);
+                222
+            }
+            222 => {
+                if m[222] != 99 {
+                    return Err(222);
+                }
+                return Ok(222);
+            }
+            238 => {
+                if m[238] != 1105 || m[239] != 0 || m[240] != 99999 {
+                    return Err(238);
+                }
+                241
+            }
+            241 => {
+                if m[241] != 1105 || m[242] != 227 || m[243] != 247 {
+                    return Err(241);
+                }
+                247
+            }
+            244 => {
+                if m[244] != 1105 || m[245] != 1 || m[246] != 99999 {
+                    return Err(244);
+                }
+                99999
+            }
+            247 => {
+                if m[247] != 1005 || m[248] != 227 || m[249] != 99999 {
+                    return Err(247);
+                }
+                if m[227] != 0 {
+                    99999
+                } else {
+                    250
+                }
+            }
+            250 => {
+                if m[250] != 1005 || m[251] != 0 || m[252] != 256 {
+                    return Err(250);
+                }
+                if m[0] != 0 {
+                    256
+                } else {
+                    253
+                }
+            }
+            253 => {
+                if m[253] != 1105 || m[254] != 1 || m[255] != 99999 {
+                    return Err(253);
+                }
+                99999
+            }
+            256 => {
+                if m[256] != 1106 || m[257] != 227 || m[258] != 99999 {
+                    return Err(256);
+                }
+                259
+            }
+            259 => {
+                if m[259] != 1106 || m[260] != 0 || m[261] != 265 {
+                    return Err(259);
+                }
+                265
+            }
+            262 => {
+                if m[262] != 1105 || m[263] != 1 || m[264] != 99999 {
+                    return Err(262);
+                }
+                99999
+            }
+            265 => {
+                if m[265] != 1006 || m[266] != 0 || m[267] != 99999 {
+                    return Err(265);
+                }
+                if m[0] == 0 {
+                    99999
+                } else {
+                    268
+                }
+            }
+            268 => {
+                if m[268] != 1006 || m[269] != 227 || m[270] != 274 {
+                    return Err(268);
+                }
+                if m[227] == 0 {
+                    274
+                } else {
+                    271
+                }
+            }
+            271 => {
+                if m[271] != 1105 || m[272] != 1 || m[273] != 99999 {
+                    return Err(271);
+                }
+                99999
+            }
+            274 => {
+                if m[274] != 1105 || m[275] != 1 || m[276] != 280 {
+                    return Err(274);
+                }
+                280
+            }
+            277 => {
+                if m[277] != 1105 || m[278] != 1 || m[279] != 99999 {
+                    return Err(277);
+                }
+                99999
+            }
+            280 => {
+                if m[280] != 1 || m[281] != 225 || m[282] != 225 || m[283] != 225 {
+                    return Err(280);
+                }
+                m[225] = m[225] + m[225];
+                284
+            }
+            284 => {
+                if m[284] != 1101 || m[285] != 294 || m[286] != 0 || m[287] != 0 {
+                    return Err(284);
+                }
+                m[0] = 294 + 0;
+                288
+            }
+            288 => {
+                if m[288] != 105 || m[289] != 1 || m[290] != 0 {
+                    return Err(288);
+                }
+                (m[0]) as usize
+            }
+            291 => {
+                if m[291] != 1105 || m[292] != 1 || m[293] != 99999 {
+                    return Err(291);
+                }
+                99999
+            }
+            294 => {
+                if m[294] != 1106 || m[295] != 0 || m[296] != 300 {
+                    return Err(294);
+                }
+                300
+            }
+            297 => {
+                if m[297] != 1105 || m[298] != 1 || m[299] != 99999 {
+                    return Err(297);
+                }
+                99999
+            }
+            300 => {
+                if m[300] != 1 || m[301] != 225 || m[302] != 225 || m[303] != 225 {
+                    return Err(300);
+                }
+                m[225] = m[225] + m[225];
+                304
+            }
+            304 => {
+                if m[304] != 1101 || m[305] != 314 || m[306] != 0 || m[307] != 0 {
+                    return Err(304);
+                }
+                m[0] = 314 + 0;
+                308
+            }
+            308 => {
+                if m[308] != 106 || m[309] != 0 || m[310] != 0 {
+                    return Err(308);
+                }
+                (m[0]) as usize
+            }
+            311 => {
+                if m[311] != 1105 || m[312] != 1 || m[313] != 99999 {
+                    return Err(311);
+                }
+                99999
+            }
+            314 => {
+                if m[314] != 7 || m[315] != 677 || m[316] != 677 || m[317] != 224 {
+                    return Err(314);
+                }
+                m[224] = (m[677] < m[677]) as i64;
+                318
+            }
+            318 => {
+                if m[318] != 102 || m[319] != 2 || m[320] != 223 || m[321] != 223 {
+                    return Err(318);
+                }
+                m[223] = 2 * m[223];
+                322
+            }
+            322 => {
+                if m[322] != 1005 || m[323] != 224 || m[324] != 329 {
+                    return Err(322);
+                }
+                if m[224] != 0 {
+                    329
+                } else {
+                    325
+                }
+            }
+            325 => {
+                if m[325] != 1001 || m[326] != 223 || m[327] != 1 || m[328] != 223 {
+                    return Err(325);
+                }
+                m[223] = m[223] + 1;
+                329
+            }
+            329 => {
+                if m[329] != 1008 || m[330] != 226 || m[331] != 226 || m[332] != 224 {
+                    return Err(329);
+                }
+                m[224] = (m[226] == 226) as i64;
+                333
+            }
+            333 => {
+                if m[333] != 1002 || m[334] != 223 || m[335] != 2 || m[336] != 223 {
+                    return Err(333);
+                }
+                m[223] = m[223] * 2;
+                337
+            }
+            337 => {
+                if m[337] != 1005 || m[338] != 224 || m[339] != 344 {
+                    return Err(337);
+                }
+                if m[224] != 0 {
+                    344
+                } else {
+                    340
+                }
+            }
+            340 => {
+                if m[340] != 1001 || m[341] != 223 || m[342] != 1 || m[343] != 223 {
+                    return Err(340);
+                }
+                m[223] = m[223] + 1;
+                344
+            }
+            344 => {
+                if m[344] != 1107 || m[345] != 677 || m[346] != 226 || m[347] != 224 {
+                    return Err(344);
+                }
+                m[224] = (677 < 226) as i64;
+                348
+            }
+            348 => {
+                if m[348] != 1002 || m[349] != 223 || m[350] != 2 || m[351] != 223 {
+                    return Err(348);
+                }
+                m[223] = m[223] * 2;
+                352
+            }
+            352 => {
+                if m[352] != 1006 || m[353] != 224 || m[354] != 359 {
+                    return Err(352);
+                }
+                if m[224] == 0 {
+                    359
+                } else {
+                    355
+                }
+            }
+            355 => {
+                if m[355] != 1001 || m[356] != 223 || m[357] != 1 || m[358] != 223 {
+                    return Err(355);
+                }
+                m[223] = m[223] + 1;
+                359
+            }
+            359 => {
+                if m[359] != 107 || m[360] != 677 || m[361] != 677 || m[362] != 224 {
+                    return Err(359);
+                }
+                m[224] = (677 < m[677]) as i64;
+                363
+            }
+            363 => {
+                if m[363] != 102 || m[364] != 2 || m[365] != 223 || m[366] != 223 {
+                    return Err(363);
+                }
+                m[223] = 2 * m[223];
+                367
+            }
+            367 => {
+                if m[367] != 1005 || m[368] != 224 || m[369] != 374 {
+                    return Err(367);
+                }
+                if m[224] != 0 {
+                    374
+                } else {
+                    370
+                }
+            }
+            370 => {
+                if m[370] != 101 || m[371] != 1 || m[372] != 223 || m[373] != 223 {
+                    return Err(370);
+                }
+                m[223] = 1 + m[223];
+                374
+            }
+            374 => {
+                if m[374] != 1108 || m[375] != 677 || m[376] != 226 || m[377] != 224 {
+                    return Err(374);
+                }
+                m[224] = (677 == 226) as i64;
+                378
+            }
+            378 => {
+                if m[378] != 102 || m[379] != 2 || m[380] != 223 || m[381] != 223 {
+                    return Err(378);
+                }
+                m[223] = 2 * m[223];
+                382
+            }
+            382 => {
+                if m[382] != 1005 || m[383] != 224 || m[384] != 389 {
+                    return Err(382);
+                }
+                if m[224] != 0 {
+                    389
+                } else {
+                    385
+                }
+            }
+            385 => {
+                if m[385] != 1001 || m[386] != 223 || m[387] != 1 || m[388] != 223 {
+                    return Err(385);
+                }
+                m[223] = m[223] + 1;
+                389
+            }
+            389 => {
+                if m[389] != 1007 || m[390] != 677 || m[391] != 677 || m[392] != 224 {
+                    return Err(389);
+                }
+                m[224] = (m[677] < 677) as i64;
+                393
+            }
+            393 => {
+                if m[393] != 1002 || m[394] != 223 || m[395] != 2 || m[396] != 223 {
+                    return Err(393);
+                }
+                m[223] = m[223] * 2;
+                397
+            }
+            397 => {
+                if m[397] != 1005 || m[398] != 224 || m[399] != 404 {
+                    return Err(397);
+                }
+                if m[224] != 0 {
+                    404
+                } else {
+                    400
+                }
+            }
+            400 => {
+                if m[400] != 101 || m[401] != 1 || m[402] != 223 || m[403] != 223 {
+                    return Err(400);
+                }
+                m[223] = 1 + m[223];
+                404
+            }
+            404 => {
+                if m[404] != 1008 || m[405] != 677 || m[406] != 226 || m[407] != 224 {
+                    return Err(404);
+                }
+                m[224] = (m[677] == 226) as i64;
+                408
+            }
+            408 => {
+                if m[408] != 102 || m[409] != 2 || m[410] != 223 || m[411] != 223 {
+                    return Err(408);
+                }
+                m[223] = 2 * m[223];
+                412
+            }
+            412 => {
+                if m[412] != 1005 || m[413] != 224 || m[414] != 419 {
+                    return Err(412);
+                }
+                if m[224] != 0 {
+                    419
+                } else {
+                    415
+                }
+            }
+            415 => {
+                if m[415] != 101 || m[416] != 1 || m[417] != 223 || m[418] != 223 {
+                    return Err(415);
+                }
+                m[223] = 1 + m[223];
+                419
+            }
+            419 => {
+                if m[419] != 1108 || m[420] != 226 || m[421] != 677 || m[422] != 224 {
+                    return Err(419);
+                }
+                m[224] = (226 == 677) as i64;
+                423
+            }
+            423 => {
+                if m[423] != 102 || m[424] != 2 || m[425] != 223 || m[426] != 223 {
+                    return Err(423);
+                }
+                m[223] = 2 * m[223];
+                427
+            }
+            427 => {
+                if m[427] != 1006 || m[428] != 224 || m[429] != 434 {
+                    return Err(427);
+                }
+                if m[224] == 0 {
+                    434
+                } else {
+                    430
+                }
+            }
+            430 => {
+                if m[430] != 1001 || m[431] != 223 || m[432] != 1 || m[433] != 223 {
+                    return Err(430);
+                }
+                m[223] = m[223] + 1;
+                434
+            }
+            434 => {
+                if m[434] != 8 || m[435] != 677 || m[436] != 226 || m[437] != 224 {
+                    return Err(434);
+                }
+                m[224] = (m[677] == m[226]) as i64;
+                438
+            }
+            438 => {
+                if m[438] != 1002 || m[439] != 223 || m[440] != 2 || m[441] != 223 {
+                    return Err(438);
+                }
+                m[223] = m[223] * 2;
+                442
+            }
+            442 => {
+                if m[442] != 1005 || m[443] != 224 || m[444] != 449 {
+                    return Err(442);
+                }
+                if m[224] != 0 {
+                    449
+                } else {
+                    445
+                }
+            }
+            445 => {
+                if m[445] != 101 || m[446] != 1 || m[447] != 223 || m[448] != 223 {
+                    return Err(445);
+                }
+                m[223] = 1 + m[223];
+                449
+            }
+            449 => {
+                if m[449] != 1008 || m[450] != 677 || m[451] != 677 || m[452] != 224 {
+                    return Err(449);
+                }
+                m[224] = (m[677] == 677) as i64;
+                453
+            }
+            453 => {
+                if m[453] != 1002 || m[454] != 223 || m[455] != 2 || m[456] != 223 {
+                    return Err(453);
+                }
+                m[223] = m[223] * 2;
+                457
+            }
+            457 => {
+                if m[457] != 1006 || m[458] != 224 || m[459] != 464 {
+                    return Err(457);
+                }
+                if m[224] == 0 {
+                    464
+                } else {
+                    460
+                }
+            }
+            460 => {
+                if m[460] != 1001 || m[461] != 223 || m[462] != 1 || m[463] != 223 {
+                    return Err(460);
+                }
+                m[223] = m[223] + 1;
+                464
+            }
+            464 => {
+                if m[464] != 1108 || m[465] != 226 || m[466] != 226 || m[467] != 224 {
+                    return Err(464);
+                }
+                m[224] = (226 == 226) as i64;
+                468
+            }
+            468 => {
+                if m[468] != 1002 || m[469] != 223 || m[470] != 2 || m[471] != 223 {
+                    return Err(468);
+                }
+                m[223] = m[223] * 2;
+                472
+            }
+            472 => {
+                if m[472] != 1005 || m[473] != 224 || m[474] != 479 {
+                    return Err(472);
+                }
+                if m[224] != 0 {
+                    479
+                } else {
+                    475
+                }
+            }
+            475 => {
+                if m[475] != 1001 || m[476] != 223 || m[477] != 1 || m[478] != 223 {
+                    return Err(475);
+                }
+                m[223] = m[223] + 1;
+                479
+            }
+            479 => {
+                if m[479] != 1007 || m[480] != 226 || m[481] != 677 || m[482] != 224 {
+                    return Err(479);
+                }
+                m[224] = (m[226] < 677) as i64;
+                483
+            }
+            483 => {
+                if m[483] != 102 || m[484] != 2 || m[485] != 223 || m[486] != 223 {
+                    return Err(483);
+                }
+                m[223] = 2 * m[223];
+                487
+            }
+            487 => {
+                if m[487] != 1005 || m[488] != 224 || m[489] != 494 {
+                    return Err(487);
+                }
+                if m[224] != 0 {
+                    494
+                } else {
+                    490
+                }
+            }
+            490 => {
+                if m[490] != 1001 || m[491] != 223 || m[492] != 1 || m[493] != 223 {
+                    return Err(490);
+                }
+                m[223] = m[223] + 1;
+                494
+            }
+            494 => {
+                if m[494] != 1007 || m[495] != 226 || m[496] != 226 || m[497] != 224 {
+                    return Err(494);
+                }
+                m[224] = (m[226] < 226) as i64;
+                498
+            }
+            498 => {
+                if m[498] != 102 || m[499] != 2 || m[500] != 223 || m[501] != 223 {
+                    return Err(498);
+                }
+                m[223] = 2 * m[223];
+                502
+            }
+            502 => {
+                if m[502] != 1005 || m[503] != 224 || m[504] != 509 {
+                    return Err(502);
+                }
+                if m[224] != 0 {
+                    509
+                } else {
+                    505
+                }
+            }
+            505 => {
+                if m[505] != 101 || m[506] != 1 || m[507] != 223 || m[508] != 223 {
+                    return Err(505);
+                }
+                m[223] = 1 + m[223];
+                509
+            }
+            509 => {
+                if m[509] != 107 || m[510] != 677 || m[511] != 226 || m[512] != 224 {
+                    return Err(509);
+                }
+                m[224] = (677 < m[226]) as i64;
+                513
+            }
+            513 => {
+                if m[513] != 1002 || m[514] != 223 || m[515] != 2 || m[516] != 223 {
+                    return Err(513);
+                }
+                m[223] = m[223] * 2;
+                517
+            }
+            517 => {
+                if m[517] != 1006 || m[518] != 224 || m[519] != 524 {
+                    return Err(517);
+                }
+                if m[224] == 0 {
+                    524
+                } else {
+                    520
+                }
+            }
+            520 => {
+                if m[520] != 1001 || m[521] != 223 || m[522] != 1 || m[523] != 223 {
+                    return Err(520);
+                }
+                m[223] = m[223] + 1;
+                524
+            }
+            524 => {
+                if m[524] != 108 || m[525] != 677 || m[526] != 677 || m[527] != 224 {
+                    return Err(524);
+                }
+                m[224] = (677 == m[677]) as i64;
+                528
+            }
+            528 => {
+                if m[528] != 102 || m[529] != 2 || m[530] != 223 || m[531] != 223 {
+                    return Err(528);
+                }
+                m[223] = 2 * m[223];
+                532
+            }
+            532 => {
+                if m[532] != 1006 || m[533] != 224 || m[534] != 539 {
+                    return Err(532);
+                }
+                if m[224] == 0 {
+                    539
+                } else {
+                    535
+                }
+            }
+            535 => {
+                if m[535] != 101 || m[536] != 1 || m[537] != 223 || m[538] != 223 {
+                    return Err(535);
+                }
+                m[223] = 1 + m[223];
+                539
+            }
+            539 => {
+                if m[539] != 7 || m[540] != 677 || m[541] != 226 || m[542] != 224 {
+                    return Err(539);
+                }
+                m[224] = (m[677] < m[226]) as i64;
+                543
+            }
+            543 => {
+                if m[543] != 102 || m[544] != 2 || m[545] != 223 || m[546] != 223 {
+                    return Err(543);
+                }
+                m[223] = 2 * m[223];
+                547
+            }
+            547 => {
+                if m[547] != 1006 || m[548] != 224 || m[549] != 554 {
+                    return Err(547);
+                }
+                if m[224] == 0 {
+                    554
+                } else {
+                    550
+                }
+            }
+            550 => {
+                if m[550] != 1001 || m[551] != 223 || m[552] != 1 || m[553] != 223 {
+                    return Err(550);
+                }
+                m[223] = m[223] + 1;
+                554
+            }
+            554 => {
+                if m[554] != 1107 || m[555] != 226 || m[556] != 677 || m[557] != 224 {
+                    return Err(554);
+                }
+                m[224] = (226 < 677) as i64;
+                558
+            }
+            558 => {
+                if m[558] != 102 || m[559] != 2 || m[560] != 223 || m[561] != 223 {
+                    return Err(558);
+                }
+                m[223] = 2 * m[223];
+                562
+            }
+            562 => {
+                if m[562] != 1005 || m[563] != 224 || m[564] != 569 {
+                    return Err(562);
+                }
+                if m[224] != 0 {
+                    569
+                } else {
+                    565
+                }
+            }
+            565 => {
+                if m[565] != 101 || m[566] != 1 || m[567] != 223 || m[568] != 223 {
+                    return Err(565);
+                }
+                m[223] = 1 + m[223];
+                569
+            }
+            569 => {
+                if m[569] != 108 || m[570] != 677 || m[571] != 226 || m[572] != 224 {
+                    return Err(569);
+                }
+                m[224] = (677 == m[226]) as i64;
+                573
+            }
+            573 => {
+                if m[573] != 1002 || m[574] != 223 || m[575] != 2 || m[576] != 223 {
+                    return Err(573);
+                }
+                m[223] = m[223] * 2;
+                577
+            }
+            577 => {
+                if m[577] != 1006 || m[578] != 224 || m[579] != 584 {
+                    return Err(577);
+                }
+                if m[224] == 0 {
+                    584
+                } else {
+                    580
+                }
+            }
+            580 => {
+                if m[580] != 101 || m[581] != 1 || m[582] != 223 || m[583] != 223 {
+                    return Err(580);
+                }
+                m[223] = 1 + m[223];
+                584
+            }
+            584 => {
+                if m[584] != 108 || m[585] != 226 || m[586] != 226 || m[587] != 224 {
+                    return Err(584);
+                }
+                m[224] = (226 == m[226]) as i64;
+                588
+            }
+            588 => {
+                if m[588] != 102 || m[589] != 2 || m[590] != 223 || m[591] != 223 {
+                    return Err(588);
+                }
+                m[223] = 2 * m[223];
+                592
+            }
+            592 => {
+                if m[592] != 1006 || m[593] != 224 || m[594] != 599 {
+                    return Err(592);
+                }
+                if m[224] == 0 {
+                    599
+                } else {
+                    595
+                }
+            }
+            595 => {
+                if m[595] != 1001 || m[596] != 223 || m[597] != 1 || m[598] != 223 {
+                    return Err(595);
+                }
+                m[223] = m[223] + 1;
+                599
+            }
+            599 => {
+                if m[599] != 1107 || m[600] != 226 || m[601] != 226 || m[602] != 224 {
+                    return Err(599);
+                }
+                m[224] = (226 < 226) as i64;
+                603
+            }
+            603 => {
+                if m[603] != 102 || m[604] != 2 || m[605] != 223 || m[606] != 223 {
+                    return Err(603);
+                }
+                m[223] = 2 * m[223];
+                607
+            }
+            607 => {
+                if m[607] != 1006 || m[608] != 224 || m[609] != 614 {
+                    return Err(607);
+                }
+                if m[224] == 0 {
+                    614
+                } else {
+                    610
+                }
+            }
+            610 => {
+                if m[610] != 1001 || m[611] != 223 || m[612] != 1 || m[613] != 223 {
+                    return Err(610);
+                }
+                m[223] = m[223] + 1;
+                614
+            }
+            614 => {
+                if m[614] != 8 || m[615] != 226 || m[616] != 677 || m[617] != 224 {
+                    return Err(614);
+                }
+                m[224] = (m[226] == m[677]) as i64;
+                618
+            }
+            618 => {
+                if m[618] != 102 || m[619] != 2 || m[620] != 223 || m[621] != 223 {
+                    return Err(618);
+                }
+                m[223] = 2 * m[223];
+                622
+            }
+            622 => {
+                if m[622] != 1006 || m[623] != 224 || m[624] != 629 {
+                    return Err(622);
+                }
+                if m[224] == 0 {
+                    629
+                } else {
+                    625
+                }
+            }
+            625 => {
+                if m[625] != 1001 || m[626] != 223 || m[627] != 1 || m[628] != 223 {
+                    return Err(625);
+                }
+                m[223] = m[223] + 1;
+                629
+            }
+            629 => {
+                if m[629] != 107 || m[630] != 226 || m[631] != 226 || m[632] != 224 {
+                    return Err(629);
+                }
+                m[224] = (226 < m[226]) as i64;
+                633
+            }
+            633 => {
+                if m[633] != 102 || m[634] != 2 || m[635] != 223 || m[636] != 223 {
+                    return Err(633);
+                }
+                m[223] = 2 * m[223];
+                637
+            }
+            637 => {
+                if m[637] != 1005 || m[638] != 224 || m[639] != 644 {
+                    return Err(637);
+                }
+                if m[224] != 0 {
+                    644
+                } else {
+                    640
+                }
+            }
+            640 => {
+                if m[640] != 101 || m[641] != 1 || m[642] != 223 || m[643] != 223 {
+                    return Err(640);
+                }
+                m[223] = 1 + m[223];
+                644
+            }
+            644 => {
+                if m[644] != 8 || m[645] != 226 || m[646] != 226 || m[647] != 224 {
+                    return Err(644);
+                }
+                m[224] = (m[226] == m[226]) as i64;
+                648
+            }
+            648 => {
+                if m[648] != 102 || m[649] != 2 || m[650] != 223 || m[651] != 223 {
+                    return Err(648);
+                }
+                m[223] = 2 * m[223];
+                652
+            }
+            652 => {
+                if m[652] != 1006 || m[653] != 224 || m[654] != 659 {
+                    return Err(652);
+                }
+                if m[224] == 0 {
+                    659
+                } else {
+                    655
+                }
+            }
+            655 => {
+                if m[655] != 101 || m[656] != 1 || m[657] != 223 || m[658] != 223 {
+                    return Err(655);
+                }
+                m[223] = 1 + m[223];
+                659
+            }
+            659 => {
+                if m[659] != 7 || m[660] != 226 || m[661] != 677 || m[662] != 224 {
+                    return Err(659);
+                }
+                m[224] = (m[226] < m[677]) as i64;
+                663
+            }
+            663 => {
+                if m[663] != 102 || m[664] != 2 || m[665] != 223 || m[666] != 223 {
+                    return Err(663);
+                }
+                m[223] = 2 * m[223];
+                667
+            }
+            667 => {
+                if m[667] != 1005 || m[668] != 224 || m[669] != 674 {
+                    return Err(667);
+                }
+                if m[224] != 0 {
+                    674
+                } else {
+                    670
+                }
+            }
+            670 => {
+                if m[670] != 101 || m[671] != 1 || m[672] != 223 || m[673] != 223 {
+                    return Err(670);
+                }
+                m[223] = 1 + m[223];
+                674
+            }
+            674 => {
+                if m[674] != 4 || m[675] != 223 {
+                    return Err(674);
+                }
+                output(m[223]);
+                676
+            }
+            676 => {
+                if m[676] != 99 {
+                    return Err(676);
+                }
+                return Ok(676);
+            }
+            _ => return Err(pc),
+        };
+    }
+}
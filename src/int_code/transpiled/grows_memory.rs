@@ -0,0 +1,38 @@
+// Generated by `int_code::transpile`. Instructions that are overwritten at
+// runtime, or that use the relative base, have no arm here and are left
+// to the interpreter, as is any instruction whose cells no longer hold
+// what they did when this was generated or that goes past the end of
+// memory.
+#[allow(unused_variables, clippy::all)]
+pub fn run(
+    m: &mut [i64],
+    mut pc: usize,
+    input: &mut dyn FnMut() -> i64,
+    output: &mut dyn FnMut(i64),
+) -> Result<usize, usize> {
+    loop {
+        pc = match pc {
+            0 => {
+                if m[0] != 3 || m[1] != 1000 || m.len() <= 1000 {
+                    return Err(0);
+                }
+                m[1000] = input();
+                2
+            }
+            2 => {
+                if m[2] != 4 || m[3] != 1000 || m.len() <= 1000 {
+                    return Err(2);
+                }
+                output(m[1000]);
+                4
+            }
+            4 => {
+                if m[4] != 99 {
+                    return Err(4);
+                }
+                return Ok(4);
+            }
+            _ => return Err(pc),
+        };
+    }
+}
@@ -0,0 +1,38 @@
+// Generated by `int_code::transpile`. Instructions that are overwritten at
+// runtime, or that use the relative base, have no arm here and are left
+// to the interpreter, as is any instruction whose cells no longer hold
+// what they did when this was generated or that goes past the end of
+// memory.
+#[allow(unused_variables, clippy::all)]
+pub fn run(
+    m: &mut [i64],
+    mut pc: usize,
+    input: &mut dyn FnMut() -> i64,
+    output: &mut dyn FnMut(i64),
+) -> Result<usize, usize> {
+    loop {
+        pc = match pc {
+            2 => {
+                if m[2] != 1101 || m[3] != 99 || m[4] != 0 || m[5] as usize >= m.len() {
+                    return Err(2);
+                }
+                m[m[5] as usize] = 99 + 0;
+                6
+            }
+            6 => {
+                if m[6] != 104 || m[7] != 55 {
+                    return Err(6);
+                }
+                output(55);
+                8
+            }
+            8 => {
+                if m[8] != 99 {
+                    return Err(8);
+                }
+                return Ok(8);
+            }
+            _ => return Err(pc),
+        };
+    }
+}
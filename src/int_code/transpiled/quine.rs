@@ -0,0 +1,48 @@
+// Generated by `int_code::transpile`. Instructions that are overwritten at
+// runtime, or that use the relative base, have no arm here and are left
+// to the interpreter, as is any instruction whose cells no longer hold
+// what they did when this was generated or that goes past the end of
+// memory.
+#[allow(unused_variables, clippy::all)]
+pub fn run(
+    m: &mut [i64],
+    mut pc: usize,
+    input: &mut dyn FnMut() -> i64,
+    output: &mut dyn FnMut(i64),
+) -> Result<usize, usize> {
+    loop {
+        pc = match pc {
+            4 => {
+                if m[4] != 1001 || m[5] != 100 || m[6] != 1 || m[7] != 100 || m.len() <= 100 {
+                    return Err(4);
+                }
+                m[100] = m[100] + 1;
+                8
+            }
+            8 => {
+                if m[8] != 1008 || m[9] != 100 || m[10] != 16 || m[11] != 101 || m.len() <= 100 || m.len() <= 101 {
+                    return Err(8);
+                }
+                m[101] = (m[100] == 16) as i64;
+                12
+            }
+            12 => {
+                if m[12] != 1006 || m[13] != 101 || m[14] != 0 || m.len() <= 101 {
+                    return Err(12);
+                }
+                if m[101] == 0 {
+                    0
+                } else {
+                    15
+                }
+            }
+            15 => {
+                if m[15] != 99 {
+                    return Err(15);
+                }
+                return Ok(15);
+            }
+            _ => return Err(pc),
+        };
+    }
+}
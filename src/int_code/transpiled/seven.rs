@@ -0,0 +1,1335 @@
+// Generated by `int_code::transpile`. Instructions that are overwritten at
+// runtime, or that use the relative base, have no arm here and are left
+// to the interpreter, as is any instruction whose cells no longer hold
+// what they did when this was generated or that goes past the end of
+// memory.
+#[allow(unused_variables, clippy::all)]
+pub fn run(
+    m: &mut [i64],
+    mut pc: usize,
+    input: &mut dyn FnMut() -> i64,
+    output: &mut dyn FnMut(i64),
+) -> Result<usize, usize> {
+    loop {
+        pc = match pc {
+            0 => {
+                if m[0] != 3 || m[1] != 8 {
+                    return Err(0);
+                }
+                m[8] = input();
+                2
+            }
+            2 => {
+                if m[2] != 1001 || m[3] != 8 || m[4] != 10 || m[5] != 8 {
+                    return Err(2);
+                }
+                m[8] = m[8] + 10;
+                6
+            }
+            6 => {
+                if m[6] != 105 || m[7] != 1 || m[8] as usize >= m.len() {
+                    return Err(6);
+                }
+                (m[m[8] as usize]) as usize
+            }
+            15 => {
+                if m[15] != 106 || m[16] != 187 || m[17] != 268 {
+                    return Err(15);
+                }
+                18
+            }
+            20 => {
+                if m[20] != 99999 {
+                    return Err(20);
+                }
+                return Ok(20);
+            }
+            21 => {
+                if m[21] != 3 || m[22] != 9 {
+                    return Err(21);
+                }
+                m[9] = input();
+                23
+            }
+            23 => {
+                if m[23] != 101 || m[24] != 4 || m[25] != 9 || m[26] != 9 {
+                    return Err(23);
+                }
+                m[9] = 4 + m[9];
+                27
+            }
+            27 => {
+                if m[27] != 1002 || m[28] != 9 || m[29] != 2 || m[30] != 9 {
+                    return Err(27);
+                }
+                m[9] = m[9] * 2;
+                31
+            }
+            31 => {
+                if m[31] != 101 || m[32] != 5 || m[33] != 9 || m[34] != 9 {
+                    return Err(31);
+                }
+                m[9] = 5 + m[9];
+                35
+            }
+            35 => {
+                if m[35] != 1002 || m[36] != 9 || m[37] != 2 || m[38] != 9 {
+                    return Err(35);
+                }
+                m[9] = m[9] * 2;
+                39
+            }
+            39 => {
+                if m[39] != 101 || m[40] != 2 || m[41] != 9 || m[42] != 9 {
+                    return Err(39);
+                }
+                m[9] = 2 + m[9];
+                43
+            }
+            43 => {
+                if m[43] != 4 || m[44] != 9 {
+                    return Err(43);
+                }
+                output(m[9]);
+                45
+            }
+            45 => {
+                if m[45] != 99 {
+                    return Err(45);
+                }
+                return Ok(45);
+            }
+            46 => {
+                if m[46] != 3 || m[47] != 9 {
+                    return Err(46);
+                }
+                m[9] = input();
+                48
+            }
+            48 => {
+                if m[48] != 1002 || m[49] != 9 || m[50] != 5 || m[51] != 9 {
+                    return Err(48);
+                }
+                m[9] = m[9] * 5;
+                52
+            }
+            52 => {
+                if m[52] != 4 || m[53] != 9 {
+                    return Err(52);
+                }
+                output(m[9]);
+                54
+            }
+            54 => {
+                if m[54] != 99 {
+                    return Err(54);
+                }
+                return Ok(54);
+            }
+            55 => {
+                if m[55] != 3 || m[56] != 9 {
+                    return Err(55);
+                }
+                m[9] = input();
+                57
+            }
+            57 => {
+                if m[57] != 1001 || m[58] != 9 || m[59] != 2 || m[60] != 9 {
+                    return Err(57);
+                }
+                m[9] = m[9] + 2;
+                61
+            }
+            61 => {
+                if m[61] != 1002 || m[62] != 9 || m[63] != 4 || m[64] != 9 {
+                    return Err(61);
+                }
+                m[9] = m[9] * 4;
+                65
+            }
+            65 => {
+                if m[65] != 101 || m[66] != 2 || m[67] != 9 || m[68] != 9 {
+                    return Err(65);
+                }
+                m[9] = 2 + m[9];
+                69
+            }
+            69 => {
+                if m[69] != 1002 || m[70] != 9 || m[71] != 3 || m[72] != 9 {
+                    return Err(69);
+                }
+                m[9] = m[9] * 3;
+                73
+            }
+            73 => {
+                if m[73] != 4 || m[74] != 9 {
+                    return Err(73);
+                }
+                output(m[9]);
+                75
+            }
+            75 => {
+                if m[75] != 99 {
+                    return Err(75);
+                }
+                return Ok(75);
+            }
+            76 => {
+                if m[76] != 3 || m[77] != 9 {
+                    return Err(76);
+                }
+                m[9] = input();
+                78
+            }
+            78 => {
+                if m[78] != 1001 || m[79] != 9 || m[80] != 3 || m[81] != 9 {
+                    return Err(78);
+                }
+                m[9] = m[9] + 3;
+                82
+            }
+            82 => {
+                if m[82] != 1002 || m[83] != 9 || m[84] != 2 || m[85] != 9 {
+                    return Err(82);
+                }
+                m[9] = m[9] * 2;
+                86
+            }
+            86 => {
+                if m[86] != 4 || m[87] != 9 {
+                    return Err(86);
+                }
+                output(m[9]);
+                88
+            }
+            88 => {
+                if m[88] != 99 {
+                    return Err(88);
+                }
+                return Ok(88);
+            }
+            89 => {
+                if m[89] != 3 || m[90] != 9 {
+                    return Err(89);
+                }
+                m[9] = input();
+                91
+            }
+            91 => {
+                if m[91] != 1002 || m[92] != 9 || m[93] != 4 || m[94] != 9 {
+                    return Err(91);
+                }
+                m[9] = m[9] * 4;
+                95
+            }
+            95 => {
+                if m[95] != 1001 || m[96] != 9 || m[97] != 4 || m[98] != 9 {
+                    return Err(95);
+                }
+                m[9] = m[9] + 4;
+                99
+            }
+            99 => {
+                if m[99] != 102 || m[100] != 5 || m[101] != 9 || m[102] != 9 {
+                    return Err(99);
+                }
+                m[9] = 5 * m[9];
+                103
+            }
+            103 => {
+                if m[103] != 4 || m[104] != 9 {
+                    return Err(103);
+                }
+                output(m[9]);
+                105
+            }
+            105 => {
+                if m[105] != 99 {
+                    return Err(105);
+                }
+                return Ok(105);
+            }
+            106 => {
+                if m[106] != 3 || m[107] != 9 {
+                    return Err(106);
+                }
+                m[9] = input();
+                108
+            }
+            108 => {
+                if m[108] != 101 || m[109] != 1 || m[110] != 9 || m[111] != 9 {
+                    return Err(108);
+                }
+                m[9] = 1 + m[9];
+                112
+            }
+            112 => {
+                if m[112] != 4 || m[113] != 9 {
+                    return Err(112);
+                }
+                output(m[9]);
+                114
+            }
+            114 => {
+                if m[114] != 3 || m[115] != 9 {
+                    return Err(114);
+                }
+                m[9] = input();
+                116
+            }
+            116 => {
+                if m[116] != 102 || m[117] != 2 || m[118] != 9 || m[119] != 9 {
+                    return Err(116);
+                }
+                m[9] = 2 * m[9];
+                120
+            }
+            120 => {
+                if m[120] != 4 || m[121] != 9 {
+                    return Err(120);
+                }
+                output(m[9]);
+                122
+            }
+            122 => {
+                if m[122] != 3 || m[123] != 9 {
+                    return Err(122);
+                }
+                m[9] = input();
+                124
+            }
+            124 => {
+                if m[124] != 1001 || m[125] != 9 || m[126] != 2 || m[127] != 9 {
+                    return Err(124);
+                }
+                m[9] = m[9] + 2;
+                128
+            }
+            128 => {
+                if m[128] != 4 || m[129] != 9 {
+                    return Err(128);
+                }
+                output(m[9]);
+                130
+            }
+            130 => {
+                if m[130] != 3 || m[131] != 9 {
+                    return Err(130);
+                }
+                m[9] = input();
+                132
+            }
+            132 => {
+                if m[132] != 101 || m[133] != 2 || m[134] != 9 || m[135] != 9 {
+                    return Err(132);
+                }
+                m[9] = 2 + m[9];
+                136
+            }
+            136 => {
+                if m[136] != 4 || m[137] != 9 {
+                    return Err(136);
+                }
+                output(m[9]);
+                138
+            }
+            138 => {
+                if m[138] != 3 || m[139] != 9 {
+                    return Err(138);
+                }
+                m[9] = input();
+                140
+            }
+            140 => {
+                if m[140] != 1001 || m[141] != 9 || m[142] != 1 || m[143] != 9 {
+                    return Err(140);
+                }
+                m[9] = m[9] + 1;
+                144
+            }
+            144 => {
+                if m[144] != 4 || m[145] != 9 {
+                    return Err(144);
+                }
+                output(m[9]);
+                146
+            }
+            146 => {
+                if m[146] != 3 || m[147] != 9 {
+                    return Err(146);
+                }
+                m[9] = input();
+                148
+            }
+            148 => {
+                if m[148] != 101 || m[149] != 1 || m[150] != 9 || m[151] != 9 {
+                    return Err(148);
+                }
+                m[9] = 1 + m[9];
+                152
+            }
+            152 => {
+                if m[152] != 4 || m[153] != 9 {
+                    return Err(152);
+                }
+                output(m[9]);
+                154
+            }
+            154 => {
+                if m[154] != 3 || m[155] != 9 {
+                    return Err(154);
+                }
+                m[9] = input();
+                156
+            }
+            156 => {
+                if m[156] != 102 || m[157] != 2 || m[158] != 9 || m[159] != 9 {
+                    return Err(156);
+                }
+                m[9] = 2 * m[9];
+                160
+            }
+            160 => {
+                if m[160] != 4 || m[161] != 9 {
+                    return Err(160);
+                }
+                output(m[9]);
+                162
+            }
+            162 => {
+                if m[162] != 3 || m[163] != 9 {
+                    return Err(162);
+                }
+                m[9] = input();
+                164
+            }
+            164 => {
+                if m[164] != 102 || m[165] != 2 || m[166] != 9 || m[167] != 9 {
+                    return Err(164);
+                }
+                m[9] = 2 * m[9];
+                168
+            }
+            168 => {
+                if m[168] != 4 || m[169] != 9 {
+                    return Err(168);
+                }
+                output(m[9]);
+                170
+            }
+            170 => {
+                if m[170] != 3 || m[171] != 9 {
+                    return Err(170);
+                }
+                m[9] = input();
+                172
+            }
+            172 => {
+                if m[172] != 1002 || m[173] != 9 || m[174] != 2 || m[175] != 9 {
+                    return Err(172);
+                }
+                m[9] = m[9] * 2;
+                176
+            }
+            176 => {
+                if m[176] != 4 || m[177] != 9 {
+                    return Err(176);
+                }
+                output(m[9]);
+                178
+            }
+            178 => {
+                if m[178] != 3 || m[179] != 9 {
+                    return Err(178);
+                }
+                m[9] = input();
+                180
+            }
+            180 => {
+                if m[180] != 101 || m[181] != 1 || m[182] != 9 || m[183] != 9 {
+                    return Err(180);
+                }
+                m[9] = 1 + m[9];
+                184
+            }
+            184 => {
+                if m[184] != 4 || m[185] != 9 {
+                    return Err(184);
+                }
+                output(m[9]);
+                186
+            }
+            186 => {
+                if m[186] != 99 {
+                    return Err(186);
+                }
+                return Ok(186);
+            }
+            187 => {
+                if m[187] != 3 || m[188] != 9 {
+                    return Err(187);
+                }
+                m[9] = input();
+                189
+            }
+            189 => {
+                if m[189] != 102 || m[190] != 2 || m[191] != 9 || m[192] != 9 {
+                    return Err(189);
+                }
+                m[9] = 2 * m[9];
+                193
+            }
+            193 => {
+                if m[193] != 4 || m[194] != 9 {
+                    return Err(193);
+                }
+                output(m[9]);
+                195
+            }
+            195 => {
+                if m[195] != 3 || m[196] != 9 {
+                    return Err(195);
+                }
+                m[9] = input();
+                197
+            }
+            197 => {
+                if m[197] != 1002 || m[198] != 9 || m[199] != 2 || m[200] != 9 {
+                    return Err(197);
+                }
+                m[9] = m[9] * 2;
+                201
+            }
+            201 => {
+                if m[201] != 4 || m[202] != 9 {
+                    return Err(201);
+                }
+                output(m[9]);
+                203
+            }
+            203 => {
+                if m[203] != 3 || m[204] != 9 {
+                    return Err(203);
+                }
+                m[9] = input();
+                205
+            }
+            205 => {
+                if m[205] != 101 || m[206] != 1 || m[207] != 9 || m[208] != 9 {
+                    return Err(205);
+                }
+                m[9] = 1 + m[9];
+                209
+            }
+            209 => {
+                if m[209] != 4 || m[210] != 9 {
+                    return Err(209);
+                }
+                output(m[9]);
+                211
+            }
+            211 => {
+                if m[211] != 3 || m[212] != 9 {
+                    return Err(211);
+                }
+                m[9] = input();
+                213
+            }
+            213 => {
+                if m[213] != 101 || m[214] != 2 || m[215] != 9 || m[216] != 9 {
+                    return Err(213);
+                }
+                m[9] = 2 + m[9];
+                217
+            }
+            217 => {
+                if m[217] != 4 || m[218] != 9 {
+                    return Err(217);
+                }
+                output(m[9]);
+                219
+            }
+            219 => {
+                if m[219] != 3 || m[220] != 9 {
+                    return Err(219);
+                }
+                m[9] = input();
+                221
+            }
+            221 => {
+                if m[221] != 102 || m[222] != 2 || m[223] != 9 || m[224] != 9 {
+                    return Err(221);
+                }
+                m[9] = 2 * m[9];
+                225
+            }
+            225 => {
+                if m[225] != 4 || m[226] != 9 {
+                    return Err(225);
+                }
+                output(m[9]);
+                227
+            }
+            227 => {
+                if m[227] != 3 || m[228] != 9 {
+                    return Err(227);
+                }
+                m[9] = input();
+                229
+            }
+            229 => {
+                if m[229] != 102 || m[230] != 2 || m[231] != 9 || m[232] != 9 {
+                    return Err(229);
+                }
+                m[9] = 2 * m[9];
+                233
+            }
+            233 => {
+                if m[233] != 4 || m[234] != 9 {
+                    return Err(233);
+                }
+                output(m[9]);
+                235
+            }
+            235 => {
+                if m[235] != 3 || m[236] != 9 {
+                    return Err(235);
+                }
+                m[9] = input();
+                237
+            }
+            237 => {
+                if m[237] != 102 || m[238] != 2 || m[239] != 9 || m[240] != 9 {
+                    return Err(237);
+                }
+                m[9] = 2 * m[9];
+                241
+            }
+            241 => {
+                if m[241] != 4 || m[242] != 9 {
+                    return Err(241);
+                }
+                output(m[9]);
+                243
+            }
+            243 => {
+                if m[243] != 3 || m[244] != 9 {
+                    return Err(243);
+                }
+                m[9] = input();
+                245
+            }
+            245 => {
+                if m[245] != 1001 || m[246] != 9 || m[247] != 1 || m[248] != 9 {
+                    return Err(245);
+                }
+                m[9] = m[9] + 1;
+                249
+            }
+            249 => {
+                if m[249] != 4 || m[250] != 9 {
+                    return Err(249);
+                }
+                output(m[9]);
+                251
+            }
+            251 => {
+                if m[251] != 3 || m[252] != 9 {
+                    return Err(251);
+                }
+                m[9] = input();
+                253
+            }
+            253 => {
+                if m[253] != 101 || m[254] != 2 || m[255] != 9 || m[256] != 9 {
+                    return Err(253);
+                }
+                m[9] = 2 + m[9];
+                257
+            }
+            257 => {
+                if m[257] != 4 || m[258] != 9 {
+                    return Err(257);
+                }
+                output(m[9]);
+                259
+            }
+            259 => {
+                if m[259] != 3 || m[260] != 9 {
+                    return Err(259);
+                }
+                m[9] = input();
+                261
+            }
+            261 => {
+                if m[261] != 1002 || m[262] != 9 || m[263] != 2 || m[264] != 9 {
+                    return Err(261);
+                }
+                m[9] = m[9] * 2;
+                265
+            }
+            265 => {
+                if m[265] != 4 || m[266] != 9 {
+                    return Err(265);
+                }
+                output(m[9]);
+                267
+            }
+            267 => {
+                if m[267] != 99 {
+                    return Err(267);
+                }
+                return Ok(267);
+            }
+            268 => {
+                if m[268] != 3 || m[269] != 9 {
+                    return Err(268);
+                }
+                m[9] = input();
+                270
+            }
+            270 => {
+                if m[270] != 101 || m[271] != 1 || m[272] != 9 || m[273] != 9 {
+                    return Err(270);
+                }
+                m[9] = 1 + m[9];
+                274
+            }
+            274 => {
+                if m[274] != 4 || m[275] != 9 {
+                    return Err(274);
+                }
+                output(m[9]);
+                276
+            }
+            276 => {
+                if m[276] != 3 || m[277] != 9 {
+                    return Err(276);
+                }
+                m[9] = input();
+                278
+            }
+            278 => {
+                if m[278] != 101 || m[279] != 1 || m[280] != 9 || m[281] != 9 {
+                    return Err(278);
+                }
+                m[9] = 1 + m[9];
+                282
+            }
+            282 => {
+                if m[282] != 4 || m[283] != 9 {
+                    return Err(282);
+                }
+                output(m[9]);
+                284
+            }
+            284 => {
+                if m[284] != 3 || m[285] != 9 {
+                    return Err(284);
+                }
+                m[9] = input();
+                286
+            }
+            286 => {
+                if m[286] != 101 || m[287] != 2 || m[288] != 9 || m[289] != 9 {
+                    return Err(286);
+                }
+                m[9] = 2 + m[9];
+                290
+            }
+            290 => {
+                if m[290] != 4 || m[291] != 9 {
+                    return Err(290);
+                }
+                output(m[9]);
+                292
+            }
+            292 => {
+                if m[292] != 3 || m[293] != 9 {
+                    return Err(292);
+                }
+                m[9] = input();
+                294
+            }
+            294 => {
+                if m[294] != 1002 || m[295] != 9 || m[296] != 2 || m[297] != 9 {
+                    return Err(294);
+                }
+                m[9] = m[9] * 2;
+                298
+            }
+            298 => {
+                if m[298] != 4 || m[299] != 9 {
+                    return Err(298);
+                }
+                output(m[9]);
+                300
+            }
+            300 => {
+                if m[300] != 3 || m[301] != 9 {
+                    return Err(300);
+                }
+                m[9] = input();
+                302
+            }
+            302 => {
+                if m[302] != 1001 || m[303] != 9 || m[304] != 2 || m[305] != 9 {
+                    return Err(302);
+                }
+                m[9] = m[9] + 2;
+                306
+            }
+            306 => {
+                if m[306] != 4 || m[307] != 9 {
+                    return Err(306);
+                }
+                output(m[9]);
+                308
+            }
+            308 => {
+                if m[308] != 3 || m[309] != 9 {
+                    return Err(308);
+                }
+                m[9] = input();
+                310
+            }
+            310 => {
+                if m[310] != 1001 || m[311] != 9 || m[312] != 1 || m[313] != 9 {
+                    return Err(310);
+                }
+                m[9] = m[9] + 1;
+                314
+            }
+            314 => {
+                if m[314] != 4 || m[315] != 9 {
+                    return Err(314);
+                }
+                output(m[9]);
+                316
+            }
+            316 => {
+                if m[316] != 3 || m[317] != 9 {
+                    return Err(316);
+                }
+                m[9] = input();
+                318
+            }
+            318 => {
+                if m[318] != 1001 || m[319] != 9 || m[320] != 2 || m[321] != 9 {
+                    return Err(318);
+                }
+                m[9] = m[9] + 2;
+                322
+            }
+            322 => {
+                if m[322] != 4 || m[323] != 9 {
+                    return Err(322);
+                }
+                output(m[9]);
+                324
+            }
+            324 => {
+                if m[324] != 3 || m[325] != 9 {
+                    return Err(324);
+                }
+                m[9] = input();
+                326
+            }
+            326 => {
+                if m[326] != 102 || m[327] != 2 || m[328] != 9 || m[329] != 9 {
+                    return Err(326);
+                }
+                m[9] = 2 * m[9];
+                330
+            }
+            330 => {
+                if m[330] != 4 || m[331] != 9 {
+                    return Err(330);
+                }
+                output(m[9]);
+                332
+            }
+            332 => {
+                if m[332] != 3 || m[333] != 9 {
+                    return Err(332);
+                }
+                m[9] = input();
+                334
+            }
+            334 => {
+                if m[334] != 102 || m[335] != 2 || m[336] != 9 || m[337] != 9 {
+                    return Err(334);
+                }
+                m[9] = 2 * m[9];
+                338
+            }
+            338 => {
+                if m[338] != 4 || m[339] != 9 {
+                    return Err(338);
+                }
+                output(m[9]);
+                340
+            }
+            340 => {
+                if m[340] != 3 || m[341] != 9 {
+                    return Err(340);
+                }
+                m[9] = input();
+                342
+            }
+            342 => {
+                if m[342] != 102 || m[343] != 2 || m[344] != 9 || m[345] != 9 {
+                    return Err(342);
+                }
+                m[9] = 2 * m[9];
+                346
+            }
+            346 => {
+                if m[346] != 4 || m[347] != 9 {
+                    return Err(346);
+                }
+                output(m[9]);
+                348
+            }
+            348 => {
+                if m[348] != 99 {
+                    return Err(348);
+                }
+                return Ok(348);
+            }
+            349 => {
+                if m[349] != 3 || m[350] != 9 {
+                    return Err(349);
+                }
+                m[9] = input();
+                351
+            }
+            351 => {
+                if m[351] != 101 || m[352] != 1 || m[353] != 9 || m[354] != 9 {
+                    return Err(351);
+                }
+                m[9] = 1 + m[9];
+                355
+            }
+            355 => {
+                if m[355] != 4 || m[356] != 9 {
+                    return Err(355);
+                }
+                output(m[9]);
+                357
+            }
+            357 => {
+                if m[357] != 3 || m[358] != 9 {
+                    return Err(357);
+                }
+                m[9] = input();
+                359
+            }
+            359 => {
+                if m[359] != 102 || m[360] != 2 || m[361] != 9 || m[362] != 9 {
+                    return Err(359);
+                }
+                m[9] = 2 * m[9];
+                363
+            }
+            363 => {
+                if m[363] != 4 || m[364] != 9 {
+                    return Err(363);
+                }
+                output(m[9]);
+                365
+            }
+            365 => {
+                if m[365] != 3 || m[366] != 9 {
+                    return Err(365);
+                }
+                m[9] = input();
+                367
+            }
+            367 => {
+                if m[367] != 101 || m[368] != 2 || m[369] != 9 || m[370] != 9 {
+                    return Err(367);
+                }
+                m[9] = 2 + m[9];
+                371
+            }
+            371 => {
+                if m[371] != 4 || m[372] != 9 {
+                    return Err(371);
+                }
+                output(m[9]);
+                373
+            }
+            373 => {
+                if m[373] != 3 || m[374] != 9 {
+                    return Err(373);
+                }
+                m[9] = input();
+                375
+            }
+            375 => {
+                if m[375] != 101 || m[376] != 1 || m[377] != 9 || m[378] != 9 {
+                    return Err(375);
+                }
+                m[9] = 1 + m[9];
+                379
+            }
+            379 => {
+                if m[379] != 4 || m[380] != 9 {
+                    return Err(379);
+                }
+                output(m[9]);
+                381
+            }
+            381 => {
+                if m[381] != 3 || m[382] != 9 {
+                    return Err(381);
+                }
+                m[9] = input();
+                383
+            }
+            383 => {
+                if m[383] != 102 || m[384] != 2 || m[385] != 9 || m[386] != 9 {
+                    return Err(383);
+                }
+                m[9] = 2 * m[9];
+                387
+            }
+            387 => {
+                if m[387] != 4 || m[388] != 9 {
+                    return Err(387);
+                }
+                output(m[9]);
+                389
+            }
+            389 => {
+                if m[389] != 3 || m[390] != 9 {
+                    return Err(389);
+                }
+                m[9] = input();
+                391
+            }
+            391 => {
+                if m[391] != 1002 || m[392] != 9 || m[393] != 2 || m[394] != 9 {
+                    return Err(391);
+                }
+                m[9] = m[9] * 2;
+                395
+            }
+            395 => {
+                if m[395] != 4 || m[396] != 9 {
+                    return Err(395);
+                }
+                output(m[9]);
+                397
+            }
+            397 => {
+                if m[397] != 3 || m[398] != 9 {
+                    return Err(397);
+                }
+                m[9] = input();
+                399
+            }
+            399 => {
+                if m[399] != 102 || m[400] != 2 || m[401] != 9 || m[402] != 9 {
+                    return Err(399);
+                }
+                m[9] = 2 * m[9];
+                403
+            }
+            403 => {
+                if m[403] != 4 || m[404] != 9 {
+                    return Err(403);
+                }
+                output(m[9]);
+                405
+            }
+            405 => {
+                if m[405] != 3 || m[406] != 9 {
+                    return Err(405);
+                }
+                m[9] = input();
+                407
+            }
+            407 => {
+                if m[407] != 1001 || m[408] != 9 || m[409] != 2 || m[410] != 9 {
+                    return Err(407);
+                }
+                m[9] = m[9] + 2;
+                411
+            }
+            411 => {
+                if m[411] != 4 || m[412] != 9 {
+                    return Err(411);
+                }
+                output(m[9]);
+                413
+            }
+            413 => {
+                if m[413] != 3 || m[414] != 9 {
+                    return Err(413);
+                }
+                m[9] = input();
+                415
+            }
+            415 => {
+                if m[415] != 102 || m[416] != 2 || m[417] != 9 || m[418] != 9 {
+                    return Err(415);
+                }
+                m[9] = 2 * m[9];
+                419
+            }
+            419 => {
+                if m[419] != 4 || m[420] != 9 {
+                    return Err(419);
+                }
+                output(m[9]);
+                421
+            }
+            421 => {
+                if m[421] != 3 || m[422] != 9 {
+                    return Err(421);
+                }
+                m[9] = input();
+                423
+            }
+            423 => {
+                if m[423] != 101 || m[424] != 1 || m[425] != 9 || m[426] != 9 {
+                    return Err(423);
+                }
+                m[9] = 1 + m[9];
+                427
+            }
+            427 => {
+                if m[427] != 4 || m[428] != 9 {
+                    return Err(427);
+                }
+                output(m[9]);
+                429
+            }
+            429 => {
+                if m[429] != 99 {
+                    return Err(429);
+                }
+                return Ok(429);
+            }
+            430 => {
+                if m[430] != 3 || m[431] != 9 {
+                    return Err(430);
+                }
+                m[9] = input();
+                432
+            }
+            432 => {
+                if m[432] != 1001 || m[433] != 9 || m[434] != 1 || m[435] != 9 {
+                    return Err(432);
+                }
+                m[9] = m[9] + 1;
+                436
+            }
+            436 => {
+                if m[436] != 4 || m[437] != 9 {
+                    return Err(436);
+                }
+                output(m[9]);
+                438
+            }
+            438 => {
+                if m[438] != 3 || m[439] != 9 {
+                    return Err(438);
+                }
+                m[9] = input();
+                440
+            }
+            440 => {
+                if m[440] != 1001 || m[441] != 9 || m[442] != 1 || m[443] != 9 {
+                    return Err(440);
+                }
+                m[9] = m[9] + 1;
+                444
+            }
+            444 => {
+                if m[444] != 4 || m[445] != 9 {
+                    return Err(444);
+                }
+                output(m[9]);
+                446
+            }
+            446 => {
+                if m[446] != 3 || m[447] != 9 {
+                    return Err(446);
+                }
+                m[9] = input();
+                448
+            }
+            448 => {
+                if m[448] != 102 || m[449] != 2 || m[450] != 9 || m[451] != 9 {
+                    return Err(448);
+                }
+                m[9] = 2 * m[9];
+                452
+            }
+            452 => {
+                if m[452] != 4 || m[453] != 9 {
+                    return Err(452);
+                }
+                output(m[9]);
+                454
+            }
+            454 => {
+                if m[454] != 3 || m[455] != 9 {
+                    return Err(454);
+                }
+                m[9] = input();
+                456
+            }
+            456 => {
+                if m[456] != 102 || m[457] != 2 || m[458] != 9 || m[459] != 9 {
+                    return Err(456);
+                }
+                m[9] = 2 * m[9];
+                460
+            }
+            460 => {
+                if m[460] != 4 || m[461] != 9 {
+                    return Err(460);
+                }
+                output(m[9]);
+                462
+            }
+            462 => {
+                if m[462] != 3 || m[463] != 9 {
+                    return Err(462);
+                }
+                m[9] = input();
+                464
+            }
+            464 => {
+                if m[464] != 1001 || m[465] != 9 || m[466] != 1 || m[467] != 9 {
+                    return Err(464);
+                }
+                m[9] = m[9] + 1;
+                468
+            }
+            468 => {
+                if m[468] != 4 || m[469] != 9 {
+                    return Err(468);
+                }
+                output(m[9]);
+                470
+            }
+            470 => {
+                if m[470] != 3 || m[471] != 9 {
+                    return Err(470);
+                }
+                m[9] = input();
+                472
+            }
+            472 => {
+                if m[472] != 1001 || m[473] != 9 || m[474] != 1 || m[475] != 9 {
+                    return Err(472);
+                }
+                m[9] = m[9] + 1;
+                476
+            }
+            476 => {
+                if m[476] != 4 || m[477] != 9 {
+                    return Err(476);
+                }
+                output(m[9]);
+                478
+            }
+            478 => {
+                if m[478] != 3 || m[479] != 9 {
+                    return Err(478);
+                }
+                m[9] = input();
+                480
+            }
+            480 => {
+                if m[480] != 1001 || m[481] != 9 || m[482] != 1 || m[483] != 9 {
+                    return Err(480);
+                }
+                m[9] = m[9] + 1;
+                484
+            }
+            484 => {
+                if m[484] != 4 || m[485] != 9 {
+                    return Err(484);
+                }
+                output(m[9]);
+                486
+            }
+            486 => {
+                if m[486] != 3 || m[487] != 9 {
+                    return Err(486);
+                }
+                m[9] = input();
+                488
+            }
+            488 => {
+                if m[488] != 1002 || m[489] != 9 || m[490] != 2 || m[491] != 9 {
+                    return Err(488);
+                }
+                m[9] = m[9] * 2;
+                492
+            }
+            492 => {
+                if m[492] != 4 || m[493] != 9 {
+                    return Err(492);
+                }
+                output(m[9]);
+                494
+            }
+            494 => {
+                if m[494] != 3 || m[495] != 9 {
+                    return Err(494);
+                }
+                m[9] = input();
+                496
+            }
+            496 => {
+                if m[496] != 101 || m[497] != 2 || m[498] != 9 || m[499] != 9 {
+                    return Err(496);
+                }
+                m[9] = 2 + m[9];
+                500
+            }
+            500 => {
+                if m[500] != 4 || m[501] != 9 {
+                    return Err(500);
+                }
+                output(m[9]);
+                502
+            }
+            502 => {
+                if m[502] != 3 || m[503] != 9 {
+                    return Err(502);
+                }
+                m[9] = input();
+                504
+            }
+            504 => {
+                if m[504] != 101 || m[505] != 1 || m[506] != 9 || m[507] != 9 {
+                    return Err(504);
+                }
+                m[9] = 1 + m[9];
+                508
+            }
+            508 => {
+                if m[508] != 4 || m[509] != 9 {
+                    return Err(508);
+                }
+                output(m[9]);
+                510
+            }
+            510 => {
+                if m[510] != 99 {
+                    return Err(510);
+                }
+                return Ok(510);
+            }
+            _ => return Err(pc),
+        };
+    }
+}
@@ -0,0 +1,241 @@
+// Generated by `int_code::transpile`. Instructions that are overwritten at
+// runtime, or that use the relative base, have no arm here and are left
+// to the interpreter, as is any instruction whose cells no longer hold
+// what they did when this was generated or that goes past the end of
+// memory.
+#[allow(unused_variables, clippy::all)]
+pub fn run(
+    m: &mut [i64],
+    mut pc: usize,
+    input: &mut dyn FnMut() -> i64,
+    output: &mut dyn FnMut(i64),
+) -> Result<usize, usize> {
+    loop {
+        pc = match pc {
+            4 => {
+                if m[4] != 1 || m[5] != 1 || m[6] != 2 || m[7] != 3 {
+                    return Err(4);
+                }
+                m[3] = m[1] + m[2];
+                8
+            }
+            8 => {
+                if m[8] != 1 || m[9] != 3 || m[10] != 4 || m[11] != 3 {
+                    return Err(8);
+                }
+                m[3] = m[3] + m[4];
+                12
+            }
+            12 => {
+                if m[12] != 1 || m[13] != 5 || m[14] != 0 || m[15] != 3 {
+                    return Err(12);
+                }
+                m[3] = m[5] + m[0];
+                16
+            }
+            16 => {
+                if m[16] != 2 || m[17] != 1 || m[18] != 9 || m[19] as usize >= m.len() {
+                    return Err(16);
+                }
+                m[m[19] as usize] = m[1] * m[9];
+                20
+            }
+            20 => {
+                if m[20] != 1 || m[21] != 13 || m[22] != 19 || m[23] as usize >= m.len() {
+                    return Err(20);
+                }
+                m[m[23] as usize] = m[13] + m[19];
+                24
+            }
+            24 => {
+                if m[24] != 2 || m[25] != 23 || m[26] != 9 || m[27] as usize >= m.len() {
+                    return Err(24);
+                }
+                m[m[27] as usize] = m[23] * m[9];
+                28
+            }
+            28 => {
+                if m[28] != 1 || m[29] != 6 || m[30] != 27 || m[31] as usize >= m.len() {
+                    return Err(28);
+                }
+                m[m[31] as usize] = m[6] + m[27];
+                32
+            }
+            32 => {
+                if m[32] != 2 || m[33] != 10 || m[34] != 31 || m[35] as usize >= m.len() {
+                    return Err(32);
+                }
+                m[m[35] as usize] = m[10] * m[31];
+                36
+            }
+            36 => {
+                if m[36] != 1 || m[37] != 6 || m[38] != 35 || m[39] as usize >= m.len() {
+                    return Err(36);
+                }
+                m[m[39] as usize] = m[6] + m[35];
+                40
+            }
+            40 => {
+                if m[40] != 2 || m[41] != 9 || m[42] != 39 || m[43] as usize >= m.len() {
+                    return Err(40);
+                }
+                m[m[43] as usize] = m[9] * m[39];
+                44
+            }
+            44 => {
+                if m[44] != 1 || m[45] != 5 || m[46] != 43 || m[47] as usize >= m.len() {
+                    return Err(44);
+                }
+                m[m[47] as usize] = m[5] + m[43];
+                48
+            }
+            48 => {
+                if m[48] != 2 || m[49] != 47 || m[50] != 13 || m[51] as usize >= m.len() {
+                    return Err(48);
+                }
+                m[m[51] as usize] = m[47] * m[13];
+                52
+            }
+            52 => {
+                if m[52] != 2 || m[53] != 51 || m[54] != 10 || m[55] as usize >= m.len() {
+                    return Err(52);
+                }
+                m[m[55] as usize] = m[51] * m[10];
+                56
+            }
+            56 => {
+                if m[56] != 1 || m[57] != 55 || m[58] != 5 || m[59] as usize >= m.len() {
+                    return Err(56);
+                }
+                m[m[59] as usize] = m[55] + m[5];
+                60
+            }
+            60 => {
+                if m[60] != 1 || m[61] != 59 || m[62] != 9 || m[63] as usize >= m.len() {
+                    return Err(60);
+                }
+                m[m[63] as usize] = m[59] + m[9];
+                64
+            }
+            64 => {
+                if m[64] != 1 || m[65] != 63 || m[66] != 9 || m[67] as usize >= m.len() {
+                    return Err(64);
+                }
+                m[m[67] as usize] = m[63] + m[9];
+                68
+            }
+            68 => {
+                if m[68] != 2 || m[69] != 6 || m[70] != 67 || m[71] as usize >= m.len() {
+                    return Err(68);
+                }
+                m[m[71] as usize] = m[6] * m[67];
+                72
+            }
+            72 => {
+                if m[72] != 1 || m[73] != 5 || m[74] != 71 || m[75] as usize >= m.len() {
+                    return Err(72);
+                }
+                m[m[75] as usize] = m[5] + m[71];
+                76
+            }
+            76 => {
+                if m[76] != 1 || m[77] != 75 || m[78] != 6 || m[79] as usize >= m.len() {
+                    return Err(76);
+                }
+                m[m[79] as usize] = m[75] + m[6];
+                80
+            }
+            80 => {
+                if m[80] != 1 || m[81] != 6 || m[82] != 79 || m[83] as usize >= m.len() {
+                    return Err(80);
+                }
+                m[m[83] as usize] = m[6] + m[79];
+                84
+            }
+            84 => {
+                if m[84] != 1 || m[85] != 83 || m[86] != 9 || m[87] as usize >= m.len() {
+                    return Err(84);
+                }
+                m[m[87] as usize] = m[83] + m[9];
+                88
+            }
+            88 => {
+                if m[88] != 2 || m[89] != 87 || m[90] != 10 || m[91] as usize >= m.len() {
+                    return Err(88);
+                }
+                m[m[91] as usize] = m[87] * m[10];
+                92
+            }
+            92 => {
+                if m[92] != 2 || m[93] != 91 || m[94] != 10 || m[95] as usize >= m.len() {
+                    return Err(92);
+                }
+                m[m[95] as usize] = m[91] * m[10];
+                96
+            }
+            96 => {
+                if m[96] != 1 || m[97] != 95 || m[98] != 5 || m[99] as usize >= m.len() {
+                    return Err(96);
+                }
+                m[m[99] as usize] = m[95] + m[5];
+                100
+            }
+            100 => {
+                if m[100] != 1 || m[101] != 99 || m[102] != 13 || m[103] as usize >= m.len() {
+                    return Err(100);
+                }
+                m[m[103] as usize] = m[99] + m[13];
+                104
+            }
+            104 => {
+                if m[104] != 2 || m[105] != 103 || m[106] != 9 || m[107] as usize >= m.len() {
+                    return Err(104);
+                }
+                m[m[107] as usize] = m[103] * m[9];
+                108
+            }
+            108 => {
+                if m[108] != 1 || m[109] != 6 || m[110] != 107 || m[111] as usize >= m.len() {
+                    return Err(108);
+                }
+                m[m[111] as usize] = m[6] + m[107];
+                112
+            }
+            112 => {
+                if m[112] != 1 || m[113] != 111 || m[114] != 5 || m[115] as usize >= m.len() {
+                    return Err(112);
+                }
+                m[m[115] as usize] = m[111] + m[5];
+                116
+            }
+            116 => {
+                if m[116] != 1 || m[117] != 115 || m[118] != 2 || m[119] as usize >= m.len() {
+                    return Err(116);
+                }
+                m[m[119] as usize] = m[115] + m[2];
+                120
+            }
+            120 => {
+                if m[120] != 1 || m[121] != 5 || m[122] != 119 || m[123] != 0 {
+                    return Err(120);
+                }
+                m[0] = m[5] + m[119];
+                124
+            }
+            124 => {
+                if m[124] != 99 {
+                    return Err(124);
+                }
+                return Ok(124);
+            }
+            125 => {
+                if m[125] != 2 || m[126] != 0 || m[127] != 14 || m[128] != 0 {
+                    return Err(125);
+                }
+                m[0] = m[0] * m[14];
+                129
+            }
+            _ => return Err(pc),
+        };
+    }
+}
@@ -0,0 +1 @@
+pub mod int_code;
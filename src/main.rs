@@ -1,12 +1,21 @@
+use advent_of_code_2019::int_code;
+use std::fs::read_to_string;
+
+mod eleven;
+mod fifteen;
 mod five;
 mod four;
-mod int_code;
+mod nineteen;
 mod one;
 mod permutations;
 mod rolling_pairs;
 mod seven;
+mod seventeen;
 mod six;
+mod thirteen;
 mod three;
+mod twenty_five;
+mod twenty_one;
 mod two;
 
 fn main() {
@@ -23,6 +32,20 @@ fn main() {
     run_day("SIX", six::main);
 
     run_day("SEVEN", seven::main);
+
+    run_day("ELEVEN", eleven::main);
+
+    run_day("THIRTEEN", thirteen::main);
+
+    run_day("FIFTEEN", fifteen::main);
+
+    run_day("SEVENTEEN", seventeen::main);
+
+    run_day("NINETEEN", nineteen::main);
+
+    run_day("TWENTY-ONE", twenty_one::main);
+
+    run_day("TWENTY-FIVE", twenty_five::main);
 }
 
 fn run_day(name: &str, print_solutions: impl FnOnce()) {
@@ -31,6 +54,18 @@ fn run_day(name: &str, print_solutions: impl FnOnce()) {
     println!();
 }
 
+// Puzzle inputs are only checked in for the first week, so a later day says
+// when its input is missing instead of stopping the days after it.
+fn read_input(path: &str) -> Option<String> {
+    let input = read_to_string(path);
+
+    if input.is_err() {
+        println!("No puzzle input at {}", path);
+    }
+
+    input.ok()
+}
+
 fn print_day_heading(day: &str) {
     println!();
     println!(">>>");
@@ -0,0 +1,133 @@
+use super::int_code::*;
+use super::read_input;
+
+pub fn main() {
+    let input = match read_input("src/nineteen.txt") {
+        Some(input) => input,
+        None => return,
+    };
+
+    let drone = input.parse::<Program>().unwrap();
+
+    let mut scanner = Scanner::new(&drone);
+    let affected = scanner.count_affected(50);
+
+    println!("Part one:");
+    println!("Points affected in the closest 50x50 area: {}", affected);
+    println!("Drone deployments: {}", scanner.queries);
+
+    let mut scanner = Scanner::new(&drone);
+    let (x, y) = scanner.fit_square(100);
+
+    println!();
+    println!("Part two:");
+    println!("Closest 100x100 square: {}", x * 10000 + y);
+    println!("Drone deployments: {}", scanner.queries);
+}
+
+// Each query deploys a fresh drone, since the program halts after reporting
+// on a single point.
+struct Scanner {
+    drone: Program,
+    queries: usize,
+}
+
+impl Scanner {
+    fn new(drone: &Program) -> Scanner {
+        Scanner {
+            drone: drone.clone(),
+            queries: 0,
+        }
+    }
+
+    fn is_affected(&mut self, x: i64, y: i64) -> bool {
+        self.queries += 1;
+
+        self.drone.clone().run(vec![x, y]) == vec![1]
+    }
+
+    fn count_affected(&mut self, size: i64) -> usize {
+        let mut count = 0;
+
+        for y in 0..size {
+            for x in 0..size {
+                count += self.is_affected(x, y) as usize;
+            }
+        }
+
+        count
+    }
+
+    // Follows the left edge of the beam down, row by row, and for each row
+    // checks the one point diagonally opposite that would complete a square
+    // with its bottom-left corner on the edge. The edge only moves right, so
+    // each row costs a couple of queries rather than a full scan.
+    //
+    // Returns the top-left corner of the first square that fits.
+    fn fit_square(&mut self, size: i64) -> (i64, i64) {
+        let mut left = 0;
+
+        for y in size - 1.. {
+            // Close to the emitter the beam is thinner than one point, so
+            // some rows have nothing in them. Give up on a row after a while
+            // and carry on from the same place on the next one.
+            let edge = (left..=left + y + 1).find(|&x| self.is_affected(x, y));
+
+            let x = match edge {
+                Some(x) => x,
+                None => continue,
+            };
+
+            left = x;
+
+            if self.is_affected(x + size - 1, y - (size - 1)) {
+                return (x, y - (size - 1));
+            }
+        }
+
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A beam covering every point with x <= y <= 2x.
+    const WEDGE: &str = "3,100,3,101,7,101,100,102,1002,100,2,103,7,103,101,104,1,102,104,105,1008,105,0,106,4,106,99";
+
+    fn wedge() -> Program {
+        WEDGE.parse::<Program>().unwrap()
+    }
+
+    fn fits(scanner: &mut Scanner, (x, y): (i64, i64), size: i64) -> bool {
+        (y..y + size).all(|y| (x..x + size).all(|x| scanner.is_affected(x, y)))
+    }
+
+    #[test]
+    fn counts_affected_points_with_a_query_each() {
+        let mut scanner = Scanner::new(&wedge());
+
+        assert_eq!(scanner.count_affected(10), 30);
+        assert_eq!(scanner.queries, 100);
+    }
+
+    #[test]
+    fn finds_the_closest_square_that_fits() {
+        let mut scanner = Scanner::new(&wedge());
+
+        assert_eq!(scanner.fit_square(3), (4, 6));
+        assert!(fits(&mut scanner, (4, 6), 3));
+        assert!(!fits(&mut scanner, (3, 5), 3));
+        assert!(!fits(&mut scanner, (4, 5), 3));
+    }
+
+    #[test]
+    fn needs_far_fewer_queries_than_scanning() {
+        let mut scanner = Scanner::new(&wedge());
+        let (x, y) = scanner.fit_square(20);
+
+        assert_eq!((x, y), (38, 57));
+        assert!(scanner.queries < 200, "{} queries", scanner.queries);
+    }
+}
@@ -43,10 +43,9 @@ where
 
         while self.i < self.values.len() {
             if self.c[self.i] < self.i {
+                #[allow(unknown_lints, clippy::manual_is_multiple_of)]
                 let swap_index = if self.i % 2 == 0 { 0 } else { self.c[self.i] };
-                let x = self.values[swap_index];
-                self.values[swap_index] = self.values[self.i];
-                self.values[self.i] = x;
+                self.values.swap(swap_index, self.i);
 
                 self.c[self.i] += 1;
                 self.i = 0;
@@ -67,6 +66,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::identity_op)]
     fn returns_the_correct_number_of_permutations() {
         assert_eq!(
             Permutations::of(['a', 'b', 'c', 'd', 'e']).count(),
@@ -12,7 +12,7 @@ where
     Iter: Iterator,
     Iter::Item: Clone,
 {
-    fn rolling_pairs(&mut self) -> RollingPairsState<Iter>;
+    fn rolling_pairs(&mut self) -> RollingPairsState<'_, Iter>;
 }
 
 impl<Iter> RollingPairs<Iter> for Iter
@@ -20,7 +20,7 @@ where
     Iter: Iterator,
     Iter::Item: Clone,
 {
-    fn rolling_pairs(&mut self) -> RollingPairsState<Iter> {
+    fn rolling_pairs(&mut self) -> RollingPairsState<'_, Iter> {
         let prev_item = self.next();
 
         RollingPairsState {
@@ -72,7 +72,7 @@ mod tests {
 
     #[test]
     fn returns_nothing_for_iterators_of_one_item() {
-        let chars = vec!['a'];
+        let chars = ['a'];
 
         let res = chars
             .iter()
@@ -98,7 +98,7 @@ mod tests {
 
     #[test]
     fn provides_an_accurate_size_hint_for_iterators_of_one_item() {
-        let size_hint = vec![1].iter().rolling_pairs().size_hint();
+        let size_hint = [1].iter().rolling_pairs().size_hint();
 
         assert_eq!(size_hint, (0, Some(0)));
     }
@@ -1,10 +1,11 @@
 use super::int_code::*;
 use super::permutations::Permutations;
+use std::collections::HashMap;
 use std::fs::read_to_string;
 
 pub fn main() {
     let input = read_to_string("src/seven.txt").unwrap();
-    let program = input.trim().parse::<Program>().unwrap();
+    let program = input.parse::<Program>().unwrap();
 
     println!("Part one:");
     println!(
@@ -24,80 +25,50 @@ pub fn main() {
 fn max_thruster_signal(
     program: &Program,
     phases: [i64; 5],
-    run_amplifier: impl Fn(&Program, [i64; 5]) -> i64,
+    run_amplifiers: impl Fn([&Program; 5]) -> i64,
 ) -> i64 {
+    // Every ordering uses the same phase settings, so each amplifier only
+    // needs to read its phase once, up front.
+    let specialized = phases
+        .iter()
+        .map(|&phase| (phase, program.specialize(&[phase]).program))
+        .collect::<HashMap<_, _>>();
+
     Permutations::of(phases)
-        .map(|phases| run_amplifier(&program, phases))
+        .map(|phases| run_amplifiers(phases.map(|phase| &specialized[&phase])))
         .max()
         .unwrap()
 }
 
-fn run_amplifier_controller(program: &Program, [i, j, k, l, m]: [i64; 5]) -> i64 {
-    let mut input = 0;
-
-    input = first_output_of(program, [i, input]);
-    input = first_output_of(program, [j, input]);
-    input = first_output_of(program, [k, input]);
-    input = first_output_of(program, [l, input]);
-    input = first_output_of(program, [m, input]);
-
-    input
-}
-
-fn first_output_of(program: &Program, inputs: [i64; 2]) -> i64 {
-    let input = inputs.iter().copied();
-
-    *program.clone().run(input).get(0).unwrap()
+fn run_amplifier_controller(amplifiers: [&Program; 5]) -> i64 {
+    amplifiers
+        .iter()
+        .fold(0, |input, amplifier| first_output_of(amplifier, input))
 }
 
-fn run_amplifier_feedback_loop(program: &Program, [i, j, k, l, m]: [i64; 5]) -> i64 {
-    let mut a = FeedbackAmplifierProgram::new(program.clone(), vec![i, 0]);
-    let mut b = FeedbackAmplifierProgram::new(program.clone(), vec![j]);
-    let mut c = FeedbackAmplifierProgram::new(program.clone(), vec![k]);
-    let mut d = FeedbackAmplifierProgram::new(program.clone(), vec![l]);
-    let mut e = FeedbackAmplifierProgram::new(program.clone(), vec![m]);
-
-    let mut output = 0;
-
-    loop {
-        output = match a
-            .run_until_next_output(&mut b)
-            .and_then(|_| b.run_until_next_output(&mut c))
-            .and_then(|_| c.run_until_next_output(&mut d))
-            .and_then(|_| d.run_until_next_output(&mut e))
-            .and_then(|_| e.run_until_next_output(&mut a))
-        {
-            Some(x) => x,
-            None => return output,
-        };
-    }
+fn first_output_of(amplifier: &Program, input: i64) -> i64 {
+    *amplifier.clone().run(vec![input]).first().unwrap()
 }
 
-struct FeedbackAmplifierProgram {
-    program: Program,
-    inputs: Vec<i64>,
-}
+fn run_amplifier_feedback_loop(amplifiers: [&Program; 5]) -> i64 {
+    let mut network = Network::new();
 
-impl FeedbackAmplifierProgram {
-    fn new(program: Program, inputs: Vec<i64>) -> FeedbackAmplifierProgram {
-        FeedbackAmplifierProgram { program, inputs }
-    }
+    let amplifiers = amplifiers
+        .iter()
+        .map(|&amplifier| network.add_machine(amplifier.clone(), vec![]))
+        .collect::<Vec<_>>();
 
-    fn push_input(&mut self, input: i64) {
-        self.inputs.push(input);
+    for (&from, &to) in amplifiers.iter().zip(amplifiers.iter().cycle().skip(1)) {
+        network.connect(from, to);
     }
 
-    fn run_until_next_output(&mut self, output_to: &mut FeedbackAmplifierProgram) -> Option<i64> {
-        let mut inputs_iter = self.inputs.iter().copied();
-        let out = self.program.run_until_next_output(&mut inputs_iter);
-        self.inputs = inputs_iter.collect();
+    let first = amplifiers[0];
+    let last = amplifiers[amplifiers.len() - 1];
 
-        for x in out {
-            output_to.push_input(x);
-        }
+    network.push_input(first, 0);
+    network.mark_sink(last);
 
-        out
-    }
+    *network.run().sinks[&last].last().unwrap()
 }
 
 #[cfg(test)]
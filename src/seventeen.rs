@@ -0,0 +1,452 @@
+use super::int_code::*;
+use super::read_input;
+use std::fmt;
+
+pub fn main() {
+    let input = match read_input("src/seventeen.txt") {
+        Some(input) => input,
+        None => return,
+    };
+
+    let robot = input.parse::<Program>().unwrap();
+    let view = Scaffold::from_camera(&robot);
+
+    println!("{}", view.render());
+
+    println!("Part one:");
+    println!("Sum of alignment parameters: {}", view.alignment_sum());
+
+    let path = view.path();
+    assert!(!path.is_empty(), "The robot has nowhere to go");
+
+    let routines = compress(&path).expect("The path doesn't fit into three functions");
+
+    println!();
+    println!("Part two:");
+    print!("{}", routines.to_input());
+    println!("Dust collected: {}", collect_dust(&robot, &routines));
+}
+
+type Point = (i64, i64);
+
+// The robot's memory limit for each routine, not counting the newline.
+const MAX_ROUTINE_LEN: usize = 20;
+const MAX_FUNCTIONS: usize = 3;
+
+struct Scaffold {
+    rows: Vec<Vec<u8>>,
+}
+
+impl Scaffold {
+    fn from_camera(robot: &Program) -> Scaffold {
+        let view = robot
+            .clone()
+            .run(vec![])
+            .into_iter()
+            .map(|c| c as u8 as char)
+            .collect::<String>();
+
+        view.parse().unwrap()
+    }
+
+    fn get(&self, (x, y): Point) -> u8 {
+        if x < 0 || y < 0 {
+            return b'.';
+        }
+
+        self.rows
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .unwrap_or(b'.')
+    }
+
+    // The robot is always on the scaffold, whichever way it faces.
+    fn is_scaffold(&self, point: Point) -> bool {
+        matches!(self.get(point), b'#' | b'^' | b'v' | b'<' | b'>')
+    }
+
+    fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| (0..row.len()).map(move |x| (x as i64, y as i64)))
+    }
+
+    fn intersections(&self) -> Vec<Point> {
+        self.points()
+            .filter(|&(x, y)| {
+                [(x, y), (x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                    .iter()
+                    .all(|&point| self.is_scaffold(point))
+            })
+            .collect()
+    }
+
+    fn alignment_sum(&self) -> i64 {
+        self.intersections().iter().map(|(x, y)| x * y).sum()
+    }
+
+    // Drives forward as far as possible, then turns whichever way the
+    // scaffold continues, until it doesn't. Intersections are always crossed
+    // straight over, which covers every piece of scaffold. Only the first
+    // segment can have no turn, when the robot already faces along the
+    // scaffold, and the path is empty if it can't go anywhere.
+    fn path(&self) -> Vec<Segment> {
+        let (mut position, mut facing) = self
+            .points()
+            .find_map(|point| {
+                let facing = match self.get(point) {
+                    b'^' => (0, -1),
+                    b'v' => (0, 1),
+                    b'<' => (-1, 0),
+                    b'>' => (1, 0),
+                    _ => return None,
+                };
+
+                Some((point, facing))
+            })
+            .expect("No robot in the camera view");
+
+        let mut path = Vec::new();
+        let mut turn = None;
+
+        loop {
+            let mut forward = 0;
+
+            while self.is_scaffold((position.0 + facing.0, position.1 + facing.1)) {
+                position = (position.0 + facing.0, position.1 + facing.1);
+                forward += 1;
+            }
+
+            if forward > 0 {
+                path.push(Segment { turn, forward });
+            }
+
+            let (dx, dy) = facing;
+
+            turn = if self.is_scaffold((position.0 + dy, position.1 - dx)) {
+                facing = (dy, -dx);
+                Some(Turn::Left)
+            } else if self.is_scaffold((position.0 - dy, position.1 + dx)) {
+                facing = (-dy, dx);
+                Some(Turn::Right)
+            } else {
+                return path;
+            };
+        }
+    }
+
+    fn render(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| String::from_utf8_lossy(row) + "\n")
+            .collect()
+    }
+}
+
+impl std::str::FromStr for Scaffold {
+    type Err = std::convert::Infallible;
+
+    fn from_str(view: &str) -> Result<Scaffold, Self::Err> {
+        let rows = view
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.bytes().collect())
+            .collect();
+
+        Ok(Scaffold { rows })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Segment {
+    turn: Option<Turn>,
+    forward: usize,
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.turn {
+            Some(Turn::Left) => write!(f, "L,{}", self.forward),
+            Some(Turn::Right) => write!(f, "R,{}", self.forward),
+            None => write!(f, "{}", self.forward),
+        }
+    }
+}
+
+fn routine(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Routines {
+    // Indices into `functions`, in the order the robot should call them.
+    main: Vec<usize>,
+    functions: Vec<Vec<Segment>>,
+}
+
+impl Routines {
+    // Everything the robot asks for, one line each: the main routine, the
+    // three functions, and whether to show the video feed.
+    fn to_input(&self) -> String {
+        let main = self
+            .main
+            .iter()
+            .map(|&i| ((b'A' + i as u8) as char).to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut input = main + "\n";
+
+        for i in 0..MAX_FUNCTIONS {
+            let function = self.functions.get(i).map(|f| routine(f));
+
+            input.push_str(&function.unwrap_or_default());
+            input.push('\n');
+        }
+
+        input.push_str("n\n");
+        input
+    }
+}
+
+// Splits the path into at most three functions, each one used as often as
+// possible before a new one is started. Functions never split a segment,
+// which the real paths don't need.
+fn compress(path: &[Segment]) -> Option<Routines> {
+    let mut routines = Routines {
+        main: Vec::new(),
+        functions: Vec::new(),
+    };
+
+    if fit(path, &mut routines) {
+        Some(routines)
+    } else {
+        None
+    }
+}
+
+fn fit(rest: &[Segment], routines: &mut Routines) -> bool {
+    if rest.is_empty() {
+        return true;
+    }
+
+    // Each call is one letter and a comma.
+    if routines.main.len() * 2 + 1 > MAX_ROUTINE_LEN {
+        return false;
+    }
+
+    for i in 0..routines.functions.len() {
+        let len = routines.functions[i].len();
+
+        if rest.starts_with(&routines.functions[i]) {
+            routines.main.push(i);
+
+            if fit(&rest[len..], routines) {
+                return true;
+            }
+
+            routines.main.pop();
+        }
+    }
+
+    if routines.functions.len() == MAX_FUNCTIONS {
+        return false;
+    }
+
+    for len in 1..=rest.len() {
+        if routine(&rest[..len]).len() > MAX_ROUTINE_LEN {
+            break;
+        }
+
+        routines.main.push(routines.functions.len());
+        routines.functions.push(rest[..len].to_vec());
+
+        if fit(&rest[len..], routines) {
+            return true;
+        }
+
+        routines.functions.pop();
+        routines.main.pop();
+    }
+
+    false
+}
+
+// Wakes the robot up by writing 2 to address 0, and returns the last thing
+// it outputs, which is the amount of dust it collected.
+fn collect_dust(robot: &Program, routines: &Routines) -> i64 {
+    let mut robot = robot.clone();
+    robot.code[0] = 2;
+
+    let input = routines
+        .to_input()
+        .bytes()
+        .map(i64::from)
+        .collect::<Vec<_>>();
+
+    *robot
+        .run(input)
+        .last()
+        .expect("The robot didn't report anything")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERSECTIONS: &str = "\
+..#..........
+..#..........
+#######...###
+#.#...#...#.#
+#############
+..#...#...#..
+..#####...^..";
+
+    const LONG_PATH: &str = "\
+#######...#####
+#.....#...#...#
+#.....#...#...#
+......#...#...#
+......#...###.#
+......#.....#.#
+^########...#.#
+......#.#...#.#
+......#########
+........#...#..
+....#########..
+....#...#......
+....#...#......
+....#...#......
+....#####......";
+
+    fn expand(routines: &Routines) -> Vec<Segment> {
+        routines
+            .main
+            .iter()
+            .flat_map(|&i| routines.functions[i].iter().copied())
+            .collect()
+    }
+
+    fn outputting(text: &str) -> Vec<i64> {
+        text.bytes().flat_map(|c| vec![104, i64::from(c)]).collect()
+    }
+
+    #[test]
+    fn sums_alignment_parameters_of_intersections() {
+        let view = INTERSECTIONS.parse::<Scaffold>().unwrap();
+
+        assert_eq!(view.intersections(), vec![(2, 2), (2, 4), (6, 4), (10, 4)]);
+        assert_eq!(view.alignment_sum(), 76);
+    }
+
+    #[test]
+    fn reads_the_view_from_the_camera() {
+        let mut code = outputting(&(INTERSECTIONS.to_string() + "\n\n"));
+        code.push(99);
+
+        let view = Scaffold::from_camera(&Program::new(code));
+
+        assert_eq!(view.render(), INTERSECTIONS.to_string() + "\n");
+    }
+
+    #[test]
+    fn follows_the_scaffold_from_the_robot() {
+        let path = LONG_PATH.parse::<Scaffold>().unwrap().path();
+
+        assert_eq!(
+            routine(&path),
+            "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2"
+        );
+    }
+
+    #[test]
+    fn drives_straight_ahead_before_the_first_turn() {
+        let view = "\
+..#####
+..#...#
+..^...#
+......#
+....###"
+            .parse::<Scaffold>()
+            .unwrap();
+
+        let path = view.path();
+
+        assert_eq!(routine(&path), "2,R,4,R,4,R,2");
+        assert_eq!(expand(&compress(&path).unwrap()), path);
+    }
+
+    #[test]
+    fn finds_no_path_when_the_robot_has_nowhere_to_go() {
+        assert!("...\n.^.\n..."
+            .parse::<Scaffold>()
+            .unwrap()
+            .path()
+            .is_empty());
+    }
+
+    #[test]
+    fn compresses_the_path_into_three_functions() {
+        let path = LONG_PATH.parse::<Scaffold>().unwrap().path();
+        let routines = compress(&path).unwrap();
+
+        assert_eq!(expand(&routines), path);
+        assert!(routines.functions.len() <= MAX_FUNCTIONS);
+
+        for line in routines.to_input().lines() {
+            assert!(line.len() <= MAX_ROUTINE_LEN, "{:?} is too long", line);
+        }
+    }
+
+    #[test]
+    fn gives_up_when_three_functions_are_not_enough() {
+        // Every segment is different and at most four fit in a function.
+        let path = (10..26)
+            .map(|forward| Segment {
+                turn: Some(Turn::Left),
+                forward,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(compress(&path), None);
+    }
+
+    #[test]
+    fn feeds_the_routines_to_the_awake_robot() {
+        // Prints a prompt, then adds up every character it reads until it
+        // has read five lines, and reports the total.
+        let prompt = outputting("Main:\n");
+        let start = prompt.len() as i64;
+
+        let mut code = prompt;
+        #[rustfmt::skip]
+        code.extend_from_slice(&[
+            3, 100,                 // in [100]
+            1, 100, 101, 101,       // add [100], [101], [101]
+            1008, 100, 10, 102,     // eq [100], 10, [102]
+            1, 102, 103, 103,       // add [102], [103], [103]
+            1008, 103, 5, 102,      // eq [103], 5, [102]
+            1006, 102, start,       // jz [102], start
+            4, 101,                 // out [101]
+            99,
+        ]);
+
+        let routines = compress(&LONG_PATH.parse::<Scaffold>().unwrap().path()).unwrap();
+        let expected = routines.to_input().bytes().map(i64::from).sum::<i64>();
+
+        assert_eq!(collect_dust(&Program::new(code), &routines), expected);
+    }
+}
@@ -26,7 +26,7 @@ fn parse_orbits<'a>(input: &'a str) -> PlanetOrbits<'a> {
 fn count_direct_and_indirect_orbits(orbits: &PlanetOrbits) -> usize {
     orbits
         .keys()
-        .map(|planet| planets_orbits(&orbits, planet).count())
+        .map(|planet| planets_orbits(orbits, planet).count())
         .sum()
 }
 
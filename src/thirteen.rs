@@ -0,0 +1,229 @@
+use super::int_code::*;
+use super::read_input;
+use std::collections::HashMap;
+use std::env;
+use std::iter;
+use std::thread;
+use std::time::Duration;
+
+pub fn main() {
+    let input = match read_input("src/thirteen.txt") {
+        Some(input) => input,
+        None => return,
+    };
+
+    let game = input.parse::<Program>().unwrap();
+
+    let mut arcade = Arcade::new(&game);
+    arcade.run();
+
+    println!("Part one:");
+    println!("Block tiles on screen: {}", arcade.blocks());
+
+    // Set `ARCADE_RENDER=1` to watch the automatic player in the terminal.
+    let mut arcade = Arcade::free_play(&game);
+    arcade.render_live = env::var("ARCADE_RENDER").is_ok();
+
+    println!();
+    println!("Part two:");
+    println!("Final score: {}", arcade.run());
+}
+
+type Point = (i64, i64);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl Tile {
+    fn from_i64(id: i64) -> Tile {
+        match id {
+            0 => Tile::Empty,
+            1 => Tile::Wall,
+            2 => Tile::Block,
+            3 => Tile::Paddle,
+            4 => Tile::Ball,
+            _ => panic!("Unexpected tile: {}", id),
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Tile::Empty => ' ',
+            Tile::Wall => '#',
+            Tile::Block => '=',
+            Tile::Paddle => '-',
+            Tile::Ball => 'o',
+        }
+    }
+}
+
+struct Arcade {
+    game: Program,
+    screen: HashMap<Point, Tile>,
+    score: i64,
+    ball: Option<Point>,
+    paddle: Option<Point>,
+    render_live: bool,
+}
+
+impl Arcade {
+    fn new(game: &Program) -> Arcade {
+        Arcade {
+            game: game.clone(),
+            screen: HashMap::new(),
+            score: 0,
+            ball: None,
+            paddle: None,
+            render_live: false,
+        }
+    }
+
+    // Memory address 0 holds the number of quarters inserted, and 2 lets
+    // the game be played for free.
+    fn free_play(game: &Program) -> Arcade {
+        let mut arcade = Arcade::new(game);
+        arcade.game.code[0] = 2;
+        arcade
+    }
+
+    // Plays until the game halts and returns the final score.
+    fn run(&mut self) -> i64 {
+        while let Some([x, y, value]) = self.next_triple() {
+            if (x, y) == (-1, 0) {
+                self.score = value;
+                continue;
+            }
+
+            let tile = Tile::from_i64(value);
+            self.screen.insert((x, y), tile);
+
+            match tile {
+                Tile::Ball => {
+                    self.ball = Some((x, y));
+
+                    if self.render_live {
+                        println!("\x1b[2J\x1b[H{}", self.render());
+                        thread::sleep(Duration::from_millis(10));
+                    }
+                }
+                Tile::Paddle => self.paddle = Some((x, y)),
+                _ => {}
+            }
+        }
+
+        self.score
+    }
+
+    // The joystick is only read when the game asks for it, and the screen
+    // can change between outputs, so it's worked out afresh for each one.
+    fn next_triple(&mut self) -> Option<[i64; 3]> {
+        let mut triple = [0; 3];
+
+        for value in triple.iter_mut() {
+            let mut joystick = iter::repeat(self.joystick());
+
+            *value = self.game.run_until_next_output(&mut joystick)?;
+        }
+
+        Some(triple)
+    }
+
+    // Keeps the paddle under the ball: -1 for left, 0 to stay, 1 for right.
+    fn joystick(&self) -> i64 {
+        match (self.ball, self.paddle) {
+            (Some((ball, _)), Some((paddle, _))) => (ball - paddle).signum(),
+            _ => 0,
+        }
+    }
+
+    fn blocks(&self) -> usize {
+        self.screen
+            .values()
+            .filter(|&&tile| tile == Tile::Block)
+            .count()
+    }
+
+    fn render(&self) -> String {
+        let width = self.screen.keys().map(|p| p.0).max().unwrap_or(0);
+        let height = self.screen.keys().map(|p| p.1).max().unwrap_or(0);
+
+        let mut out = format!("Score: {}\n", self.score);
+
+        for y in 0..=height {
+            for x in 0..=width {
+                let tile = self.screen.get(&(x, y)).copied().unwrap_or(Tile::Empty);
+
+                out.push(tile.to_char());
+            }
+
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputting(values: &[i64]) -> Vec<i64> {
+        values.iter().flat_map(|&value| vec![104, value]).collect()
+    }
+
+    fn game(code: Vec<i64>) -> Program {
+        Program::new(code.into_iter().chain(iter::once(99)).collect())
+    }
+
+    #[test]
+    fn draws_tiles_onto_the_screen() {
+        let mut arcade = Arcade::new(&game(outputting(&[
+            0, 0, 1, 1, 0, 2, 2, 0, 2, 1, 1, 4, 2, 2, 3, 2, 0, 0,
+        ])));
+
+        arcade.run();
+
+        assert_eq!(arcade.blocks(), 1);
+        assert_eq!(arcade.ball, Some((1, 1)));
+        assert_eq!(arcade.paddle, Some((2, 2)));
+        assert_eq!(arcade.render(), "Score: 0\n#= \n o \n  -\n");
+    }
+
+    #[test]
+    fn keeps_the_last_score() {
+        let mut arcade = Arcade::new(&game(outputting(&[-1, 0, 12, 0, 0, 1, -1, 0, 345])));
+
+        assert_eq!(arcade.run(), 345);
+        assert_eq!(arcade.screen.len(), 1);
+    }
+
+    #[test]
+    fn moves_the_joystick_towards_the_ball() {
+        // Draws the ball right of the paddle, reads the joystick into a cell
+        // past the end of the program and reports it as the score.
+        let mut code = outputting(&[5, 0, 4, 3, 1, 3]);
+        code.extend_from_slice(&[3, 1000]);
+        code.extend_from_slice(&outputting(&[-1, 0]));
+        code.extend_from_slice(&[4, 1000]);
+
+        assert_eq!(Arcade::new(&game(code.clone())).run(), 1);
+
+        // And with the ball on the left instead.
+        code[1] = 1;
+
+        assert_eq!(Arcade::new(&game(code)).run(), -1);
+    }
+
+    #[test]
+    fn free_play_writes_two_quarters_into_address_zero() {
+        let arcade = Arcade::free_play(&game(outputting(&[])));
+
+        assert_eq!(arcade.game.code[0], 2);
+    }
+}
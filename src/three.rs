@@ -66,8 +66,8 @@ fn parse_move(input: &str) -> Move {
 }
 
 fn get_distance_to_nearest_crossover(a: &Route, b: &Route) -> i64 {
-    let path_a = places_visited(&a);
-    let path_b = places_visited(&b);
+    let path_a = places_visited(a);
+    let path_b = places_visited(b);
 
     crossover_points(path_a, path_b)
         .map(|(x, y)| x.abs() + y.abs())
@@ -84,6 +84,7 @@ where
     b.filter(move |place| places_a_visited.contains(place))
 }
 
+#[allow(unknown_lints, clippy::manual_repeat_n)]
 fn places_visited<'a>(route: &'a Route) -> impl Iterator<Item = (i64, i64)> + 'a {
     route
         .iter()
@@ -115,8 +116,8 @@ fn part_two(input: &str) -> i64 {
 }
 
 fn fewest_combined_steps_to_crossover(a: &Route, b: &Route) -> i64 {
-    let path_a = places_visited(&a).collect::<Vec<Coord>>();
-    let path_b = places_visited(&b).collect::<Vec<Coord>>();
+    let path_a = places_visited(a).collect::<Vec<Coord>>();
+    let path_b = places_visited(b).collect::<Vec<Coord>>();
 
     let crossovers = crossover_points(path_a.iter().cloned(), path_b.iter().cloned());
 
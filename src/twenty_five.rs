@@ -0,0 +1,647 @@
+use super::int_code::*;
+use super::read_input;
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::io::{self, BufRead, Write};
+
+pub fn main() {
+    let input = match read_input("src/twenty_five.txt") {
+        Some(input) => input,
+        None => return,
+    };
+
+    let terminal = Terminal::new(input.parse::<Program>().unwrap());
+
+    // Set `ADVENTURE_INTERACTIVE=1` to play it by hand instead.
+    if env::var("ADVENTURE_INTERACTIVE").is_ok() {
+        play(terminal);
+        return;
+    }
+
+    println!("Part one:");
+
+    match solve(terminal) {
+        Ok(ending) => println!("Password: {}", password(&ending).unwrap_or("not found")),
+        Err(err) => println!("Couldn't get through the checkpoint: {}", err),
+    }
+}
+
+// Items that are known to end the game or leave the droid unable to move.
+const TRAPS: [&str; 5] = [
+    "escape pod",
+    "giant electromagnet",
+    "infinite loop",
+    "molten lava",
+    "photons",
+];
+
+// Every combination of the items carried is tried at the checkpoint, so this
+// keeps it to a number that finishes, and well within a `u32` of subsets.
+const MAX_CARRIED: usize = 16;
+
+// Far more than any room description or command needs, so a program that
+// runs this long without asking for input is stuck.
+const MAX_STEPS_PER_COMMAND: usize = 1_000_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Status {
+    Waiting,
+    Ended,
+    Stuck,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reply {
+    text: String,
+    status: Status,
+}
+
+// Anything that can be played like the ship's droid. Cloning a game is how
+// a save point is made.
+trait Game: Clone {
+    // Sends one line, or nothing to read the opening text, and returns all
+    // the game printed before it wanted more input.
+    fn send(&mut self, command: &str) -> Reply;
+}
+
+#[derive(Debug, Clone)]
+struct Terminal {
+    program: Program,
+}
+
+impl Terminal {
+    fn new(program: Program) -> Terminal {
+        Terminal { program }
+    }
+}
+
+impl Game for Terminal {
+    fn send(&mut self, command: &str) -> Reply {
+        let mut input = command.bytes().map(i64::from).collect::<VecDeque<_>>();
+
+        if !command.is_empty() {
+            input.push_back(i64::from(b'\n'));
+        }
+
+        let mut input = input.into_iter();
+        let mut text = String::new();
+
+        for _ in 0..MAX_STEPS_PER_COMMAND {
+            let status = match self.program.step(&mut input) {
+                Step::Executed => continue,
+                Step::Output(c) => {
+                    text.push(c as u8 as char);
+                    continue;
+                }
+                Step::AwaitingInput => Status::Waiting,
+                Step::Halted => Status::Ended,
+            };
+
+            return Reply { text, status };
+        }
+
+        Reply {
+            text,
+            status: Status::Stuck,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Room {
+    name: String,
+    doors: Vec<String>,
+    items: Vec<String>,
+}
+
+// Every room described in the text, in order. Stepping onto the pressure
+// sensitive floor with the wrong weight describes two: the floor and the
+// room the droid is thrown back into.
+fn parse_rooms(text: &str) -> Vec<Room> {
+    let mut rooms = Vec::new();
+    let mut list = None;
+
+    for line in text.lines() {
+        if line.starts_with("== ") && line.ends_with(" ==") {
+            rooms.push(Room {
+                name: line[3..line.len() - 3].to_string(),
+                doors: Vec::new(),
+                items: Vec::new(),
+            });
+            list = None;
+        } else if line == "Doors here lead:" {
+            list = Some(true);
+        } else if line == "Items here:" {
+            list = Some(false);
+        } else if let (Some(is_doors), Some(entry), Some(room)) =
+            (list, line.strip_prefix("- "), rooms.last_mut())
+        {
+            if is_doors {
+                room.doors.push(entry.to_string());
+            } else {
+                room.items.push(entry.to_string());
+            }
+        }
+    }
+
+    rooms
+}
+
+fn password(ending: &str) -> Option<&str> {
+    let start = ending.find("typing ")? + "typing ".len();
+    let digits = ending[start..].find(|c: char| !c.is_ascii_digit())?;
+
+    Some(&ending[start..start + digits])
+}
+
+struct Explored<G> {
+    rooms: BTreeMap<String, (Room, G)>,
+    doors: BTreeMap<(String, String), String>,
+    // The room next to the pressure sensitive floor, and the way onto it.
+    checkpoint: Option<(String, String)>,
+}
+
+// Maps the whole ship by keeping a save point in every room and trying each
+// door from a copy of it, so the droid never has to walk back.
+fn explore<G: Game>(mut game: G) -> Result<(String, Explored<G>), String> {
+    let opening = game.send("");
+    let start = parse_rooms(&opening.text)
+        .pop()
+        .ok_or("the game didn't start in a room")?;
+
+    let mut explored = Explored {
+        rooms: BTreeMap::new(),
+        doors: BTreeMap::new(),
+        checkpoint: None,
+    };
+
+    let mut queue = VecDeque::new();
+    queue.push_back(start.name.clone());
+    explored
+        .rooms
+        .insert(start.name.clone(), (start.clone(), game));
+
+    while let Some(name) = queue.pop_front() {
+        let (room, save) = explored.rooms[&name].clone();
+
+        for door in &room.doors {
+            let mut game = save.clone();
+            let reply = game.send(door);
+
+            let arrived = match parse_rooms(&reply.text).pop() {
+                Some(arrived) if reply.status == Status::Waiting => arrived,
+                _ => return Err(format!("going {} from {} ended the game", door, name)),
+            };
+
+            if arrived.name == name {
+                explored.checkpoint = Some((name.clone(), door.clone()));
+                continue;
+            }
+
+            explored
+                .doors
+                .insert((name.clone(), door.clone()), arrived.name.clone());
+
+            if !explored.rooms.contains_key(&arrived.name) {
+                queue.push_back(arrived.name.clone());
+                explored.rooms.insert(arrived.name.clone(), (arrived, game));
+            }
+        }
+    }
+
+    Ok((start.name, explored))
+}
+
+// Tries taking the item from a copy of the save point, and then walking out
+// of the room, to catch traps that aren't on the known list.
+fn is_safe<G: Game>(item: &str, room: &Room, save: &G) -> bool {
+    if TRAPS.contains(&item) {
+        return false;
+    }
+
+    let mut game = save.clone();
+
+    if game.send(&format!("take {}", item)).status != Status::Waiting {
+        return false;
+    }
+
+    match room.doors.first() {
+        Some(door) => {
+            let reply = game.send(door);
+
+            reply.status == Status::Waiting && !reply.text.contains("can't move")
+        }
+        None => true,
+    }
+}
+
+fn route(doors: &BTreeMap<(String, String), String>, from: &str, to: &str) -> Option<Vec<String>> {
+    let mut previous = BTreeMap::new();
+    let mut queue = VecDeque::new();
+
+    queue.push_back(from.to_string());
+    previous.insert(from.to_string(), None);
+
+    while let Some(room) = queue.pop_front() {
+        if room == to {
+            let mut path = Vec::new();
+            let mut current = room;
+
+            while let Some(Some((before, door))) = previous.get(&current).cloned() {
+                path.push(door);
+                current = before;
+            }
+
+            path.reverse();
+            return Some(path);
+        }
+
+        for ((_, door), next) in doors.iter().filter(|((from, _), _)| *from == room) {
+            if !previous.contains_key(next) {
+                previous.insert(next.clone(), Some((room.clone(), door.clone())));
+                queue.push_back(next.clone());
+            }
+        }
+    }
+
+    None
+}
+
+// Explores the ship, picks up everything safe, carries it to the checkpoint
+// and tries every combination of items on the pressure sensitive floor.
+// Returns the text the game ended with.
+fn solve<G: Game>(game: G) -> Result<String, String> {
+    let (start, explored) = explore(game.clone())?;
+
+    let (checkpoint, floor) = explored
+        .checkpoint
+        .clone()
+        .ok_or("there's no pressure sensitive floor")?;
+
+    let mut game = game;
+    let mut here = start;
+    let mut carried = Vec::new();
+
+    game.send("");
+
+    for (name, (room, save)) in &explored.rooms {
+        let items = room
+            .items
+            .iter()
+            .filter(|item| is_safe(item, room, save))
+            .collect::<Vec<_>>();
+
+        if items.is_empty() {
+            continue;
+        }
+
+        walk(&mut game, &explored, &here, name)?;
+        here = name.clone();
+
+        for item in items {
+            game.send(&format!("take {}", item));
+            carried.push(item.clone());
+        }
+    }
+
+    walk(&mut game, &explored, &here, &checkpoint)?;
+
+    find_weight(&game, &carried, &floor)
+}
+
+// Tries dropping each combination of the items before stepping onto the
+// floor, until one is the right weight.
+fn find_weight<G: Game>(game: &G, carried: &[String], floor: &str) -> Result<String, String> {
+    if carried.len() > MAX_CARRIED {
+        return Err(format!(
+            "{} items is too many to try every combination of",
+            carried.len()
+        ));
+    }
+
+    for subset in 0..1u32 << carried.len() {
+        let mut attempt = game.clone();
+
+        for (i, item) in carried.iter().enumerate() {
+            if subset & (1 << i) == 0 {
+                attempt.send(&format!("drop {}", item));
+            }
+        }
+
+        let reply = attempt.send(floor);
+
+        if reply.status == Status::Ended {
+            return Ok(reply.text);
+        }
+    }
+
+    Err(format!(
+        "no combination of {:?} is the right weight",
+        carried
+    ))
+}
+
+fn walk<G: Game>(game: &mut G, explored: &Explored<G>, from: &str, to: &str) -> Result<(), String> {
+    let path = route(&explored.doors, from, to)
+        .ok_or_else(|| format!("no way from {} to {}", from, to))?;
+
+    for door in path {
+        game.send(&door);
+    }
+
+    Ok(())
+}
+
+// Plays from the terminal. `save` and `load` keep one save point.
+fn play(mut game: Terminal) {
+    let mut save = None;
+    let mut reply = game.send("");
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    while reply.status == Status::Waiting {
+        print!("{}", reply.text);
+        io::stdout().flush().unwrap();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return,
+        };
+
+        reply = match line.trim() {
+            "save" => {
+                save = Some(game.clone());
+                Reply {
+                    text: "Saved.\n\nCommand?\n".to_string(),
+                    status: Status::Waiting,
+                }
+            }
+            "load" => {
+                let text = match &save {
+                    Some(saved) => {
+                        game = saved.clone();
+                        "Loaded.\n\nCommand?\n"
+                    }
+                    None => "Nothing saved yet.\n\nCommand?\n",
+                };
+
+                Reply {
+                    text: text.to_string(),
+                    status: Status::Waiting,
+                }
+            }
+            command => game.send(command),
+        };
+    }
+
+    print!("{}", reply.text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    // A small ship in the same format as the real one. The floor wants
+    // exactly the mug and the hypercube.
+    #[derive(Debug, Clone)]
+    struct FakeShip {
+        room: &'static str,
+        items: BTreeMap<&'static str, Vec<&'static str>>,
+        inventory: BTreeSet<&'static str>,
+        stuck: bool,
+    }
+
+    const LAYOUT: [(&str, &str, &str); 8] = [
+        ("Hull Breach", "north", "Kitchen"),
+        ("Hull Breach", "east", "Lab"),
+        ("Kitchen", "south", "Hull Breach"),
+        ("Kitchen", "north", "Security Checkpoint"),
+        ("Lab", "west", "Hull Breach"),
+        ("Security Checkpoint", "south", "Kitchen"),
+        ("Security Checkpoint", "west", "Pressure-Sensitive Floor"),
+        ("Pressure-Sensitive Floor", "east", "Security Checkpoint"),
+    ];
+
+    impl FakeShip {
+        fn new() -> FakeShip {
+            let mut items = BTreeMap::new();
+            items.insert("Hull Breach", vec![]);
+            items.insert("Kitchen", vec!["mug", "giant electromagnet"]);
+            items.insert("Lab", vec!["hypercube", "escape pod", "ornament"]);
+            items.insert("Security Checkpoint", vec!["loop"]);
+
+            FakeShip {
+                room: "Hull Breach",
+                items,
+                inventory: BTreeSet::new(),
+                stuck: false,
+            }
+        }
+
+        fn describe(&self, room: &str) -> String {
+            let mut text = format!("\n\n\n== {} ==\nA room.\n\nDoors here lead:\n", room);
+
+            for (_, door, _) in LAYOUT.iter().filter(|(from, _, _)| *from == room) {
+                text.push_str(&format!("- {}\n", door));
+            }
+
+            let items = self.items.get(room).cloned().unwrap_or_default();
+
+            if !items.is_empty() {
+                text.push_str("\nItems here:\n");
+
+                for item in items {
+                    text.push_str(&format!("- {}\n", item));
+                }
+            }
+
+            text
+        }
+
+        fn waiting(text: String) -> Reply {
+            Reply {
+                text: text + "\nCommand?\n",
+                status: Status::Waiting,
+            }
+        }
+    }
+
+    impl Game for FakeShip {
+        fn send(&mut self, command: &str) -> Reply {
+            if command.is_empty() {
+                return FakeShip::waiting(self.describe(self.room));
+            }
+
+            if let Some(item) = command.strip_prefix("take ") {
+                let here = self.items.get_mut(self.room).unwrap();
+                here.retain(|i| *i != item);
+
+                match item {
+                    "escape pod" => {
+                        return Reply {
+                            text: "\nYou're launched into space! Bye!\n".to_string(),
+                            status: Status::Ended,
+                        }
+                    }
+                    "loop" => {
+                        return Reply {
+                            text: String::new(),
+                            status: Status::Stuck,
+                        }
+                    }
+                    "giant electromagnet" => self.stuck = true,
+                    _ => {}
+                }
+
+                let item = ["mug", "hypercube", "ornament", "giant electromagnet"]
+                    .iter()
+                    .find(|i| **i == item)
+                    .unwrap();
+
+                self.inventory.insert(item);
+                return FakeShip::waiting(format!("\nYou take the {}.\n", item));
+            }
+
+            if let Some(item) = command.strip_prefix("drop ") {
+                self.inventory.retain(|i| *i != item);
+                return FakeShip::waiting(format!("\nYou drop the {}.\n", item));
+            }
+
+            if self.stuck {
+                return FakeShip::waiting(
+                    "\nThe giant electromagnet is stuck to you.  You can't move!!\n".to_string(),
+                );
+            }
+
+            let (_, _, to) = LAYOUT
+                .iter()
+                .find(|(from, door, _)| *from == self.room && *door == command)
+                .unwrap();
+
+            if *to != "Pressure-Sensitive Floor" {
+                self.room = to;
+                return FakeShip::waiting(self.describe(to));
+            }
+
+            let wanted = vec!["hypercube", "mug"]
+                .into_iter()
+                .collect::<BTreeSet<_>>();
+
+            if self.inventory == wanted {
+                Reply {
+                    text: self.describe(to)
+                        + "\nAnalysis complete! You may proceed.\n\"Oh, hello! You should be able to get in by typing 1234 on the keypad at the main airlock.\"\n",
+                    status: Status::Ended,
+                }
+            } else {
+                FakeShip::waiting(
+                    self.describe(to)
+                        + "\nA loud, robotic voice says \"Alert! Droids on this ship are heavier than the detected value!\" and you are ejected back to the checkpoint.\n"
+                        + &self.describe(self.room),
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn parses_doors_and_items() {
+        let rooms = parse_rooms(&FakeShip::new().describe("Lab"));
+
+        assert_eq!(
+            rooms,
+            vec![Room {
+                name: "Lab".to_string(),
+                doors: vec!["west".to_string()],
+                items: vec![
+                    "hypercube".to_string(),
+                    "escape pod".to_string(),
+                    "ornament".to_string()
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn maps_the_ship_and_finds_the_checkpoint() {
+        let (start, explored) = explore(FakeShip::new()).unwrap();
+
+        assert_eq!(start, "Hull Breach");
+        assert_eq!(
+            explored.rooms.keys().collect::<Vec<_>>(),
+            vec!["Hull Breach", "Kitchen", "Lab", "Security Checkpoint"]
+        );
+        assert_eq!(
+            explored.checkpoint,
+            Some(("Security Checkpoint".to_string(), "west".to_string()))
+        );
+        assert_eq!(
+            route(&explored.doors, "Lab", "Security Checkpoint"),
+            Some(vec![
+                "west".to_string(),
+                "north".to_string(),
+                "north".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn avoids_known_and_newly_found_traps() {
+        let (_, explored) = explore(FakeShip::new()).unwrap();
+        let safe = |room: &str, item: &str| {
+            let (room, save) = &explored.rooms[room];
+            is_safe(item, room, save)
+        };
+
+        assert!(safe("Kitchen", "mug"));
+        assert!(!safe("Kitchen", "giant electromagnet"));
+        assert!(!safe("Lab", "escape pod"));
+        assert!(!safe("Security Checkpoint", "loop"));
+    }
+
+    #[test]
+    fn tries_item_combinations_until_the_floor_lets_the_droid_through() {
+        let ending = solve(FakeShip::new()).unwrap();
+
+        assert!(ending.contains("Analysis complete!"));
+        assert_eq!(password(&ending), Some("1234"));
+    }
+
+    #[test]
+    fn refuses_to_try_the_combinations_of_too_many_items() {
+        let carried = (0..40).map(|i| format!("item {}", i)).collect::<Vec<_>>();
+
+        assert_eq!(
+            find_weight(&FakeShip::new(), &carried, "north"),
+            Err("40 items is too many to try every combination of".to_string())
+        );
+    }
+
+    #[test]
+    fn plays_intcode_programs_over_ascii() {
+        // Prints "ok" and a newline, reads one character and prints it back.
+        let mut terminal = Terminal::new("104,111,104,107,104,10,3,100,4,100,99".parse().unwrap());
+
+        assert_eq!(
+            terminal.send(""),
+            Reply {
+                text: "ok\n".to_string(),
+                status: Status::Waiting
+            }
+        );
+        assert_eq!(
+            terminal.send("x"),
+            Reply {
+                text: "x".to_string(),
+                status: Status::Ended
+            }
+        );
+    }
+
+    #[test]
+    fn gives_up_on_programs_that_stop_asking_for_input() {
+        let mut terminal = Terminal::new("1105,1,0".parse().unwrap());
+
+        assert_eq!(terminal.send("").status, Status::Stuck);
+    }
+}
@@ -0,0 +1,516 @@
+use super::int_code::Program;
+use super::read_input;
+use std::fmt;
+use std::str::FromStr;
+
+const WALK_SCRIPT: &str = "\
+NOT A J
+NOT B T
+OR T J
+NOT C T
+OR T J
+AND D J
+WALK";
+
+// As above, but only jump if it's possible to either walk on from where the
+// droid lands or jump again straight away.
+const RUN_SCRIPT: &str = "\
+NOT A J
+NOT B T
+OR T J
+NOT C T
+OR T J
+AND D J
+NOT E T
+NOT T T
+OR H T
+AND T J
+RUN";
+
+pub fn main() {
+    let input = match read_input("src/twenty_one.txt") {
+        Some(input) => input,
+        None => return,
+    };
+
+    let droid = input.parse::<Program>().unwrap();
+
+    println!("Part one:");
+    match learn(&droid, Mode::Walk, 4) {
+        Some((script, outcome)) => println!("{}{}", script, outcome),
+        None => println!("{}", run_script(&droid, &WALK_SCRIPT.parse().unwrap())),
+    }
+
+    println!();
+    println!("Part two:");
+    println!("{}", run_script(&droid, &RUN_SCRIPT.parse().unwrap()));
+}
+
+const MAX_INSTRUCTIONS: usize = 15;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Op {
+    And,
+    Or,
+    Not,
+}
+
+// A to I are the ground sensors one to nine tiles ahead, T is scratch space
+// and J decides whether to jump. Only T and J can be written.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Register {
+    Sensor(usize),
+    T,
+    J,
+}
+
+impl Register {
+    fn is_writable(self) -> bool {
+        matches!(self, Register::T | Register::J)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    Walk,
+    Run,
+}
+
+impl Mode {
+    fn sensors(self) -> usize {
+        match self {
+            Mode::Walk => 4,
+            Mode::Run => 9,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Instruction {
+    op: Op,
+    x: Register,
+    y: Register,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Script {
+    instructions: Vec<Instruction>,
+    mode: Mode,
+}
+
+impl Script {
+    // Whether to jump, given what the sensors see.
+    fn evaluate(&self, sensors: &[bool]) -> bool {
+        let mut t = false;
+        let mut j = false;
+
+        for instruction in &self.instructions {
+            let x = match instruction.x {
+                Register::Sensor(i) => sensors[i],
+                Register::T => t,
+                Register::J => j,
+            };
+
+            let y = match instruction.y {
+                Register::T => &mut t,
+                Register::J => &mut j,
+                Register::Sensor(_) => unreachable!(),
+            };
+
+            *y = match instruction.op {
+                Op::And => x && *y,
+                Op::Or => x || *y,
+                Op::Not => !x,
+            };
+        }
+
+        j
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScriptError {
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl FromStr for Script {
+    type Err = ScriptError;
+
+    fn from_str(text: &str) -> Result<Script, ScriptError> {
+        let lines = text.trim().lines().map(str::trim).collect::<Vec<_>>();
+        let error = |line: usize, message: String| ScriptError { line, message };
+
+        let mode = match lines.last() {
+            Some(&"WALK") => Mode::Walk,
+            Some(&"RUN") => Mode::Run,
+            _ => return Err(error(lines.len(), "expected WALK or RUN".to_string())),
+        };
+
+        if lines.len() - 1 > MAX_INSTRUCTIONS {
+            return Err(error(
+                MAX_INSTRUCTIONS + 1,
+                format!("at most {} instructions fit in memory", MAX_INSTRUCTIONS),
+            ));
+        }
+
+        let mut instructions = Vec::new();
+
+        for (i, line) in lines[..lines.len() - 1].iter().enumerate() {
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            let (op, x, y) = match words[..] {
+                [op, x, y] => (op, x, y),
+                _ => return Err(error(i + 1, format!("expected `OP X Y`, got {:?}", line))),
+            };
+
+            let op = match op {
+                "AND" => Op::And,
+                "OR" => Op::Or,
+                "NOT" => Op::Not,
+                _ => return Err(error(i + 1, format!("unknown instruction {}", op))),
+            };
+
+            let register = |name: &str| match name {
+                "T" => Some(Register::T),
+                "J" => Some(Register::J),
+                _ => {
+                    let sensor = (name.len() == 1)
+                        .then(|| name.as_bytes()[0].wrapping_sub(b'A') as usize)
+                        .filter(|&sensor| sensor < mode.sensors())?;
+
+                    Some(Register::Sensor(sensor))
+                }
+            };
+
+            let x = register(x).ok_or_else(|| error(i + 1, format!("unknown register {}", x)))?;
+            let y = register(y)
+                .filter(|y| y.is_writable())
+                .ok_or_else(|| error(i + 1, format!("can't write to {}", y)))?;
+
+            instructions.push(Instruction { op, x, y });
+        }
+
+        Ok(Script { instructions, mode })
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Register::Sensor(i) => write!(f, "{}", (b'A' + *i as u8) as char),
+            Register::T => write!(f, "T"),
+            Register::J => write!(f, "J"),
+        }
+    }
+}
+
+// The springscript exactly as the droid reads it, one instruction a line.
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for instruction in &self.instructions {
+            let op = match instruction.op {
+                Op::And => "AND",
+                Op::Or => "OR",
+                Op::Not => "NOT",
+            };
+
+            writeln!(f, "{} {} {}", op, instruction.x, instruction.y)?;
+        }
+
+        match self.mode {
+            Mode::Walk => writeln!(f, "WALK"),
+            Mode::Run => writeln!(f, "RUN"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Damage(i64),
+    // The droid fell into space, with the animation it printed.
+    Fell(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Damage(damage) => write!(f, "Hull damage: {}", damage),
+            Outcome::Fell(animation) => write!(f, "{}", animation),
+        }
+    }
+}
+
+// Anything the droid prints outside the ASCII range is the damage report.
+fn run_script(droid: &Program, script: &Script) -> Outcome {
+    let input = script
+        .to_string()
+        .bytes()
+        .map(i64::from)
+        .collect::<Vec<_>>();
+    let output = droid.clone().run(input);
+
+    match output.last() {
+        Some(&damage) if damage > 127 => Outcome::Damage(damage),
+        _ => Outcome::Fell(output.iter().map(|&c| c as u8 as char).collect()),
+    }
+}
+
+// A stretch of hull, true for ground and false for a hole, as in `#.##`.
+type Hull = Vec<bool>;
+
+fn parse_hull(text: &str) -> Hull {
+    text.chars().map(|c| c == '#').collect()
+}
+
+// The last frame of the animation shows the hull the droid fell through.
+fn hull_from_animation(animation: &str) -> Option<Hull> {
+    animation
+        .lines()
+        .rev()
+        .find(|line| !line.is_empty() && line.chars().all(|c| c == '#' || c == '.'))
+        .map(parse_hull)
+}
+
+// The droid starts on the first tile, walks one tile at a time and lands
+// four tiles on when it jumps. It's safe once it passes the end, and the
+// sensors see ground beyond it.
+fn survives(script: &Script, hull: &[bool]) -> bool {
+    let mut position = 0;
+
+    while position < hull.len() {
+        if !hull[position] {
+            return false;
+        }
+
+        let sensors = (1..=script.mode.sensors())
+            .map(|ahead| hull.get(position + ahead).copied().unwrap_or(true))
+            .collect::<Vec<_>>();
+
+        position += if script.evaluate(&sensors) { 4 } else { 1 };
+    }
+
+    true
+}
+
+// Tries every script of up to `max_len` instructions, shortest first, and
+// returns the first that gets across all of the hulls.
+fn search(mode: Mode, hulls: &[Hull], max_len: usize) -> Option<Script> {
+    let registers = (0..mode.sensors())
+        .map(Register::Sensor)
+        .chain(vec![Register::T, Register::J])
+        .collect::<Vec<_>>();
+
+    let candidates = registers
+        .iter()
+        .flat_map(|&x| {
+            [Op::And, Op::Or, Op::Not].iter().flat_map(move |&op| {
+                [Register::T, Register::J]
+                    .iter()
+                    .map(move |&y| Instruction { op, x, y })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut script = Script {
+        instructions: Vec::new(),
+        mode,
+    };
+
+    (1..=max_len).find_map(|len| extend(&mut script, &candidates, len, hulls))
+}
+
+fn extend(
+    script: &mut Script,
+    candidates: &[Instruction],
+    len: usize,
+    hulls: &[Hull],
+) -> Option<Script> {
+    if script.instructions.len() == len {
+        return if hulls.iter().all(|hull| survives(script, hull)) {
+            Some(script.clone())
+        } else {
+            None
+        };
+    }
+
+    for &candidate in candidates {
+        script.instructions.push(candidate);
+
+        if let Some(found) = extend(script, candidates, len, hulls) {
+            return Some(found);
+        }
+
+        script.instructions.pop();
+    }
+
+    None
+}
+
+// Searches for a script that survives every hull seen so far, tries it on
+// the droid, and learns the hull from the animation whenever it falls.
+// Gives up if the search does, or if the droid falls somewhere the
+// simulator says the script is safe.
+fn learn(droid: &Program, mode: Mode, max_len: usize) -> Option<(Script, Outcome)> {
+    let mut hulls = Vec::new();
+
+    loop {
+        let script = search(mode, &hulls, max_len)?;
+
+        match run_script(droid, &script) {
+            Outcome::Fell(animation) => {
+                let hull = hull_from_animation(&animation)?;
+
+                if hulls.contains(&hull) {
+                    return None;
+                }
+
+                hulls.push(hull);
+            }
+            damage => return Some((script, damage)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn printing(text: &str, then: Option<i64>) -> Program {
+        let mut code = text
+            .bytes()
+            .flat_map(|c| vec![104, i64::from(c)])
+            .collect::<Vec<_>>();
+
+        if let Some(value) = then {
+            code.extend_from_slice(&[104, value]);
+        }
+
+        code.push(99);
+
+        Program::new(code)
+    }
+
+    #[test]
+    fn round_trips_a_script() {
+        let script = WALK_SCRIPT.parse::<Script>().unwrap();
+
+        assert_eq!(script.to_string(), format!("{}\n", WALK_SCRIPT));
+        assert_eq!(script.instructions.len(), 6);
+    }
+
+    #[test]
+    fn rejects_scripts_the_droid_would() {
+        let err = |text: &str| text.parse::<Script>().unwrap_err().to_string();
+
+        assert_eq!(err("NOT A B\nWALK"), "line 1: can't write to B");
+        assert_eq!(err("NOT E J\nWALK"), "line 1: unknown register E");
+        assert_eq!(err("XOR A J\nRUN"), "line 1: unknown instruction XOR");
+        assert_eq!(err("NOT A J"), "line 1: expected WALK or RUN");
+        assert!("NOT E J\nRUN".parse::<Script>().is_ok());
+    }
+
+    #[test]
+    fn sends_the_script_as_ascii() {
+        // Echoes the first character it reads.
+        let echo = "3,100,4,100,99".parse::<Program>().unwrap();
+
+        assert_eq!(
+            run_script(&echo, &WALK_SCRIPT.parse().unwrap()),
+            Outcome::Fell("N".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_damage_or_the_animation() {
+        let script = WALK_SCRIPT.parse().unwrap();
+
+        assert_eq!(
+            run_script(&printing("Walking...\n\n", Some(19352638)), &script),
+            Outcome::Damage(19352638)
+        );
+
+        let animation = "Didn't make it across:\n\n.................\n.................\n@................\n#####.#..########\n\n";
+        let outcome = run_script(&printing(animation, None), &script);
+
+        assert_eq!(outcome, Outcome::Fell(animation.to_string()));
+        assert_eq!(
+            hull_from_animation(animation),
+            Some(parse_hull("#####.#..########"))
+        );
+    }
+
+    #[test]
+    fn simulates_jumping_over_holes() {
+        let script = WALK_SCRIPT.parse::<Script>().unwrap();
+        let naive = "NOT A J\nWALK".parse::<Script>().unwrap();
+
+        for hull in &[
+            "#####.###########",
+            "#####..#.########",
+            "#####...#########",
+        ] {
+            assert!(survives(&script, &parse_hull(hull)), "{}", hull);
+        }
+
+        assert!(!survives(&naive, &parse_hull("#####..#.########")));
+        assert!(!survives(&script, &parse_hull("#####.#.##..#.###")));
+    }
+
+    #[test]
+    fn searches_for_a_script_that_survives_every_hull() {
+        let hulls = [
+            "#####.###########",
+            "#####..#.########",
+            "#####...#########",
+        ]
+        .iter()
+        .map(|hull| parse_hull(hull))
+        .collect::<Vec<_>>();
+
+        let found = search(Mode::Walk, &hulls, 4).unwrap();
+
+        assert!(found.instructions.len() > 1);
+        assert!(hulls.iter().all(|hull| survives(&found, hull)));
+    }
+
+    #[test]
+    fn learns_hulls_from_the_droid_until_it_gets_across() {
+        // Falls unless told to jump as its first instruction, then reports
+        // the damage.
+        let animation = "Didn't make it across:\n\n@................\n#####.###########\n";
+        let mut code = vec![3, 1000, 1008, 1000, 78, 1001, 1005, 1001, 0];
+        let falls = printing(animation, None).code.to_vec();
+        let fall_at = code.len() as i64 + 3;
+
+        code[8] = fall_at + falls.len() as i64;
+        code.extend_from_slice(&[1105, 1, fall_at]);
+        code.extend(falls);
+        code.extend_from_slice(&[104, 1234, 99]);
+
+        let (script, outcome) = learn(&Program::new(code), Mode::Walk, 2).unwrap();
+
+        assert_eq!(script.instructions[0].op, Op::Not);
+        assert_eq!(outcome, Outcome::Damage(1234));
+    }
+
+    #[test]
+    fn stops_when_the_droid_disagrees_with_the_simulator() {
+        let always_falls = printing("@................\n#####.###########\n", None);
+
+        assert_eq!(learn(&always_falls, Mode::Walk, 2), None);
+    }
+
+    #[test]
+    fn gives_up_when_no_script_can_survive() {
+        let hulls = vec![parse_hull("#....#")];
+
+        assert_eq!(search(Mode::Walk, &hulls, 2), None);
+    }
+}
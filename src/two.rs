@@ -2,8 +2,7 @@ use super::int_code::*;
 use std::fs::read_to_string;
 
 pub fn main() {
-    let contents = read_to_string("src/two.txt").unwrap();
-    let input = contents.trim();
+    let input = read_to_string("src/two.txt").unwrap();
 
     println!("Part one:");
     println!("Final value at position 0: {}", part_one(&input));
@@ -16,17 +15,17 @@ pub fn main() {
 }
 
 fn part_one(input: &str) -> i64 {
-    let mut program = input.parse::<Program>().unwrap();
+    let mut program = load(input);
 
     run_program_with_inputs(12, 2, &mut program);
 
-    program.code[0]
+    program.read_symbol("result").unwrap()
 }
 
 fn part_two(input: &str) -> (i64, i64) {
     let target = 19690720;
 
-    let initial_program = input.parse::<Program>().unwrap();
+    let initial_program = load(input);
 
     for i in 0..99 {
         for j in 0..99 {
@@ -34,7 +33,7 @@ fn part_two(input: &str) -> (i64, i64) {
 
             run_program_with_inputs(i, j, &mut program);
 
-            if program.code[0] == target {
+            if program.read_symbol("result").unwrap() == target {
                 return (i, j);
             }
         }
@@ -43,9 +42,15 @@ fn part_two(input: &str) -> (i64, i64) {
     panic!("Could not find a pair of inputs resulting in {}", target);
 }
 
-fn run_program_with_inputs(a: i64, b: i64, program: &mut Program) {
-    program.code[1] = a;
-    program.code[2] = b;
+fn load(input: &str) -> Program {
+    let mut program = input.parse::<Program>().unwrap();
+    program.set_symbols(Symbols::load_sidecar("src/two.txt").unwrap());
+    program
+}
+
+fn run_program_with_inputs(noun: i64, verb: i64, program: &mut Program) {
+    program.write_symbol("noun", noun).unwrap();
+    program.write_symbol("verb", verb).unwrap();
 
     program.run(vec![]);
 }
@@ -0,0 +1,103 @@
+use advent_of_code_2019::int_code::{CaseReport, Spec};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+const SPECS: &str = "src/int_code/specs";
+
+// Runs every case in every spec file as a test of its own, named
+// `file: program / case`. Like the built in harness, any arguments that
+// aren't flags are filters, and only cases whose name contains one run.
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let filters = args
+        .iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .collect::<Vec<_>>();
+    let list = args.iter().any(|arg| arg == "--list");
+
+    let mut paths = fs::read_dir(SPECS)
+        .unwrap_or_else(|err| panic!("{}: {}", SPECS, err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "spec"))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+
+    let specs = paths
+        .iter()
+        .map(|path| {
+            let spec = Spec::load(path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+
+            (
+                path.file_stem().unwrap().to_string_lossy().into_owned(),
+                spec,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut tests = specs
+        .iter()
+        .flat_map(|(file, spec)| {
+            spec.programs.iter().flat_map(move |program| {
+                program.cases.iter().map(move |case| {
+                    let name = format!("{}: {} / {}", file, program.name, case.name);
+
+                    (name, program, case)
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let total = tests.len();
+    tests.retain(|(name, ..)| filters.is_empty() || filters.iter().any(|f| name.contains(*f)));
+
+    if list {
+        for (name, ..) in &tests {
+            println!("{}: test", name);
+        }
+
+        return;
+    }
+
+    println!("\nrunning {} tests", tests.len());
+
+    let mut failures = Vec::new();
+
+    for (name, program, case) in &tests {
+        let report = CaseReport {
+            program: program.name.clone(),
+            case: case.name.clone(),
+            line: case.line,
+            result: case.run(&program.code),
+        };
+
+        match &report.result {
+            Ok(()) => println!("test {} ... ok", name),
+            Err(_) => {
+                println!("test {} ... FAILED", name);
+                failures.push((name, report));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:\n");
+
+        for (name, report) in &failures {
+            println!("---- {} ----\n{}\n", name, report);
+        }
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} filtered out\n",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        tests.len() - failures.len(),
+        failures.len(),
+        total - tests.len()
+    );
+
+    if !failures.is_empty() {
+        process::exit(1);
+    }
+}